@@ -1,3 +1,27 @@
+extern crate cc;
+
+use std::env;
+use std::process::Command;
+
+// llvm-sys found llvm-config already; its path comes through the links key.
+fn llvm_cxxflags() -> String {
+    let llvm_config = env::var("DEP_LLVM_10_CONFIG_PATH").unwrap_or_else(|_| "llvm-config".into());
+    let output = Command::new(&llvm_config)
+        .arg("--cxxflags")
+        .output()
+        .unwrap_or_else(|err| panic!("couldn't run {}: {}", llvm_config, err));
+    String::from_utf8(output.stdout).expect("llvm-config printed invalid UTF-8")
+}
+
 fn main() {
-    println!("cargo:rustc-link-lib=dylib={}", "ffi");
+    println!("cargo:rerun-if-changed=src/wrapper.cpp");
+
+    let mut build = cc::Build::new();
+    build.cpp(true).file("src/wrapper.cpp").warnings(false);
+    for flag in llvm_cxxflags().split_whitespace() {
+        build.flag(flag);
+    }
+    build.compile("llvm-wrapper");
+
+    println!("cargo:rustc-link-lib=dylib=ffi");
 }
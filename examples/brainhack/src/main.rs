@@ -12,18 +12,17 @@ use llvm_sys_wrapper::*;
 use std::io;
 use std::io::Read;
 
-struct Compiler {
-    ctx: Context,
-    builder: Builder,
-    module: Module,
-    buffer: LLVMValueRef,
-    ptr: LLVMValueRef,
-    main_func: Function,
+struct Compiler<'ctx> {
+    ctx: &'ctx Context,
+    builder: Builder<'ctx>,
+    module: Module<'ctx>,
+    buffer: PointerValue<'ctx>,
+    ptr: PointerValue<'ctx>,
+    main_func: Function<'ctx>,
 }
 
-impl Compiler {
-    fn new(module_name: &str) -> Compiler {
-        let context = Context::global_context();
+impl<'ctx> Compiler<'ctx> {
+    fn new(context: &'ctx Context, module_name: &str) -> Compiler<'ctx> {
         let builder = context.create_builder();
         let module = context.create_module(module_name);
 
@@ -33,20 +32,13 @@ impl Compiler {
         let entry_block = main_function.append_basic_block("entry");
         builder.position_at_end(entry_block);
 
-        // setup calloc function
-        let calloc_type = fn_type!(
-            context.Int8PointerType(),
-            context.Int64Type(),
-            context.Int64Type()
-        );
-        let calloc_func = module.get_or_add_function("calloc", calloc_type);
-
         // setup memory
         let data = builder.build_alloca(context.Int8PointerType());
         let ptr = builder.build_alloca(context.Int8PointerType());
 
-        let mut args = [context.UInt64(30000), context.UInt64(1)];
-        let data_ptr = builder.build_call(calloc_func.as_ref(), &mut args);
+        let size = context.UInt64(30000);
+        let data_ptr = builder.build_array_malloc(context.Int8Type(), size);
+        builder.build_memset(data_ptr, 1, context.UInt8(0), size, false);
 
         builder.build_store(data_ptr, data);
         builder.build_store(data_ptr, ptr);
@@ -62,31 +54,35 @@ impl Compiler {
     }
 
     fn end_emit(&self) {
-        // setup free function
-        let free_type = fn_type!(self.ctx.VoidType(), self.ctx.Int8PointerType());
-        let free_func = self.module.get_or_add_function("free", free_type);
-
         // free memory
-        let mut args = [self.builder.build_load(self.buffer)];
-        self.builder.build_call(free_func.as_ref(), &mut args);
+        let buffer = self
+            .builder
+            .build_load(self.buffer)
+            .into_pointer_value()
+            .unwrap();
+        self.builder.build_free(buffer);
 
         // return void
         let _ret = self.builder.build_ret_void();
     }
 
+    fn load_ptr(&self) -> PointerValue<'ctx> {
+        self.builder
+            .build_load(self.ptr)
+            .into_pointer_value()
+            .unwrap()
+    }
+
     fn emit_move_ptr(&self, diff: i64) {
-        let mut indices = [self.ctx.SInt32(diff as u64)];
-        let gep = self
-            .builder
-            .build_inbounds_gep(self.builder.build_load(self.ptr), &mut indices);
+        let indices = [self.ctx.SInt32(diff as u64)];
+        let gep = self.builder.build_inbounds_gep(self.load_ptr(), &indices);
         self.builder.build_store(gep, self.ptr);
     }
 
     fn emit_add(&self, diff: i64) {
-        let tmp = self.builder.build_load(self.ptr);
-        let add = self
-            .builder
-            .build_add(self.builder.build_load(tmp), self.ctx.SInt8(diff as u64));
+        let tmp = self.load_ptr();
+        let val = self.builder.build_load(tmp).into_int_value().unwrap();
+        let add = self.builder.build_add(val, self.ctx.SInt8(diff as u64));
         self.builder.build_store(add, tmp);
     }
 
@@ -95,10 +91,14 @@ impl Compiler {
         let putchar_type = fn_type!(self.ctx.Int32Type(), self.ctx.Int32Type());
         let putchar_func = self.module.get_or_add_function("putchar", putchar_type);
 
-        let val = self.builder.build_load(self.builder.build_load(self.ptr));
+        let val = self
+            .builder
+            .build_load(self.load_ptr())
+            .into_int_value()
+            .unwrap();
         let ext_val = self.builder.build_sext(val, self.ctx.Int32Type());
-        let mut args = [ext_val];
-        let _call = self.builder.build_call(putchar_func.as_ref(), &mut args);
+        let args = [ext_val.into()];
+        let _call = self.builder.build_call(putchar_func, &args);
     }
 
     fn emit_while_start(&self, chars: &mut std::str::Chars) {
@@ -109,7 +109,11 @@ impl Compiler {
         self.builder.build_br(cond_block);
         self.builder.position_at_end(cond_block);
 
-        let load = self.builder.build_load(self.builder.build_load(self.ptr));
+        let load = self
+            .builder
+            .build_load(self.load_ptr())
+            .into_int_value()
+            .unwrap();
         let cond = self.builder.build_icmp_ne(load, self.ctx.UInt8(0));
         self.builder.build_cond_br(cond, body_block, end_block);
         self.builder.position_at_end(body_block);
@@ -128,13 +132,13 @@ impl Compiler {
     }
 
     #[allow(dead_code)]
-    fn run(&self) {
+    fn run(self) {
         match self.module.verify() {
             Ok(_) => {
                 let interperter = self.module.create_interpreter().unwrap();
-                let named_function = self.module.named_function("main");
+                let named_function = interperter.named_function("main").unwrap();
                 let mut params = [];
-                let run_result = interperter.run_function(named_function.as_ref(), &mut params);
+                let run_result = interperter.run_function(named_function, &mut params);
                 let _ = run_result.to_int();
             }
             Err(msg) => panic!("Error: {}", msg),
@@ -160,10 +164,11 @@ impl Compiler {
 #[allow(unused_must_use)]
 fn main() {
     // initialize LLVM
-    LLVM::initialize();
+    LLVM::initialize().unwrap();
 
     // create compiler
-    let compiler = Compiler::new("brainhack");
+    let context = Context::global_context();
+    let compiler = Compiler::new(&context, "brainhack");
 
     // read input
     let mut buffer = String::new();
@@ -176,6 +181,9 @@ fn main() {
     // end compile
     compiler.end_emit();
 
+    // optimize: promote the allocas to registers and fold what we can
+    PassManager::o2().run(&compiler.module);
+
     // dump
     compiler.dump();
 }
@@ -0,0 +1,35 @@
+; ModuleID = 'fib_example'
+source_filename = "fib_example"
+
+@0 = private unnamed_addr constant [5 x i8] c"%lu\0A\00", align 1
+
+declare i32 @printf(i8*, ...)
+
+define i64 @fib(i64 %0) {
+entry:
+  %1 = icmp eq i64 %0, 0
+  br i1 %1, label %end, label %else0
+
+else0:                                            ; preds = %entry
+  %2 = icmp eq i64 %0, 1
+  br i1 %2, label %end, label %else1
+
+end:                                              ; preds = %else1, %else0, %entry
+  %3 = phi i64 [ 0, %entry ], [ 1, %else0 ], [ %8, %else1 ]
+  ret i64 %3
+
+else1:                                            ; preds = %else0
+  %4 = sub i64 %0, 2
+  %5 = tail call i64 @fib(i64 %4)
+  %6 = sub i64 %0, 1
+  %7 = tail call i64 @fib(i64 %6)
+  %8 = add i64 %5, %7
+  br label %end
+}
+
+define void @main() {
+entry:
+  %0 = call i64 @fib(i64 10)
+  %1 = call i32 (i8*, ...) @printf(i8* getelementptr inbounds ([5 x i8], [5 x i8]* @0, i32 0, i32 0), i64 %0)
+  ret void
+}
@@ -4,7 +4,7 @@ use llvm_sys_wrapper::*;
 
 fn main() {
     // initialize LLVM
-    LLVM::initialize();
+    LLVM::initialize().unwrap();
 
     // create context, builder and module
     let ctx = Context::global_context();
@@ -25,7 +25,7 @@ fn main() {
     let entry_block = fib_func.append_basic_block("entry");
     builder.position_at_end(entry_block);
 
-    let arg = fib_func.get_param(0);
+    let arg = fib_func.get_param(0).into_int_value().unwrap();
     let cond = builder.build_icmp_eq(arg, ctx.UInt64(0));
 
     let else0 = fib_func.append_basic_block("else0");
@@ -43,13 +43,14 @@ fn main() {
     // else1
     builder.position_at_end(else1);
     let sub2 = builder.build_sub(arg, ctx.UInt64(2));
-    let mut args = [sub2];
-    let fib_sub2 = builder.build_tail_call(fib_func.as_ref(), &mut args);
+    let fib_sub2 = builder.build_tail_call(fib_func, &[sub2.into()]);
     let sub1 = builder.build_sub(arg, ctx.UInt64(1));
-    let mut args = [sub1];
-    let fib_sub1 = builder.build_tail_call(fib_func.as_ref(), &mut args);
+    let fib_sub1 = builder.build_tail_call(fib_func, &[sub1.into()]);
 
-    let sum = builder.build_add(fib_sub2, fib_sub1);
+    let sum = builder.build_add(
+        fib_sub2.into_int_value().unwrap(),
+        fib_sub1.into_int_value().unwrap(),
+    );
     builder.build_br(end);
 
     // end
@@ -60,7 +61,7 @@ fn main() {
     phi.add_incoming(sum, else1);
 
     // return
-    builder.build_ret(phi.as_ref());
+    builder.build_ret(phi);
 
     //
     // declare main function
@@ -71,15 +72,13 @@ fn main() {
     builder.position_at_end(entry_block);
 
     // call fib(10)
-    let mut args = [ctx.UInt64(10)];
-    let ret = builder.build_call(fib_func.as_ref(), &mut args);
+    let ret = builder.build_call(fib_func, &[ctx.UInt64(10).into()]);
 
     // format string
     let fmt_d = builder.build_global_string_ptr("%lu\n");
 
     // call printf function
-    let mut args = [fmt_d, ret];
-    builder.build_call(printf_func.as_ref(), &mut args);
+    builder.build_call(printf_func, &[fmt_d.into(), ret]);
 
     // return void
     builder.build_ret_void();
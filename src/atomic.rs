@@ -0,0 +1,123 @@
+extern crate llvm_sys;
+
+use self::llvm_sys::prelude::LLVMBool;
+use self::llvm_sys::{LLVMAtomicOrdering, LLVMAtomicRMWBinOp};
+
+// How an atomic operation orders the memory accesses around it, weakest first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AtomicOrdering {
+    NotAtomic,
+    Unordered,
+    Monotonic,
+    Acquire,
+    Release,
+    AcquireRelease,
+    SequentiallyConsistent,
+}
+
+impl From<AtomicOrdering> for LLVMAtomicOrdering {
+    fn from(ordering: AtomicOrdering) -> LLVMAtomicOrdering {
+        match ordering {
+            AtomicOrdering::NotAtomic => LLVMAtomicOrdering::LLVMAtomicOrderingNotAtomic,
+            AtomicOrdering::Unordered => LLVMAtomicOrdering::LLVMAtomicOrderingUnordered,
+            AtomicOrdering::Monotonic => LLVMAtomicOrdering::LLVMAtomicOrderingMonotonic,
+            AtomicOrdering::Acquire => LLVMAtomicOrdering::LLVMAtomicOrderingAcquire,
+            AtomicOrdering::Release => LLVMAtomicOrdering::LLVMAtomicOrderingRelease,
+            AtomicOrdering::AcquireRelease => LLVMAtomicOrdering::LLVMAtomicOrderingAcquireRelease,
+            AtomicOrdering::SequentiallyConsistent => {
+                LLVMAtomicOrdering::LLVMAtomicOrderingSequentiallyConsistent
+            }
+        }
+    }
+}
+
+impl From<LLVMAtomicOrdering> for AtomicOrdering {
+    fn from(ordering: LLVMAtomicOrdering) -> AtomicOrdering {
+        match ordering {
+            LLVMAtomicOrdering::LLVMAtomicOrderingNotAtomic => AtomicOrdering::NotAtomic,
+            LLVMAtomicOrdering::LLVMAtomicOrderingUnordered => AtomicOrdering::Unordered,
+            LLVMAtomicOrdering::LLVMAtomicOrderingMonotonic => AtomicOrdering::Monotonic,
+            LLVMAtomicOrdering::LLVMAtomicOrderingAcquire => AtomicOrdering::Acquire,
+            LLVMAtomicOrdering::LLVMAtomicOrderingRelease => AtomicOrdering::Release,
+            LLVMAtomicOrdering::LLVMAtomicOrderingAcquireRelease => AtomicOrdering::AcquireRelease,
+            LLVMAtomicOrdering::LLVMAtomicOrderingSequentiallyConsistent => {
+                AtomicOrdering::SequentiallyConsistent
+            }
+        }
+    }
+}
+
+// What an atomicrmw does with the value in memory and its operand. Max and Min
+// compare as signed, UMax and UMin as unsigned; FAdd and FSub take floats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AtomicRMWBinOp {
+    Xchg,
+    Add,
+    Sub,
+    And,
+    Nand,
+    Or,
+    Xor,
+    Max,
+    Min,
+    UMax,
+    UMin,
+    FAdd,
+    FSub,
+}
+
+impl From<AtomicRMWBinOp> for LLVMAtomicRMWBinOp {
+    fn from(op: AtomicRMWBinOp) -> LLVMAtomicRMWBinOp {
+        match op {
+            AtomicRMWBinOp::Xchg => LLVMAtomicRMWBinOp::LLVMAtomicRMWBinOpXchg,
+            AtomicRMWBinOp::Add => LLVMAtomicRMWBinOp::LLVMAtomicRMWBinOpAdd,
+            AtomicRMWBinOp::Sub => LLVMAtomicRMWBinOp::LLVMAtomicRMWBinOpSub,
+            AtomicRMWBinOp::And => LLVMAtomicRMWBinOp::LLVMAtomicRMWBinOpAnd,
+            AtomicRMWBinOp::Nand => LLVMAtomicRMWBinOp::LLVMAtomicRMWBinOpNand,
+            AtomicRMWBinOp::Or => LLVMAtomicRMWBinOp::LLVMAtomicRMWBinOpOr,
+            AtomicRMWBinOp::Xor => LLVMAtomicRMWBinOp::LLVMAtomicRMWBinOpXor,
+            AtomicRMWBinOp::Max => LLVMAtomicRMWBinOp::LLVMAtomicRMWBinOpMax,
+            AtomicRMWBinOp::Min => LLVMAtomicRMWBinOp::LLVMAtomicRMWBinOpMin,
+            AtomicRMWBinOp::UMax => LLVMAtomicRMWBinOp::LLVMAtomicRMWBinOpUMax,
+            AtomicRMWBinOp::UMin => LLVMAtomicRMWBinOp::LLVMAtomicRMWBinOpUMin,
+            AtomicRMWBinOp::FAdd => LLVMAtomicRMWBinOp::LLVMAtomicRMWBinOpFAdd,
+            AtomicRMWBinOp::FSub => LLVMAtomicRMWBinOp::LLVMAtomicRMWBinOpFSub,
+        }
+    }
+}
+
+impl From<LLVMAtomicRMWBinOp> for AtomicRMWBinOp {
+    fn from(op: LLVMAtomicRMWBinOp) -> AtomicRMWBinOp {
+        match op {
+            LLVMAtomicRMWBinOp::LLVMAtomicRMWBinOpXchg => AtomicRMWBinOp::Xchg,
+            LLVMAtomicRMWBinOp::LLVMAtomicRMWBinOpAdd => AtomicRMWBinOp::Add,
+            LLVMAtomicRMWBinOp::LLVMAtomicRMWBinOpSub => AtomicRMWBinOp::Sub,
+            LLVMAtomicRMWBinOp::LLVMAtomicRMWBinOpAnd => AtomicRMWBinOp::And,
+            LLVMAtomicRMWBinOp::LLVMAtomicRMWBinOpNand => AtomicRMWBinOp::Nand,
+            LLVMAtomicRMWBinOp::LLVMAtomicRMWBinOpOr => AtomicRMWBinOp::Or,
+            LLVMAtomicRMWBinOp::LLVMAtomicRMWBinOpXor => AtomicRMWBinOp::Xor,
+            LLVMAtomicRMWBinOp::LLVMAtomicRMWBinOpMax => AtomicRMWBinOp::Max,
+            LLVMAtomicRMWBinOp::LLVMAtomicRMWBinOpMin => AtomicRMWBinOp::Min,
+            LLVMAtomicRMWBinOp::LLVMAtomicRMWBinOpUMax => AtomicRMWBinOp::UMax,
+            LLVMAtomicRMWBinOp::LLVMAtomicRMWBinOpUMin => AtomicRMWBinOp::UMin,
+            LLVMAtomicRMWBinOp::LLVMAtomicRMWBinOpFAdd => AtomicRMWBinOp::FAdd,
+            LLVMAtomicRMWBinOp::LLVMAtomicRMWBinOpFSub => AtomicRMWBinOp::FSub,
+        }
+    }
+}
+
+// Which threads an atomic operation synchronizes with. SingleThread only
+// orders against signal handlers running on the same thread.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SyncScope {
+    SingleThread,
+    System,
+}
+
+impl SyncScope {
+    // The C API's singleThread flag.
+    #[inline]
+    pub(crate) fn single_thread(self) -> LLVMBool {
+        (self == SyncScope::SingleThread) as LLVMBool
+    }
+}
@@ -0,0 +1,140 @@
+extern crate llvm_sys;
+
+use self::llvm_sys::core::*;
+use self::llvm_sys::prelude::*;
+use self::llvm_sys::{LLVMAttributeFunctionIndex, LLVMAttributeIndex, LLVMAttributeReturnIndex};
+use std::os::raw::{c_char, c_uint};
+use types::Type;
+
+// Built from src/wrapper.cpp; LLVMCreateTypeAttribute only exists from LLVM 12
+// on, so the shim picks what the LLVM it is compiled against can do.
+extern "C" {
+    fn LLVMWrapperCreateTypeAttribute(
+        context: LLVMContextRef,
+        kind_id: c_uint,
+        type_ref: LLVMTypeRef,
+    ) -> LLVMAttributeRef;
+}
+
+// Attributes that can be put on functions, return values, params and call sites.
+// `StructRet` and `ByVal` carry the pointee type, printed as `sret(%T)`; LLVM
+// before 12 keeps it for `byval` only.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Attribute<'ctx> {
+    AlwaysInline,
+    NoInline,
+    NoUnwind,
+    NoReturn,
+    Cold,
+    ReadOnly,
+    ReadNone,
+    NoAlias,
+    NoCapture,
+    NonNull,
+    ZExt,
+    SExt,
+    Dereferenceable(u64),
+    StructRet(Type<'ctx>),
+    ByVal(Type<'ctx>),
+}
+
+impl<'ctx> Attribute<'ctx> {
+    // The attribute's name in textual IR.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Attribute::AlwaysInline => "alwaysinline",
+            Attribute::NoInline => "noinline",
+            Attribute::NoUnwind => "nounwind",
+            Attribute::NoReturn => "noreturn",
+            Attribute::Cold => "cold",
+            Attribute::ReadOnly => "readonly",
+            Attribute::ReadNone => "readnone",
+            Attribute::NoAlias => "noalias",
+            Attribute::NoCapture => "nocapture",
+            Attribute::NonNull => "nonnull",
+            Attribute::ZExt => "zeroext",
+            Attribute::SExt => "signext",
+            Attribute::Dereferenceable(_) => "dereferenceable",
+            Attribute::StructRet(_) => "sret",
+            Attribute::ByVal(_) => "byval",
+        }
+    }
+
+    #[inline]
+    fn value(&self) -> u64 {
+        match *self {
+            Attribute::Dereferenceable(bytes) => bytes,
+            _ => 0,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn kind_id(&self) -> u32 {
+        let name = self.name();
+        unsafe { LLVMGetEnumAttributeKindForName(name.as_ptr() as *const c_char, name.len()) }
+    }
+
+    #[inline]
+    pub(crate) fn create(&self, context: LLVMContextRef) -> LLVMAttributeRef {
+        match *self {
+            Attribute::StructRet(typ) | Attribute::ByVal(typ) => unsafe {
+                LLVMWrapperCreateTypeAttribute(context, self.kind_id(), typ.as_ref())
+            },
+            _ => unsafe { LLVMCreateEnumAttribute(context, self.kind_id(), self.value()) },
+        }
+    }
+}
+
+// Where an attribute goes: on the function itself, its return value or one of its params.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AttributeLoc {
+    Function,
+    Return,
+    Param(u32),
+}
+
+impl From<AttributeLoc> for LLVMAttributeIndex {
+    fn from(loc: AttributeLoc) -> LLVMAttributeIndex {
+        match loc {
+            AttributeLoc::Function => LLVMAttributeFunctionIndex,
+            AttributeLoc::Return => LLVMAttributeReturnIndex,
+            AttributeLoc::Param(index) => index + 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CallConv {
+    C,
+    Fast,
+    Cold,
+    X86Stdcall,
+    X86Fastcall,
+    Other(u32),
+}
+
+impl From<CallConv> for u32 {
+    fn from(call_conv: CallConv) -> u32 {
+        match call_conv {
+            CallConv::C => 0,
+            CallConv::Fast => 8,
+            CallConv::Cold => 9,
+            CallConv::X86Stdcall => 64,
+            CallConv::X86Fastcall => 65,
+            CallConv::Other(id) => id,
+        }
+    }
+}
+
+impl From<u32> for CallConv {
+    fn from(id: u32) -> CallConv {
+        match id {
+            0 => CallConv::C,
+            8 => CallConv::Fast,
+            9 => CallConv::Cold,
+            64 => CallConv::X86Stdcall,
+            65 => CallConv::X86Fastcall,
+            id => CallConv::Other(id),
+        }
+    }
+}
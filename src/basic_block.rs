@@ -0,0 +1,255 @@
+extern crate llvm_sys;
+
+use self::llvm_sys::core::*;
+use self::llvm_sys::prelude::*;
+use self::llvm_sys::LLVMOpcode;
+use context::ContextMarker;
+use fast_math;
+use function::Function;
+use instruction::{Instruction, InstructionIter};
+use small_cstr::SmallCStr;
+use std::ffi::CStr;
+use std::marker::PhantomData;
+use values::{AnyValue, Value};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BasicBlock<'ctx> {
+    llvm_basic_block: LLVMBasicBlockRef,
+    _context: ContextMarker<'ctx>,
+}
+
+impl<'ctx> BasicBlock<'ctx> {
+    pub fn from_ptr(basic_block_ptr: LLVMBasicBlockRef) -> BasicBlock<'ctx> {
+        BasicBlock {
+            llvm_basic_block: basic_block_ptr,
+            _context: PhantomData,
+        }
+    }
+
+    // For values that stand for a block, such as the operands of a branch.
+    pub fn from_value(value: Value<'ctx>) -> Option<BasicBlock<'ctx>> {
+        unsafe {
+            if LLVMValueIsBasicBlock(value.as_ref()) == 0 {
+                None
+            } else {
+                Some(BasicBlock::from_ptr(LLVMValueAsBasicBlock(value.as_ref())))
+            }
+        }
+    }
+
+    pub fn as_ref(&self) -> LLVMBasicBlockRef {
+        self.llvm_basic_block
+    }
+
+    #[inline]
+    pub fn as_value(&self) -> Value<'ctx> {
+        Value::from_ptr(unsafe { LLVMBasicBlockAsValue(self.llvm_basic_block) })
+    }
+
+    pub fn get_name(&self) -> String {
+        unsafe {
+            CStr::from_ptr(LLVMGetBasicBlockName(self.llvm_basic_block))
+                .to_string_lossy()
+                .into_owned()
+        }
+    }
+
+    #[inline]
+    pub fn set_name(&self, name: &str) {
+        self.as_value().set_name(name)
+    }
+
+    // None for a block that is not part of a function.
+    #[inline]
+    pub fn get_parent(&self) -> Option<Function<'ctx>> {
+        let function = unsafe { LLVMGetBasicBlockParent(self.llvm_basic_block) };
+        if function.is_null() {
+            None
+        } else {
+            Some(Function::from_ptr(function))
+        }
+    }
+
+    // None while the block is still being built.
+    #[inline]
+    pub fn get_terminator(&self) -> Option<Instruction<'ctx>> {
+        Instruction::from_nullable(unsafe { LLVMGetBasicBlockTerminator(self.llvm_basic_block) })
+    }
+
+    #[inline]
+    pub fn get_first_instruction(&self) -> Option<Instruction<'ctx>> {
+        Instruction::from_nullable(unsafe { LLVMGetFirstInstruction(self.llvm_basic_block) })
+    }
+
+    #[inline]
+    pub fn get_last_instruction(&self) -> Option<Instruction<'ctx>> {
+        Instruction::from_nullable(unsafe { LLVMGetLastInstruction(self.llvm_basic_block) })
+    }
+
+    #[inline]
+    pub fn get_instructions(&self) -> InstructionIter<'ctx> {
+        InstructionIter::new(self.get_first_instruction())
+    }
+
+    #[inline]
+    pub fn count_instructions(&self) -> usize {
+        self.get_instructions().count()
+    }
+
+    #[inline]
+    pub fn get_next_basic_block(&self) -> Option<BasicBlock<'ctx>> {
+        BasicBlock::from_nullable(unsafe { LLVMGetNextBasicBlock(self.llvm_basic_block) })
+    }
+
+    #[inline]
+    pub fn get_previous_basic_block(&self) -> Option<BasicBlock<'ctx>> {
+        BasicBlock::from_nullable(unsafe { LLVMGetPreviousBasicBlock(self.llvm_basic_block) })
+    }
+
+    // Only changes the layout of the function, not its control flow.
+    #[inline]
+    pub fn move_before(&self, other: BasicBlock<'ctx>) {
+        unsafe { LLVMMoveBasicBlockBefore(self.llvm_basic_block, other.llvm_basic_block) }
+    }
+
+    #[inline]
+    pub fn move_after(&self, other: BasicBlock<'ctx>) {
+        unsafe { LLVMMoveBasicBlockAfter(self.llvm_basic_block, other.llvm_basic_block) }
+    }
+
+    /// Removes the block and its instructions from the function and frees them.
+    ///
+    /// # Safety
+    ///
+    /// Nothing may branch to the block any more, nothing outside it may use
+    /// its instructions, and no copy of this handle, or of a handle to one of
+    /// its instructions, may be used again.
+    #[inline]
+    pub unsafe fn delete(self) {
+        LLVMDeleteBasicBlock(self.llvm_basic_block)
+    }
+
+    // Moves `instruction` and everything after it into a new block placed right
+    // after this one, and ends this block with a branch to it. Returns None when
+    // `instruction` is not in this block, the block has no parent, or
+    // `instruction` is a PHI or EH pad, which have to stay at the block's top.
+    //
+    // PHI nodes in the successors that came from this block are replaced by
+    // new ones coming from the new block, and the old ones are freed: handles
+    // to them dangle afterwards. Each replacement is returned as the old PHI's
+    // raw pointer, to compare with `as_ref()` of a held handle, and the new PHI.
+    pub fn split_at(
+        &self,
+        instruction: Instruction<'ctx>,
+        name: &str,
+    ) -> Option<(BasicBlock<'ctx>, Vec<(LLVMValueRef, Instruction<'ctx>)>)> {
+        let function = self.get_parent()?;
+        if instruction.get_parent() != Some(*self) {
+            return None;
+        }
+        match instruction.get_opcode() {
+            LLVMOpcode::LLVMPHI
+            | LLVMOpcode::LLVMLandingPad
+            | LLVMOpcode::LLVMCleanupPad
+            | LLVMOpcode::LLVMCatchPad
+            | LLVMOpcode::LLVMCatchSwitch => return None,
+            _ => {}
+        }
+
+        let name = SmallCStr::new(name);
+        unsafe {
+            let context = LLVMGetTypeContext(LLVMTypeOf(function.as_ref()));
+            let new_block = BasicBlock::from_ptr(match self.get_next_basic_block() {
+                Some(next) => LLVMInsertBasicBlockInContext(context, next.as_ref(), name.as_ptr()),
+                None => LLVMAppendBasicBlockInContext(context, function.as_ref(), name.as_ptr()),
+            });
+
+            let builder = LLVMCreateBuilderInContext(context);
+            LLVMPositionBuilderAtEnd(builder, new_block.as_ref());
+            let moving: Vec<Instruction> = InstructionIter::new(Some(instruction)).collect();
+            for moved in moving {
+                // re-inserting through a builder would otherwise drop the name
+                let mut len = 0;
+                let name_ptr = LLVMGetValueName2(moved.as_ref(), &mut len);
+                let name = CStr::from_ptr(name_ptr).to_owned();
+                LLVMInstructionRemoveFromParent(moved.as_ref());
+                LLVMInsertIntoBuilderWithName(builder, moved.as_ref(), name.as_ptr());
+            }
+            LLVMPositionBuilderAtEnd(builder, self.llvm_basic_block);
+            LLVMBuildBr(builder, new_block.as_ref());
+
+            let mut replaced = Vec::new();
+            if let Some(terminator) = new_block.get_terminator() {
+                // a switch can name the same block more than once
+                let mut successors = Vec::new();
+                for index in 0..LLVMGetNumSuccessors(terminator.as_ref()) {
+                    let successor =
+                        BasicBlock::from_ptr(LLVMGetSuccessor(terminator.as_ref(), index));
+                    if !successors.contains(&successor) {
+                        successors.push(successor);
+                    }
+                }
+                for successor in successors {
+                    replaced.extend(successor.retarget_phis(builder, *self, new_block));
+                }
+            }
+            LLVMDisposeBuilder(builder);
+            Some((new_block, replaced))
+        }
+    }
+
+    // The C API cannot change the block a PHI value comes from, so each PHI
+    // naming `from` is rebuilt with `to` in its place, keeping its fast-math flags.
+    // Returns the erased PHIs paired with their replacements.
+    unsafe fn retarget_phis(
+        &self,
+        builder: LLVMBuilderRef,
+        from: BasicBlock,
+        to: BasicBlock,
+    ) -> Vec<(LLVMValueRef, Instruction<'ctx>)> {
+        let mut replaced = Vec::new();
+        let phis: Vec<Instruction> = self
+            .get_instructions()
+            .take_while(|instruction| instruction.get_opcode() == LLVMOpcode::LLVMPHI)
+            .collect();
+        for phi in phis {
+            let count = LLVMCountIncoming(phi.as_ref());
+            let mut values = Vec::with_capacity(count as usize);
+            let mut blocks = Vec::with_capacity(count as usize);
+            for index in 0..count {
+                values.push(LLVMGetIncomingValue(phi.as_ref(), index));
+                let block = LLVMGetIncomingBlock(phi.as_ref(), index);
+                blocks.push(if block == from.as_ref() {
+                    to.as_ref()
+                } else {
+                    block
+                });
+            }
+            let retargeted =
+                (0..count).any(|index| LLVMGetIncomingBlock(phi.as_ref(), index) == from.as_ref());
+            if !retargeted {
+                continue;
+            }
+
+            LLVMPositionBuilderBefore(builder, phi.as_ref());
+            let no_name = SmallCStr::new("");
+            let new_phi = LLVMBuildPhi(builder, LLVMTypeOf(phi.as_ref()), no_name.as_ptr());
+            LLVMAddIncoming(new_phi, values.as_mut_ptr(), blocks.as_mut_ptr(), count);
+            fast_math::set(new_phi, fast_math::get(phi.as_ref()));
+            let name = phi.get_name();
+            phi.replace_all_uses_with(Value::from_ptr(new_phi));
+            phi.erase_from_parent();
+            Value::from_ptr(new_phi).set_name(&name);
+            replaced.push((phi.as_ref(), Instruction::from_ptr(new_phi)));
+        }
+        replaced
+    }
+
+    pub(crate) fn from_nullable(basic_block: LLVMBasicBlockRef) -> Option<BasicBlock<'ctx>> {
+        if basic_block.is_null() {
+            None
+        } else {
+            Some(BasicBlock::from_ptr(basic_block))
+        }
+    }
+}
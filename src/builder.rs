@@ -4,28 +4,65 @@ extern crate llvm_sys;
 
 use self::llvm_sys::core::*;
 use self::llvm_sys::prelude::*;
+use self::llvm_sys::target::{LLVMGetModuleDataLayout, LLVMStoreSizeOfType};
 use self::llvm_sys::LLVMIntPredicate::*;
 use self::llvm_sys::LLVMRealPredicate::*;
-use cstring_manager::CStringManager;
+use atomic::{AtomicOrdering, AtomicRMWBinOp, SyncScope};
+use basic_block::BasicBlock;
+use context::{Context, ContextMarker};
+use fast_math::{self, FastMathFlags};
+use function::Function;
+use instruction::Instruction;
 use phi::Phi;
+use reduction::VectorReduction;
+use small_cstr::SmallCStr;
+use std::cell::Cell;
+use std::marker::PhantomData;
+use std::ops::Deref;
+use std::ptr;
+use types::{AnyType, FloatType, FunctionType, IntType, PointerType};
+use values::{
+    value_refs, AnyValue, FloatMathValue, FloatValue, IntCompareValue, IntMathValue, IntValue,
+    PointerValue, Value, VectorValue,
+};
+
+// Where the builder inserts. The C API can hand back the block but not the
+// spot within it, so the builder keeps track of it itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum InsertPoint<'ctx> {
+    Unset,
+    AtEnd(BasicBlock<'ctx>),
+    Before(Instruction<'ctx>),
+}
 
 #[derive(Debug)]
-pub struct Builder {
+pub struct Builder<'ctx> {
     llvm_builder: LLVMBuilderRef,
+    insert_point: Cell<InsertPoint<'ctx>>,
+    fast_math: Cell<FastMathFlags>,
+    _context: ContextMarker<'ctx>,
 }
 
-impl Builder {
-    pub fn new() -> Builder {
+impl Builder<'static> {
+    pub fn new() -> Builder<'static> {
         let builder = unsafe { LLVMCreateBuilder() };
         Builder {
             llvm_builder: builder,
+            insert_point: Cell::new(InsertPoint::Unset),
+            fast_math: Cell::new(FastMathFlags::NONE),
+            _context: PhantomData,
         }
     }
+}
 
-    pub fn new_in_context(context: LLVMContextRef) -> Builder {
-        let builder = unsafe { LLVMCreateBuilderInContext(context) };
+impl<'ctx> Builder<'ctx> {
+    pub fn new_in_context(context: &'ctx Context) -> Builder<'ctx> {
+        let builder = unsafe { LLVMCreateBuilderInContext(context.as_ref()) };
         Builder {
             llvm_builder: builder,
+            insert_point: Cell::new(InsertPoint::Unset),
+            fast_math: Cell::new(FastMathFlags::NONE),
+            _context: PhantomData,
         }
     }
 
@@ -34,1264 +71,2780 @@ impl Builder {
     }
 
     #[inline]
-    pub fn position_at_end(&self, entry_block: LLVMBasicBlockRef) {
+    pub fn position_at_end(&self, entry_block: BasicBlock<'ctx>) {
+        unsafe {
+            LLVMPositionBuilderAtEnd(self.llvm_builder, entry_block.as_ref());
+        }
+        self.insert_point.set(InsertPoint::AtEnd(entry_block));
+    }
+
+    // New instructions go right before `instruction`, in order.
+    #[inline]
+    pub fn position_before(&self, instruction: Instruction<'ctx>) {
         unsafe {
-            LLVMPositionBuilderAtEnd(self.llvm_builder, entry_block);
+            LLVMPositionBuilderBefore(self.llvm_builder, instruction.as_ref());
+        }
+        self.insert_point.set(InsertPoint::Before(instruction));
+    }
+
+    // Ahead of everything already in the block, such as when hoisting allocas
+    // into the entry block.
+    #[inline]
+    pub fn position_at_start(&self, block: BasicBlock<'ctx>) {
+        match block.get_first_instruction() {
+            Some(first) => self.position_before(first),
+            None => self.position_at_end(block),
+        }
+    }
+
+    // None when the builder has not been positioned or was cleared.
+    #[inline]
+    pub fn get_insert_block(&self) -> Option<BasicBlock<'ctx>> {
+        BasicBlock::from_nullable(unsafe { LLVMGetInsertBlock(self.llvm_builder) })
+    }
+
+    // Nothing can be built until the builder is positioned again.
+    #[inline]
+    pub fn clear_insertion_position(&self) {
+        unsafe { LLVMClearInsertionPosition(self.llvm_builder) }
+        self.insert_point.set(InsertPoint::Unset);
+    }
+
+    // Remembers where the builder is inserting and goes back there when the
+    // guard is dropped. The guard derefs to the builder, so code can be emitted
+    // elsewhere through it in the meantime. The instruction or block the
+    // builder was positioned at must still exist by then.
+    #[inline]
+    pub fn save_insert_point(&self) -> InsertPointGuard<'_, 'ctx> {
+        InsertPointGuard {
+            builder: self,
+            insert_point: self.insert_point.get(),
         }
     }
 
+    // Fast-math flags for every float operation, compare, select, phi and
+    // call of float type built from now on. NONE, the default, turns them off.
+    #[inline]
+    pub fn set_fast_math_flags(&self, flags: FastMathFlags) {
+        self.fast_math.set(flags);
+    }
+
     #[inline]
-    pub fn build_alloca(&self, typ: LLVMTypeRef) -> LLVMValueRef {
+    pub fn get_fast_math_flags(&self) -> FastMathFlags {
+        self.fast_math.get()
+    }
+
+    // Leaves constants and instructions that cannot carry the flags alone.
+    fn with_fast_math(&self, value: LLVMValueRef) -> LLVMValueRef {
+        let flags = self.fast_math.get();
+        if !flags.is_empty() {
+            fast_math::set(value, flags);
+        }
+        value
+    }
+
+    // The module of the function being built into.
+    unsafe fn insert_module(&self) -> LLVMModuleRef {
+        let function = LLVMGetBasicBlockParent(LLVMGetInsertBlock(self.llvm_builder));
+        LLVMGetGlobalParent(function)
+    }
+
+    fn restore_insert_point(&self, insert_point: InsertPoint<'ctx>) {
+        match insert_point {
+            InsertPoint::Unset => self.clear_insertion_position(),
+            InsertPoint::AtEnd(block) => self.position_at_end(block),
+            InsertPoint::Before(instruction) => self.position_before(instruction),
+        }
+    }
+
+    #[inline]
+    pub fn build_alloca<T: AnyType<'ctx>>(&self, typ: T) -> PointerValue<'ctx> {
         self.build_alloca_with_name(typ, "")
     }
 
     #[inline]
-    pub fn build_alloca_with_name(&self, typ: LLVMTypeRef, name: &str) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildAlloca(self.llvm_builder, typ, val_name_ptr) }
+    pub fn build_alloca_with_name<T: AnyType<'ctx>>(
+        &self,
+        typ: T,
+        name: &str,
+    ) -> PointerValue<'ctx> {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            PointerValue::from_value_ref(LLVMBuildAlloca(
+                self.llvm_builder,
+                typ.as_type_ref(),
+                val_name.as_ptr(),
+            ))
+        }
     }
 
     #[inline]
-    pub fn build_array_alloca(&self, typ: LLVMTypeRef, size: LLVMValueRef) -> LLVMValueRef {
+    pub fn build_array_alloca<T: AnyType<'ctx>>(
+        &self,
+        typ: T,
+        size: IntValue<'ctx>,
+    ) -> PointerValue<'ctx> {
         self.build_array_alloca_with_name(typ, size, "")
     }
 
     #[inline]
-    pub fn build_array_alloca_with_name(
+    pub fn build_array_alloca_with_name<T: AnyType<'ctx>>(
         &self,
-        typ: LLVMTypeRef,
-        size: LLVMValueRef,
+        typ: T,
+        size: IntValue<'ctx>,
         name: &str,
-    ) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildArrayAlloca(self.llvm_builder, typ, size, val_name_ptr) }
+    ) -> PointerValue<'ctx> {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            PointerValue::from_value_ref(LLVMBuildArrayAlloca(
+                self.llvm_builder,
+                typ.as_type_ref(),
+                size.as_value_ref(),
+                val_name.as_ptr(),
+            ))
+        }
     }
 
     #[inline]
-    pub fn build_malloc(&self, typ: LLVMTypeRef) -> LLVMValueRef {
+    pub fn build_malloc<T: AnyType<'ctx>>(&self, typ: T) -> PointerValue<'ctx> {
         self.build_malloc_with_name(typ, "")
     }
 
     #[inline]
-    pub fn build_malloc_with_name(&self, typ: LLVMTypeRef, name: &str) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildMalloc(self.llvm_builder, typ, val_name_ptr) }
+    pub fn build_malloc_with_name<T: AnyType<'ctx>>(
+        &self,
+        typ: T,
+        name: &str,
+    ) -> PointerValue<'ctx> {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            PointerValue::from_value_ref(LLVMBuildMalloc(
+                self.llvm_builder,
+                typ.as_type_ref(),
+                val_name.as_ptr(),
+            ))
+        }
     }
 
     #[inline]
-    pub fn build_array_malloc(&self, typ: LLVMTypeRef, size: LLVMValueRef) -> LLVMValueRef {
+    pub fn build_array_malloc<T: AnyType<'ctx>>(
+        &self,
+        typ: T,
+        size: IntValue<'ctx>,
+    ) -> PointerValue<'ctx> {
         self.build_array_malloc_with_name(typ, size, "")
     }
 
     #[inline]
-    pub fn build_array_malloc_with_name(
+    pub fn build_array_malloc_with_name<T: AnyType<'ctx>>(
         &self,
-        typ: LLVMTypeRef,
-        size: LLVMValueRef,
+        typ: T,
+        size: IntValue<'ctx>,
         name: &str,
-    ) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildArrayMalloc(self.llvm_builder, typ, size, val_name_ptr) }
+    ) -> PointerValue<'ctx> {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            PointerValue::from_value_ref(LLVMBuildArrayMalloc(
+                self.llvm_builder,
+                typ.as_type_ref(),
+                size.as_value_ref(),
+                val_name.as_ptr(),
+            ))
+        }
     }
 
     #[inline]
-    pub fn build_free(&self, pointer: LLVMValueRef) -> LLVMValueRef {
-        unsafe { LLVMBuildFree(self.llvm_builder, pointer) }
+    pub fn build_free(&self, pointer: PointerValue<'ctx>) -> Value<'ctx> {
+        unsafe { Value::from_ptr(LLVMBuildFree(self.llvm_builder, pointer.as_value_ref())) }
+    }
+
+    // Copies `size` bytes between buffers that must not overlap, through the
+    // llvm.memcpy intrinsic. An alignment of 0 promises nothing.
+    pub fn build_memcpy(
+        &self,
+        dest: PointerValue<'ctx>,
+        dest_align: u32,
+        src: PointerValue<'ctx>,
+        src_align: u32,
+        size: IntValue<'ctx>,
+        is_volatile: bool,
+    ) -> Value<'ctx> {
+        let call = unsafe {
+            LLVMBuildMemCpy(
+                self.llvm_builder,
+                dest.as_value_ref(),
+                dest_align,
+                src.as_value_ref(),
+                src_align,
+                size.as_value_ref(),
+            )
+        };
+        self.mem_intrinsic(call, is_volatile)
+    }
+
+    // Like build_memcpy, but the buffers may overlap.
+    pub fn build_memmove(
+        &self,
+        dest: PointerValue<'ctx>,
+        dest_align: u32,
+        src: PointerValue<'ctx>,
+        src_align: u32,
+        size: IntValue<'ctx>,
+        is_volatile: bool,
+    ) -> Value<'ctx> {
+        let call = unsafe {
+            LLVMBuildMemMove(
+                self.llvm_builder,
+                dest.as_value_ref(),
+                dest_align,
+                src.as_value_ref(),
+                src_align,
+                size.as_value_ref(),
+            )
+        };
+        self.mem_intrinsic(call, is_volatile)
+    }
+
+    // Fills `size` bytes with `value`, which must be an i8.
+    pub fn build_memset(
+        &self,
+        dest: PointerValue<'ctx>,
+        dest_align: u32,
+        value: IntValue<'ctx>,
+        size: IntValue<'ctx>,
+        is_volatile: bool,
+    ) -> Value<'ctx> {
+        let call = unsafe {
+            LLVMBuildMemSet(
+                self.llvm_builder,
+                dest.as_value_ref(),
+                value.as_value_ref(),
+                size.as_value_ref(),
+                dest_align,
+            )
+        };
+        self.mem_intrinsic(call, is_volatile)
+    }
+
+    // The C API always builds the non-volatile form; the flag is the
+    // intrinsic's last argument.
+    fn mem_intrinsic(&self, call: LLVMValueRef, is_volatile: bool) -> Value<'ctx> {
+        if is_volatile {
+            unsafe {
+                let context = LLVMGetTypeContext(LLVMTypeOf(call));
+                let flag = LLVMConstInt(LLVMInt1TypeInContext(context), 1, 0);
+                LLVMSetOperand(call, 3, flag);
+            }
+        }
+        Value::from_ptr(call)
     }
 
     #[inline]
-    pub fn build_struct_gep(&self, ptr: LLVMValueRef, index: u32) -> LLVMValueRef {
+    pub fn build_struct_gep(&self, ptr: PointerValue<'ctx>, index: u32) -> PointerValue<'ctx> {
         self.build_struct_gep_with_name(ptr, index, "")
     }
 
     #[inline]
     pub fn build_struct_gep_with_name(
         &self,
-        ptr: LLVMValueRef,
+        ptr: PointerValue<'ctx>,
         index: u32,
         name: &str,
-    ) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildStructGEP(self.llvm_builder, ptr, index, val_name_ptr) }
+    ) -> PointerValue<'ctx> {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            PointerValue::from_value_ref(LLVMBuildStructGEP(
+                self.llvm_builder,
+                ptr.as_value_ref(),
+                index,
+                val_name.as_ptr(),
+            ))
+        }
     }
 
     #[inline]
-    pub fn build_store(&self, val: LLVMValueRef, ptr: LLVMValueRef) -> LLVMValueRef {
-        unsafe { LLVMBuildStore(self.llvm_builder, val, ptr) }
+    pub fn build_store<V: AnyValue<'ctx>>(&self, val: V, ptr: PointerValue<'ctx>) -> Value<'ctx> {
+        unsafe {
+            Value::from_ptr(LLVMBuildStore(
+                self.llvm_builder,
+                val.as_value_ref(),
+                ptr.as_value_ref(),
+            ))
+        }
     }
 
     #[inline]
-    pub fn build_load(&self, pointer_val: LLVMValueRef) -> LLVMValueRef {
+    pub fn build_load(&self, pointer_val: PointerValue<'ctx>) -> Value<'ctx> {
         self.build_load_with_name(pointer_val, "")
     }
 
     #[inline]
-    pub fn build_load_with_name(&self, pointer_val: LLVMValueRef, name: &str) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildLoad(self.llvm_builder, pointer_val, val_name_ptr) }
+    pub fn build_load_with_name(&self, pointer_val: PointerValue<'ctx>, name: &str) -> Value<'ctx> {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            Value::from_ptr(LLVMBuildLoad(
+                self.llvm_builder,
+                pointer_val.as_value_ref(),
+                val_name.as_ptr(),
+            ))
+        }
+    }
+
+    // Atomic loads and stores need a type with a power-of-two size, and
+    // cannot use the Release or AcquireRelease (loads) or Acquire (stores)
+    // orderings.
+    #[inline]
+    pub fn build_atomic_load(
+        &self,
+        pointer_val: PointerValue<'ctx>,
+        ordering: AtomicOrdering,
+        scope: SyncScope,
+    ) -> Value<'ctx> {
+        self.build_atomic_load_with_name(pointer_val, ordering, scope, "")
+    }
+
+    pub fn build_atomic_load_with_name(
+        &self,
+        pointer_val: PointerValue<'ctx>,
+        ordering: AtomicOrdering,
+        scope: SyncScope,
+        name: &str,
+    ) -> Value<'ctx> {
+        let load = self.build_load_with_name(pointer_val, name);
+        unsafe {
+            LLVMSetOrdering(load.as_ref(), ordering.into());
+            LLVMSetAtomicSingleThread(load.as_ref(), scope.single_thread());
+            self.align_naturally(load.as_ref(), LLVMTypeOf(load.as_ref()));
+        }
+        load
+    }
+
+    pub fn build_atomic_store<V: AnyValue<'ctx>>(
+        &self,
+        val: V,
+        ptr: PointerValue<'ctx>,
+        ordering: AtomicOrdering,
+        scope: SyncScope,
+    ) -> Value<'ctx> {
+        let store = self.build_store(val, ptr);
+        unsafe {
+            LLVMSetOrdering(store.as_ref(), ordering.into());
+            LLVMSetAtomicSingleThread(store.as_ref(), scope.single_thread());
+            self.align_naturally(store.as_ref(), val.get_type().as_type_ref());
+        }
+        store
+    }
+
+    // Plain loads and stores get the ABI alignment, which for i64 can be less
+    // than its size and would make the access a library call that may tear.
+    // atomicrmw and cmpxchg are aligned to the size already.
+    unsafe fn align_naturally(&self, instruction: LLVMValueRef, typ: LLVMTypeRef) {
+        let data_layout = LLVMGetModuleDataLayout(self.insert_module());
+        let size = LLVMStoreSizeOfType(data_layout, typ);
+        LLVMSetAlignment(instruction, size as u32);
+    }
+
+    // Applies `op` to the value at `ptr` and `val`, stores the result and
+    // returns what was there before, all as one atomic step.
+    pub fn build_atomic_rmw<V: AnyValue<'ctx>>(
+        &self,
+        op: AtomicRMWBinOp,
+        ptr: PointerValue<'ctx>,
+        val: V,
+        ordering: AtomicOrdering,
+        scope: SyncScope,
+    ) -> Value<'ctx> {
+        unsafe {
+            Value::from_ptr(LLVMBuildAtomicRMW(
+                self.llvm_builder,
+                op.into(),
+                ptr.as_value_ref(),
+                val.as_value_ref(),
+                ordering.into(),
+                scope.single_thread(),
+            ))
+        }
+    }
+
+    // Stores `new` at `ptr` if it holds `cmp`. Yields { old value, i1 success }.
+    // The failure ordering can be no stronger than the success one and cannot
+    // be Release or AcquireRelease.
+    #[inline]
+    pub fn build_cmpxchg<V: AnyValue<'ctx>>(
+        &self,
+        ptr: PointerValue<'ctx>,
+        cmp: V,
+        new: V,
+        success: AtomicOrdering,
+        failure: AtomicOrdering,
+        scope: SyncScope,
+    ) -> Value<'ctx> {
+        unsafe {
+            Value::from_ptr(LLVMBuildAtomicCmpXchg(
+                self.llvm_builder,
+                ptr.as_value_ref(),
+                cmp.as_value_ref(),
+                new.as_value_ref(),
+                success.into(),
+                failure.into(),
+                scope.single_thread(),
+            ))
+        }
+    }
+
+    // Like build_cmpxchg, but allowed to fail even when the values match, which
+    // is cheaper on some targets when it is retried in a loop anyway.
+    #[inline]
+    pub fn build_weak_cmpxchg<V: AnyValue<'ctx>>(
+        &self,
+        ptr: PointerValue<'ctx>,
+        cmp: V,
+        new: V,
+        success: AtomicOrdering,
+        failure: AtomicOrdering,
+        scope: SyncScope,
+    ) -> Value<'ctx> {
+        let cmpxchg = self.build_cmpxchg(ptr, cmp, new, success, failure, scope);
+        unsafe { LLVMSetWeak(cmpxchg.as_ref(), 1) }
+        cmpxchg
+    }
+
+    // Only Acquire, Release, AcquireRelease and SequentiallyConsistent are
+    // valid fence orderings.
+    #[inline]
+    pub fn build_fence(&self, ordering: AtomicOrdering, scope: SyncScope) -> Value<'ctx> {
+        let no_name = SmallCStr::new("");
+        unsafe {
+            Value::from_ptr(LLVMBuildFence(
+                self.llvm_builder,
+                ordering.into(),
+                scope.single_thread(),
+                no_name.as_ptr(),
+            ))
+        }
     }
 
     #[inline]
-    pub fn build_select(
+    pub fn build_select<V: AnyValue<'ctx>>(
         &self,
-        cond: LLVMValueRef,
-        then_val: LLVMValueRef,
-        else_val: LLVMValueRef,
-    ) -> LLVMValueRef {
+        cond: IntValue<'ctx>,
+        then_val: V,
+        else_val: V,
+    ) -> V {
         self.build_select_with_name(cond, then_val, else_val, "")
     }
 
     #[inline]
-    pub fn build_select_with_name(
+    pub fn build_select_with_name<V: AnyValue<'ctx>>(
         &self,
-        cond: LLVMValueRef,
-        then_val: LLVMValueRef,
-        else_val: LLVMValueRef,
+        cond: IntValue<'ctx>,
+        then_val: V,
+        else_val: V,
         name: &str,
-    ) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildSelect(self.llvm_builder, cond, then_val, else_val, val_name_ptr) }
+    ) -> V {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            V::from_value_ref(self.with_fast_math(LLVMBuildSelect(
+                self.llvm_builder,
+                cond.as_value_ref(),
+                then_val.as_value_ref(),
+                else_val.as_value_ref(),
+                val_name.as_ptr(),
+            )))
+        }
     }
 
     #[inline]
-    pub fn build_int_to_ptr(&self, val: LLVMValueRef, to_type: LLVMTypeRef) -> LLVMValueRef {
+    pub fn build_int_to_ptr(
+        &self,
+        val: IntValue<'ctx>,
+        to_type: PointerType<'ctx>,
+    ) -> PointerValue<'ctx> {
         self.build_int_to_ptr_with_name(val, to_type, "")
     }
 
     #[inline]
     pub fn build_int_to_ptr_with_name(
         &self,
-        val: LLVMValueRef,
-        to_type: LLVMTypeRef,
+        val: IntValue<'ctx>,
+        to_type: PointerType<'ctx>,
         name: &str,
-    ) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildIntToPtr(self.llvm_builder, val, to_type, val_name_ptr) }
+    ) -> PointerValue<'ctx> {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            PointerValue::from_value_ref(LLVMBuildIntToPtr(
+                self.llvm_builder,
+                val.as_value_ref(),
+                to_type.as_type_ref(),
+                val_name.as_ptr(),
+            ))
+        }
     }
 
     #[inline]
-    pub fn build_ptr_to_int(&self, val: LLVMValueRef, to_type: LLVMTypeRef) -> LLVMValueRef {
+    pub fn build_ptr_to_int(
+        &self,
+        val: PointerValue<'ctx>,
+        to_type: IntType<'ctx>,
+    ) -> IntValue<'ctx> {
         self.build_ptr_to_int_with_name(val, to_type, "")
     }
 
     #[inline]
     pub fn build_ptr_to_int_with_name(
         &self,
-        val: LLVMValueRef,
-        to_type: LLVMTypeRef,
+        val: PointerValue<'ctx>,
+        to_type: IntType<'ctx>,
         name: &str,
-    ) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildPtrToInt(self.llvm_builder, val, to_type, val_name_ptr) }
+    ) -> IntValue<'ctx> {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            IntValue::from_value_ref(LLVMBuildPtrToInt(
+                self.llvm_builder,
+                val.as_value_ref(),
+                to_type.as_type_ref(),
+                val_name.as_ptr(),
+            ))
+        }
     }
 
     #[inline]
-    pub fn build_bitcast(&self, value: LLVMValueRef, to_type: LLVMTypeRef) -> LLVMValueRef {
+    pub fn build_bitcast<V: AnyValue<'ctx>, T: AnyType<'ctx>>(
+        &self,
+        value: V,
+        to_type: T,
+    ) -> Value<'ctx> {
         self.build_bitcast_with_name(value, to_type, "")
     }
 
     #[inline]
-    pub fn build_bitcast_with_name(
+    pub fn build_bitcast_with_name<V: AnyValue<'ctx>, T: AnyType<'ctx>>(
         &self,
-        value: LLVMValueRef,
-        to_type: LLVMTypeRef,
+        value: V,
+        to_type: T,
         name: &str,
-    ) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildBitCast(self.llvm_builder, value, to_type, val_name_ptr) }
+    ) -> Value<'ctx> {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            Value::from_ptr(LLVMBuildBitCast(
+                self.llvm_builder,
+                value.as_value_ref(),
+                to_type.as_type_ref(),
+                val_name.as_ptr(),
+            ))
+        }
     }
 
     #[inline]
-    pub fn build_zext(&self, val: LLVMValueRef, to_type: LLVMTypeRef) -> LLVMValueRef {
+    pub fn build_zext<T: IntMathValue<'ctx>>(&self, val: T, to_type: T::BaseType) -> T {
         self.build_zext_with_name(val, to_type, "")
     }
 
     #[inline]
-    pub fn build_zext_with_name(
+    pub fn build_zext_with_name<T: IntMathValue<'ctx>>(
         &self,
-        val: LLVMValueRef,
-        to_type: LLVMTypeRef,
+        val: T,
+        to_type: T::BaseType,
         name: &str,
-    ) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildZExt(self.llvm_builder, val, to_type, val_name_ptr) }
+    ) -> T {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            T::from_value_ref(LLVMBuildZExt(
+                self.llvm_builder,
+                val.as_value_ref(),
+                to_type.as_type_ref(),
+                val_name.as_ptr(),
+            ))
+        }
     }
 
     #[inline]
-    pub fn build_trunc(&self, val: LLVMValueRef, to_type: LLVMTypeRef) -> LLVMValueRef {
+    pub fn build_trunc<T: IntMathValue<'ctx>>(&self, val: T, to_type: T::BaseType) -> T {
         self.build_trunc_with_name(val, to_type, "")
     }
 
     #[inline]
-    pub fn build_trunc_with_name(
+    pub fn build_trunc_with_name<T: IntMathValue<'ctx>>(
         &self,
-        val: LLVMValueRef,
-        to_type: LLVMTypeRef,
+        val: T,
+        to_type: T::BaseType,
         name: &str,
-    ) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildTrunc(self.llvm_builder, val, to_type, val_name_ptr) }
+    ) -> T {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            T::from_value_ref(LLVMBuildTrunc(
+                self.llvm_builder,
+                val.as_value_ref(),
+                to_type.as_type_ref(),
+                val_name.as_ptr(),
+            ))
+        }
     }
 
     #[inline]
-    pub fn build_fp_trunc(&self, val: LLVMValueRef, to_type: LLVMTypeRef) -> LLVMValueRef {
+    pub fn build_fp_trunc<T: FloatMathValue<'ctx>>(&self, val: T, to_type: T::BaseType) -> T {
         self.build_fp_trunc_with_name(val, to_type, "")
     }
 
     #[inline]
-    pub fn build_fp_trunc_with_name(
+    pub fn build_fp_trunc_with_name<T: FloatMathValue<'ctx>>(
         &self,
-        val: LLVMValueRef,
-        to_type: LLVMTypeRef,
+        val: T,
+        to_type: T::BaseType,
         name: &str,
-    ) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildFPTrunc(self.llvm_builder, val, to_type, val_name_ptr) }
+    ) -> T {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            T::from_value_ref(LLVMBuildFPTrunc(
+                self.llvm_builder,
+                val.as_value_ref(),
+                to_type.as_type_ref(),
+                val_name.as_ptr(),
+            ))
+        }
     }
 
     #[inline]
-    pub fn build_trunc_or_bitcast(&self, val: LLVMValueRef, to_type: LLVMTypeRef) -> LLVMValueRef {
+    pub fn build_trunc_or_bitcast<V: AnyValue<'ctx>, T: AnyType<'ctx>>(
+        &self,
+        val: V,
+        to_type: T,
+    ) -> Value<'ctx> {
         self.build_trunc_or_bitcast_with_name(val, to_type, "")
     }
 
     #[inline]
-    pub fn build_trunc_or_bitcast_with_name(
+    pub fn build_trunc_or_bitcast_with_name<V: AnyValue<'ctx>, T: AnyType<'ctx>>(
         &self,
-        val: LLVMValueRef,
-        to_type: LLVMTypeRef,
+        val: V,
+        to_type: T,
         name: &str,
-    ) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildTruncOrBitCast(self.llvm_builder, val, to_type, val_name_ptr) }
+    ) -> Value<'ctx> {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            Value::from_ptr(LLVMBuildTruncOrBitCast(
+                self.llvm_builder,
+                val.as_value_ref(),
+                to_type.as_type_ref(),
+                val_name.as_ptr(),
+            ))
+        }
     }
 
     #[inline]
-    pub fn build_insert_value(
+    pub fn build_insert_value<A: AnyValue<'ctx>, V: AnyValue<'ctx>>(
         &self,
-        agg_val: LLVMValueRef,
-        elt_val: LLVMValueRef,
+        agg_val: A,
+        elt_val: V,
         index: u32,
-    ) -> LLVMValueRef {
+    ) -> A {
         self.build_insert_value_with_name(agg_val, elt_val, index, "")
     }
 
     #[inline]
-    pub fn build_insert_value_with_name(
+    pub fn build_insert_value_with_name<A: AnyValue<'ctx>, V: AnyValue<'ctx>>(
         &self,
-        agg_val: LLVMValueRef,
-        elt_val: LLVMValueRef,
+        agg_val: A,
+        elt_val: V,
         index: u32,
         name: &str,
-    ) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildInsertValue(self.llvm_builder, agg_val, elt_val, index, val_name_ptr) }
+    ) -> A {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            A::from_value_ref(LLVMBuildInsertValue(
+                self.llvm_builder,
+                agg_val.as_value_ref(),
+                elt_val.as_value_ref(),
+                index,
+                val_name.as_ptr(),
+            ))
+        }
     }
 
     #[inline]
-    pub fn build_extract_value(&self, agg_val: LLVMValueRef, index: u32) -> LLVMValueRef {
+    pub fn build_extract_value<A: AnyValue<'ctx>>(&self, agg_val: A, index: u32) -> Value<'ctx> {
         self.build_extract_value_with_name(agg_val, index, "")
     }
 
     #[inline]
-    pub fn build_extract_value_with_name(
+    pub fn build_extract_value_with_name<A: AnyValue<'ctx>>(
         &self,
-        agg_val: LLVMValueRef,
+        agg_val: A,
         index: u32,
         name: &str,
-    ) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildExtractValue(self.llvm_builder, agg_val, index, val_name_ptr) }
+    ) -> Value<'ctx> {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            Value::from_ptr(LLVMBuildExtractValue(
+                self.llvm_builder,
+                agg_val.as_value_ref(),
+                index,
+                val_name.as_ptr(),
+            ))
+        }
     }
 
     #[inline]
-    pub fn build_add(&self, lhs: LLVMValueRef, rhs: LLVMValueRef) -> LLVMValueRef {
-        self.build_add_with_name(lhs, rhs, "")
+    pub fn build_extract_element(
+        &self,
+        vector: VectorValue<'ctx>,
+        index: IntValue<'ctx>,
+    ) -> Value<'ctx> {
+        self.build_extract_element_with_name(vector, index, "")
     }
 
     #[inline]
-    pub fn build_add_with_name(
+    pub fn build_extract_element_with_name(
         &self,
-        lhs: LLVMValueRef,
-        rhs: LLVMValueRef,
+        vector: VectorValue<'ctx>,
+        index: IntValue<'ctx>,
         name: &str,
-    ) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildAdd(self.llvm_builder, lhs, rhs, val_name_ptr) }
+    ) -> Value<'ctx> {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            Value::from_ptr(LLVMBuildExtractElement(
+                self.llvm_builder,
+                vector.as_value_ref(),
+                index.as_value_ref(),
+                val_name.as_ptr(),
+            ))
+        }
     }
 
     #[inline]
-    pub fn build_fadd(&self, lhs: LLVMValueRef, rhs: LLVMValueRef) -> LLVMValueRef {
-        self.build_fadd_with_name(lhs, rhs, "")
+    pub fn build_insert_element<V: AnyValue<'ctx>>(
+        &self,
+        vector: VectorValue<'ctx>,
+        element: V,
+        index: IntValue<'ctx>,
+    ) -> VectorValue<'ctx> {
+        self.build_insert_element_with_name(vector, element, index, "")
     }
 
     #[inline]
-    pub fn build_fadd_with_name(
+    pub fn build_insert_element_with_name<V: AnyValue<'ctx>>(
         &self,
-        lhs: LLVMValueRef,
-        rhs: LLVMValueRef,
+        vector: VectorValue<'ctx>,
+        element: V,
+        index: IntValue<'ctx>,
         name: &str,
-    ) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildFAdd(self.llvm_builder, lhs, rhs, val_name_ptr) }
+    ) -> VectorValue<'ctx> {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            VectorValue::from_value_ref(LLVMBuildInsertElement(
+                self.llvm_builder,
+                vector.as_value_ref(),
+                element.as_value_ref(),
+                index.as_value_ref(),
+                val_name.as_ptr(),
+            ))
+        }
     }
 
+    // Picks lanes from `left` followed by `right` by index. `mask` is a
+    // constant vector of i32, and its length is the length of the result.
     #[inline]
-    pub fn build_sub(&self, lhs: LLVMValueRef, rhs: LLVMValueRef) -> LLVMValueRef {
-        self.build_sub_with_name(lhs, rhs, "")
+    pub fn build_shuffle_vector(
+        &self,
+        left: VectorValue<'ctx>,
+        right: VectorValue<'ctx>,
+        mask: VectorValue<'ctx>,
+    ) -> VectorValue<'ctx> {
+        self.build_shuffle_vector_with_name(left, right, mask, "")
     }
 
     #[inline]
-    pub fn build_sub_with_name(
+    pub fn build_shuffle_vector_with_name(
         &self,
-        lhs: LLVMValueRef,
-        rhs: LLVMValueRef,
+        left: VectorValue<'ctx>,
+        right: VectorValue<'ctx>,
+        mask: VectorValue<'ctx>,
         name: &str,
-    ) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildSub(self.llvm_builder, lhs, rhs, val_name_ptr) }
+    ) -> VectorValue<'ctx> {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            VectorValue::from_value_ref(LLVMBuildShuffleVector(
+                self.llvm_builder,
+                left.as_value_ref(),
+                right.as_value_ref(),
+                mask.as_value_ref(),
+                val_name.as_ptr(),
+            ))
+        }
     }
 
+    // A vector with `value` in each of its `count` lanes. Constants can use
+    // VectorType::const_splat instead.
     #[inline]
-    pub fn build_fsub(&self, lhs: LLVMValueRef, rhs: LLVMValueRef) -> LLVMValueRef {
-        self.build_fsub_with_name(lhs, rhs, "")
+    pub fn build_vector_splat<V: AnyValue<'ctx>>(&self, value: V, count: u32) -> VectorValue<'ctx> {
+        self.build_vector_splat_with_name(value, count, "")
+    }
+
+    pub fn build_vector_splat_with_name<V: AnyValue<'ctx>>(
+        &self,
+        value: V,
+        count: u32,
+        name: &str,
+    ) -> VectorValue<'ctx> {
+        let vector_type = value.get_type().vector_type(count);
+        unsafe {
+            let context = LLVMGetTypeContext(vector_type.as_type_ref());
+            let i32_type = IntType::from_type_ref(LLVMInt32TypeInContext(context));
+            let single = self.build_insert_element(
+                vector_type.get_undef(),
+                value,
+                i32_type.const_int(0, false),
+            );
+            let mask = i32_type.vector_type(count).const_zero();
+            self.build_shuffle_vector_with_name(single, vector_type.get_undef(), mask, name)
+        }
     }
 
     #[inline]
-    pub fn build_fsub_with_name(
+    pub fn build_vector_reduce(
         &self,
-        lhs: LLVMValueRef,
-        rhs: LLVMValueRef,
+        op: VectorReduction,
+        vector: VectorValue<'ctx>,
+    ) -> Result<Value<'ctx>, String> {
+        self.build_vector_reduce_with_name(op, vector, "")
+    }
+
+    #[inline]
+    pub fn build_vector_reduce_with_name(
+        &self,
+        op: VectorReduction,
+        vector: VectorValue<'ctx>,
         name: &str,
-    ) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildFSub(self.llvm_builder, lhs, rhs, val_name_ptr) }
+    ) -> Result<Value<'ctx>, String> {
+        self.build_reduction(op.name(), None, vector, name)
     }
 
+    // Adds the lanes to `start` one after another, in lane order, unless the
+    // builder's fast-math flags allow reassociation.
     #[inline]
-    pub fn build_mul(&self, lhs: LLVMValueRef, rhs: LLVMValueRef) -> LLVMValueRef {
-        self.build_mul_with_name(lhs, rhs, "")
+    pub fn build_vector_reduce_fadd(
+        &self,
+        start: FloatValue<'ctx>,
+        vector: VectorValue<'ctx>,
+    ) -> Result<FloatValue<'ctx>, String> {
+        self.build_vector_reduce_fadd_with_name(start, vector, "")
     }
 
     #[inline]
-    pub fn build_mul_with_name(
+    pub fn build_vector_reduce_fadd_with_name(
         &self,
-        lhs: LLVMValueRef,
-        rhs: LLVMValueRef,
+        start: FloatValue<'ctx>,
+        vector: VectorValue<'ctx>,
         name: &str,
-    ) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildMul(self.llvm_builder, lhs, rhs, val_name_ptr) }
+    ) -> Result<FloatValue<'ctx>, String> {
+        let sum = self.build_reduction("fadd", Some(start), vector, name)?;
+        unsafe { Ok(FloatValue::from_value_ref(sum.as_ref())) }
     }
 
     #[inline]
-    pub fn build_fmul(&self, lhs: LLVMValueRef, rhs: LLVMValueRef) -> LLVMValueRef {
-        self.build_fmul_with_name(lhs, rhs, "")
+    pub fn build_vector_reduce_fmul(
+        &self,
+        start: FloatValue<'ctx>,
+        vector: VectorValue<'ctx>,
+    ) -> Result<FloatValue<'ctx>, String> {
+        self.build_vector_reduce_fmul_with_name(start, vector, "")
     }
 
     #[inline]
-    pub fn build_fmul_with_name(
+    pub fn build_vector_reduce_fmul_with_name(
         &self,
-        lhs: LLVMValueRef,
-        rhs: LLVMValueRef,
+        start: FloatValue<'ctx>,
+        vector: VectorValue<'ctx>,
         name: &str,
-    ) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildFMul(self.llvm_builder, lhs, rhs, val_name_ptr) }
+    ) -> Result<FloatValue<'ctx>, String> {
+        let product = self.build_reduction("fmul", Some(start), vector, name)?;
+        unsafe { Ok(FloatValue::from_value_ref(product.as_ref())) }
+    }
+
+    // LLVM 12 renamed llvm.experimental.vector.reduce.* to llvm.vector.reduce.*,
+    // and dropped the v2 prefix and the overload on the start value of the
+    // float sums and products. Whichever name this LLVM knows is used; the
+    // error says when it knows neither, or there is no function to put the
+    // intrinsic's declaration in.
+    fn build_reduction(
+        &self,
+        op: &str,
+        start: Option<FloatValue<'ctx>>,
+        vector: VectorValue<'ctx>,
+        name: &str,
+    ) -> Result<Value<'ctx>, String> {
+        let module = unsafe {
+            if LLVMGetInsertBlock(self.llvm_builder).is_null() {
+                return Err("the builder is not positioned in a block".to_string());
+            }
+            self.insert_module()
+        };
+        let current = format!("llvm.vector.reduce.{}", op);
+        let experimental = match start {
+            Some(_) => format!("llvm.experimental.vector.reduce.v2.{}", op),
+            None => format!("llvm.experimental.vector.reduce.{}", op),
+        };
+        let lookup =
+            |name: &str| unsafe { LLVMLookupIntrinsicID(name.as_ptr() as *const _, name.len()) };
+        let (id, is_experimental) = match lookup(&current) {
+            0 => (lookup(&experimental), true),
+            id => (id, false),
+        };
+        if id == 0 {
+            return Err(format!("LLVM has no {} intrinsic", current));
+        }
+
+        let mut overloads = vec![vector.get_type().as_type_ref()];
+        if let (Some(start), true) = (start, is_experimental) {
+            overloads.insert(0, start.get_type().as_type_ref());
+        }
+        let mut args: Vec<LLVMValueRef> = start.iter().map(|start| start.as_value_ref()).collect();
+        args.push(vector.as_value_ref());
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            let intrinsic =
+                LLVMGetIntrinsicDeclaration(module, id, overloads.as_mut_ptr(), overloads.len());
+            Ok(Value::from_ptr(self.with_fast_math(LLVMBuildCall(
+                self.llvm_builder,
+                intrinsic,
+                args.as_mut_ptr(),
+                args.len() as u32,
+                val_name.as_ptr(),
+            ))))
+        }
     }
 
     #[inline]
-    pub fn build_sdiv(&self, lhs: LLVMValueRef, rhs: LLVMValueRef) -> LLVMValueRef {
+    pub fn build_add<T: IntMathValue<'ctx>>(&self, lhs: T, rhs: T) -> T {
+        self.build_add_with_name(lhs, rhs, "")
+    }
+
+    #[inline]
+    pub fn build_add_with_name<T: IntMathValue<'ctx>>(&self, lhs: T, rhs: T, name: &str) -> T {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            T::from_value_ref(LLVMBuildAdd(
+                self.llvm_builder,
+                lhs.as_value_ref(),
+                rhs.as_value_ref(),
+                val_name.as_ptr(),
+            ))
+        }
+    }
+
+    // The nsw and nuw forms promise the result does not overflow as a signed or
+    // unsigned number; if it does, the result is poison.
+    #[inline]
+    pub fn build_nsw_add<T: IntMathValue<'ctx>>(&self, lhs: T, rhs: T) -> T {
+        self.build_nsw_add_with_name(lhs, rhs, "")
+    }
+
+    #[inline]
+    pub fn build_nsw_add_with_name<T: IntMathValue<'ctx>>(&self, lhs: T, rhs: T, name: &str) -> T {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            T::from_value_ref(LLVMBuildNSWAdd(
+                self.llvm_builder,
+                lhs.as_value_ref(),
+                rhs.as_value_ref(),
+                val_name.as_ptr(),
+            ))
+        }
+    }
+
+    #[inline]
+    pub fn build_nuw_add<T: IntMathValue<'ctx>>(&self, lhs: T, rhs: T) -> T {
+        self.build_nuw_add_with_name(lhs, rhs, "")
+    }
+
+    #[inline]
+    pub fn build_nuw_add_with_name<T: IntMathValue<'ctx>>(&self, lhs: T, rhs: T, name: &str) -> T {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            T::from_value_ref(LLVMBuildNUWAdd(
+                self.llvm_builder,
+                lhs.as_value_ref(),
+                rhs.as_value_ref(),
+                val_name.as_ptr(),
+            ))
+        }
+    }
+
+    #[inline]
+    pub fn build_fadd<T: FloatMathValue<'ctx>>(&self, lhs: T, rhs: T) -> T {
+        self.build_fadd_with_name(lhs, rhs, "")
+    }
+
+    #[inline]
+    pub fn build_fadd_with_name<T: FloatMathValue<'ctx>>(&self, lhs: T, rhs: T, name: &str) -> T {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            T::from_value_ref(self.with_fast_math(LLVMBuildFAdd(
+                self.llvm_builder,
+                lhs.as_value_ref(),
+                rhs.as_value_ref(),
+                val_name.as_ptr(),
+            )))
+        }
+    }
+
+    #[inline]
+    pub fn build_sub<T: IntMathValue<'ctx>>(&self, lhs: T, rhs: T) -> T {
+        self.build_sub_with_name(lhs, rhs, "")
+    }
+
+    #[inline]
+    pub fn build_sub_with_name<T: IntMathValue<'ctx>>(&self, lhs: T, rhs: T, name: &str) -> T {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            T::from_value_ref(LLVMBuildSub(
+                self.llvm_builder,
+                lhs.as_value_ref(),
+                rhs.as_value_ref(),
+                val_name.as_ptr(),
+            ))
+        }
+    }
+
+    #[inline]
+    pub fn build_nsw_sub<T: IntMathValue<'ctx>>(&self, lhs: T, rhs: T) -> T {
+        self.build_nsw_sub_with_name(lhs, rhs, "")
+    }
+
+    #[inline]
+    pub fn build_nsw_sub_with_name<T: IntMathValue<'ctx>>(&self, lhs: T, rhs: T, name: &str) -> T {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            T::from_value_ref(LLVMBuildNSWSub(
+                self.llvm_builder,
+                lhs.as_value_ref(),
+                rhs.as_value_ref(),
+                val_name.as_ptr(),
+            ))
+        }
+    }
+
+    #[inline]
+    pub fn build_nuw_sub<T: IntMathValue<'ctx>>(&self, lhs: T, rhs: T) -> T {
+        self.build_nuw_sub_with_name(lhs, rhs, "")
+    }
+
+    #[inline]
+    pub fn build_nuw_sub_with_name<T: IntMathValue<'ctx>>(&self, lhs: T, rhs: T, name: &str) -> T {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            T::from_value_ref(LLVMBuildNUWSub(
+                self.llvm_builder,
+                lhs.as_value_ref(),
+                rhs.as_value_ref(),
+                val_name.as_ptr(),
+            ))
+        }
+    }
+
+    #[inline]
+    pub fn build_fsub<T: FloatMathValue<'ctx>>(&self, lhs: T, rhs: T) -> T {
+        self.build_fsub_with_name(lhs, rhs, "")
+    }
+
+    #[inline]
+    pub fn build_fsub_with_name<T: FloatMathValue<'ctx>>(&self, lhs: T, rhs: T, name: &str) -> T {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            T::from_value_ref(self.with_fast_math(LLVMBuildFSub(
+                self.llvm_builder,
+                lhs.as_value_ref(),
+                rhs.as_value_ref(),
+                val_name.as_ptr(),
+            )))
+        }
+    }
+
+    #[inline]
+    pub fn build_mul<T: IntMathValue<'ctx>>(&self, lhs: T, rhs: T) -> T {
+        self.build_mul_with_name(lhs, rhs, "")
+    }
+
+    #[inline]
+    pub fn build_mul_with_name<T: IntMathValue<'ctx>>(&self, lhs: T, rhs: T, name: &str) -> T {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            T::from_value_ref(LLVMBuildMul(
+                self.llvm_builder,
+                lhs.as_value_ref(),
+                rhs.as_value_ref(),
+                val_name.as_ptr(),
+            ))
+        }
+    }
+
+    #[inline]
+    pub fn build_nsw_mul<T: IntMathValue<'ctx>>(&self, lhs: T, rhs: T) -> T {
+        self.build_nsw_mul_with_name(lhs, rhs, "")
+    }
+
+    #[inline]
+    pub fn build_nsw_mul_with_name<T: IntMathValue<'ctx>>(&self, lhs: T, rhs: T, name: &str) -> T {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            T::from_value_ref(LLVMBuildNSWMul(
+                self.llvm_builder,
+                lhs.as_value_ref(),
+                rhs.as_value_ref(),
+                val_name.as_ptr(),
+            ))
+        }
+    }
+
+    #[inline]
+    pub fn build_nuw_mul<T: IntMathValue<'ctx>>(&self, lhs: T, rhs: T) -> T {
+        self.build_nuw_mul_with_name(lhs, rhs, "")
+    }
+
+    #[inline]
+    pub fn build_nuw_mul_with_name<T: IntMathValue<'ctx>>(&self, lhs: T, rhs: T, name: &str) -> T {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            T::from_value_ref(LLVMBuildNUWMul(
+                self.llvm_builder,
+                lhs.as_value_ref(),
+                rhs.as_value_ref(),
+                val_name.as_ptr(),
+            ))
+        }
+    }
+
+    #[inline]
+    pub fn build_fmul<T: FloatMathValue<'ctx>>(&self, lhs: T, rhs: T) -> T {
+        self.build_fmul_with_name(lhs, rhs, "")
+    }
+
+    #[inline]
+    pub fn build_fmul_with_name<T: FloatMathValue<'ctx>>(&self, lhs: T, rhs: T, name: &str) -> T {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            T::from_value_ref(self.with_fast_math(LLVMBuildFMul(
+                self.llvm_builder,
+                lhs.as_value_ref(),
+                rhs.as_value_ref(),
+                val_name.as_ptr(),
+            )))
+        }
+    }
+
+    #[inline]
+    pub fn build_sdiv<T: IntMathValue<'ctx>>(&self, lhs: T, rhs: T) -> T {
         self.build_sdiv_with_name(lhs, rhs, "")
     }
 
     #[inline]
-    pub fn build_sdiv_with_name(
+    pub fn build_sdiv_with_name<T: IntMathValue<'ctx>>(&self, lhs: T, rhs: T, name: &str) -> T {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            T::from_value_ref(LLVMBuildSDiv(
+                self.llvm_builder,
+                lhs.as_value_ref(),
+                rhs.as_value_ref(),
+                val_name.as_ptr(),
+            ))
+        }
+    }
+
+    // The exact forms promise the division leaves no remainder; if it does, the
+    // result is poison.
+    #[inline]
+    pub fn build_exact_sdiv<T: IntMathValue<'ctx>>(&self, lhs: T, rhs: T) -> T {
+        self.build_exact_sdiv_with_name(lhs, rhs, "")
+    }
+
+    #[inline]
+    pub fn build_exact_sdiv_with_name<T: IntMathValue<'ctx>>(
         &self,
-        lhs: LLVMValueRef,
-        rhs: LLVMValueRef,
+        lhs: T,
+        rhs: T,
         name: &str,
-    ) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildSDiv(self.llvm_builder, lhs, rhs, val_name_ptr) }
+    ) -> T {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            T::from_value_ref(LLVMBuildExactSDiv(
+                self.llvm_builder,
+                lhs.as_value_ref(),
+                rhs.as_value_ref(),
+                val_name.as_ptr(),
+            ))
+        }
     }
 
     #[inline]
-    pub fn build_fdiv(&self, lhs: LLVMValueRef, rhs: LLVMValueRef) -> LLVMValueRef {
+    pub fn build_fdiv<T: FloatMathValue<'ctx>>(&self, lhs: T, rhs: T) -> T {
         self.build_fdiv_with_name(lhs, rhs, "")
     }
 
     #[inline]
-    pub fn build_fdiv_with_name(
-        &self,
-        lhs: LLVMValueRef,
-        rhs: LLVMValueRef,
-        name: &str,
-    ) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildFDiv(self.llvm_builder, lhs, rhs, val_name_ptr) }
+    pub fn build_fdiv_with_name<T: FloatMathValue<'ctx>>(&self, lhs: T, rhs: T, name: &str) -> T {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            T::from_value_ref(self.with_fast_math(LLVMBuildFDiv(
+                self.llvm_builder,
+                lhs.as_value_ref(),
+                rhs.as_value_ref(),
+                val_name.as_ptr(),
+            )))
+        }
     }
 
     #[inline]
-    pub fn build_udiv(&self, lhs: LLVMValueRef, rhs: LLVMValueRef) -> LLVMValueRef {
+    pub fn build_udiv<T: IntMathValue<'ctx>>(&self, lhs: T, rhs: T) -> T {
         self.build_udiv_with_name(lhs, rhs, "")
     }
 
     #[inline]
-    pub fn build_udiv_with_name(
+    pub fn build_udiv_with_name<T: IntMathValue<'ctx>>(&self, lhs: T, rhs: T, name: &str) -> T {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            T::from_value_ref(LLVMBuildUDiv(
+                self.llvm_builder,
+                lhs.as_value_ref(),
+                rhs.as_value_ref(),
+                val_name.as_ptr(),
+            ))
+        }
+    }
+
+    #[inline]
+    pub fn build_exact_udiv<T: IntMathValue<'ctx>>(&self, lhs: T, rhs: T) -> T {
+        self.build_exact_udiv_with_name(lhs, rhs, "")
+    }
+
+    #[inline]
+    pub fn build_exact_udiv_with_name<T: IntMathValue<'ctx>>(
         &self,
-        lhs: LLVMValueRef,
-        rhs: LLVMValueRef,
+        lhs: T,
+        rhs: T,
         name: &str,
-    ) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildUDiv(self.llvm_builder, lhs, rhs, val_name_ptr) }
+    ) -> T {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            T::from_value_ref(LLVMBuildExactUDiv(
+                self.llvm_builder,
+                lhs.as_value_ref(),
+                rhs.as_value_ref(),
+                val_name.as_ptr(),
+            ))
+        }
     }
 
     #[inline]
-    pub fn build_srem(&self, lhs: LLVMValueRef, rhs: LLVMValueRef) -> LLVMValueRef {
+    pub fn build_srem<T: IntMathValue<'ctx>>(&self, lhs: T, rhs: T) -> T {
         self.build_srem_with_name(lhs, rhs, "")
     }
 
     #[inline]
-    pub fn build_srem_with_name(
-        &self,
-        lhs: LLVMValueRef,
-        rhs: LLVMValueRef,
-        name: &str,
-    ) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildSRem(self.llvm_builder, lhs, rhs, val_name_ptr) }
+    pub fn build_srem_with_name<T: IntMathValue<'ctx>>(&self, lhs: T, rhs: T, name: &str) -> T {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            T::from_value_ref(LLVMBuildSRem(
+                self.llvm_builder,
+                lhs.as_value_ref(),
+                rhs.as_value_ref(),
+                val_name.as_ptr(),
+            ))
+        }
     }
 
     #[inline]
-    pub fn build_frem(&self, lhs: LLVMValueRef, rhs: LLVMValueRef) -> LLVMValueRef {
+    pub fn build_frem<T: FloatMathValue<'ctx>>(&self, lhs: T, rhs: T) -> T {
         self.build_frem_with_name(lhs, rhs, "")
     }
 
     #[inline]
-    pub fn build_frem_with_name(
-        &self,
-        lhs: LLVMValueRef,
-        rhs: LLVMValueRef,
-        name: &str,
-    ) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildFRem(self.llvm_builder, lhs, rhs, val_name_ptr) }
+    pub fn build_frem_with_name<T: FloatMathValue<'ctx>>(&self, lhs: T, rhs: T, name: &str) -> T {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            T::from_value_ref(self.with_fast_math(LLVMBuildFRem(
+                self.llvm_builder,
+                lhs.as_value_ref(),
+                rhs.as_value_ref(),
+                val_name.as_ptr(),
+            )))
+        }
     }
 
     #[inline]
-    pub fn build_urem(&self, lhs: LLVMValueRef, rhs: LLVMValueRef) -> LLVMValueRef {
+    pub fn build_urem<T: IntMathValue<'ctx>>(&self, lhs: T, rhs: T) -> T {
         self.build_urem_with_name(lhs, rhs, "")
     }
 
     #[inline]
-    pub fn build_urem_with_name(
-        &self,
-        lhs: LLVMValueRef,
-        rhs: LLVMValueRef,
-        name: &str,
-    ) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildURem(self.llvm_builder, lhs, rhs, val_name_ptr) }
+    pub fn build_urem_with_name<T: IntMathValue<'ctx>>(&self, lhs: T, rhs: T, name: &str) -> T {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            T::from_value_ref(LLVMBuildURem(
+                self.llvm_builder,
+                lhs.as_value_ref(),
+                rhs.as_value_ref(),
+                val_name.as_ptr(),
+            ))
+        }
+    }
+
+    #[inline]
+    pub fn build_ret<V: AnyValue<'ctx>>(&self, value: V) -> Value<'ctx> {
+        unsafe { Value::from_ptr(LLVMBuildRet(self.llvm_builder, value.as_value_ref())) }
     }
 
     #[inline]
-    pub fn build_ret(&self, value: LLVMValueRef) -> LLVMValueRef {
-        unsafe { LLVMBuildRet(self.llvm_builder, value) }
+    pub fn build_ret_void(&self) -> Value<'ctx> {
+        unsafe { Value::from_ptr(LLVMBuildRetVoid(self.llvm_builder)) }
     }
 
     #[inline]
-    pub fn build_ret_void(&self) -> LLVMValueRef {
-        unsafe { LLVMBuildRetVoid(self.llvm_builder) }
+    pub fn build_unreachable(&self) -> Value<'ctx> {
+        unsafe { Value::from_ptr(LLVMBuildUnreachable(self.llvm_builder)) }
     }
 
     #[inline]
-    pub fn build_and(&self, lhs: LLVMValueRef, rhs: LLVMValueRef) -> LLVMValueRef {
+    pub fn build_and<T: IntMathValue<'ctx>>(&self, lhs: T, rhs: T) -> T {
         self.build_and_with_name(lhs, rhs, "")
     }
 
     #[inline]
-    pub fn build_and_with_name(
-        &self,
-        lhs: LLVMValueRef,
-        rhs: LLVMValueRef,
-        name: &str,
-    ) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildAnd(self.llvm_builder, lhs, rhs, val_name_ptr) }
+    pub fn build_and_with_name<T: IntMathValue<'ctx>>(&self, lhs: T, rhs: T, name: &str) -> T {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            T::from_value_ref(LLVMBuildAnd(
+                self.llvm_builder,
+                lhs.as_value_ref(),
+                rhs.as_value_ref(),
+                val_name.as_ptr(),
+            ))
+        }
     }
 
     #[inline]
-    pub fn build_or(&self, lhs: LLVMValueRef, rhs: LLVMValueRef) -> LLVMValueRef {
+    pub fn build_or<T: IntMathValue<'ctx>>(&self, lhs: T, rhs: T) -> T {
         self.build_or_with_name(lhs, rhs, "")
     }
 
     #[inline]
-    pub fn build_or_with_name(
-        &self,
-        lhs: LLVMValueRef,
-        rhs: LLVMValueRef,
-        name: &str,
-    ) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildOr(self.llvm_builder, lhs, rhs, val_name_ptr) }
+    pub fn build_or_with_name<T: IntMathValue<'ctx>>(&self, lhs: T, rhs: T, name: &str) -> T {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            T::from_value_ref(LLVMBuildOr(
+                self.llvm_builder,
+                lhs.as_value_ref(),
+                rhs.as_value_ref(),
+                val_name.as_ptr(),
+            ))
+        }
     }
 
     #[inline]
-    pub fn build_xor(&self, lhs: LLVMValueRef, rhs: LLVMValueRef) -> LLVMValueRef {
+    pub fn build_xor<T: IntMathValue<'ctx>>(&self, lhs: T, rhs: T) -> T {
         self.build_xor_with_name(lhs, rhs, "")
     }
 
     #[inline]
-    pub fn build_xor_with_name(
-        &self,
-        lhs: LLVMValueRef,
-        rhs: LLVMValueRef,
-        name: &str,
-    ) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildXor(self.llvm_builder, lhs, rhs, val_name_ptr) }
+    pub fn build_xor_with_name<T: IntMathValue<'ctx>>(&self, lhs: T, rhs: T, name: &str) -> T {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            T::from_value_ref(LLVMBuildXor(
+                self.llvm_builder,
+                lhs.as_value_ref(),
+                rhs.as_value_ref(),
+                val_name.as_ptr(),
+            ))
+        }
     }
 
     #[inline]
-    pub fn build_neg(&self, value: LLVMValueRef) -> LLVMValueRef {
+    pub fn build_neg<T: IntMathValue<'ctx>>(&self, value: T) -> T {
         self.build_neg_with_name(value, "")
     }
 
     #[inline]
-    pub fn build_neg_with_name(&self, value: LLVMValueRef, name: &str) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildNeg(self.llvm_builder, value, val_name_ptr) }
+    pub fn build_neg_with_name<T: IntMathValue<'ctx>>(&self, value: T, name: &str) -> T {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            T::from_value_ref(LLVMBuildNeg(
+                self.llvm_builder,
+                value.as_value_ref(),
+                val_name.as_ptr(),
+            ))
+        }
+    }
+
+    #[inline]
+    pub fn build_nsw_neg<T: IntMathValue<'ctx>>(&self, value: T) -> T {
+        self.build_nsw_neg_with_name(value, "")
+    }
+
+    #[inline]
+    pub fn build_nsw_neg_with_name<T: IntMathValue<'ctx>>(&self, value: T, name: &str) -> T {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            T::from_value_ref(LLVMBuildNSWNeg(
+                self.llvm_builder,
+                value.as_value_ref(),
+                val_name.as_ptr(),
+            ))
+        }
+    }
+
+    #[inline]
+    pub fn build_nuw_neg<T: IntMathValue<'ctx>>(&self, value: T) -> T {
+        self.build_nuw_neg_with_name(value, "")
+    }
+
+    #[inline]
+    pub fn build_nuw_neg_with_name<T: IntMathValue<'ctx>>(&self, value: T, name: &str) -> T {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            T::from_value_ref(LLVMBuildNUWNeg(
+                self.llvm_builder,
+                value.as_value_ref(),
+                val_name.as_ptr(),
+            ))
+        }
     }
 
     #[inline]
-    pub fn build_fneg(&self, value: LLVMValueRef) -> LLVMValueRef {
+    pub fn build_fneg<T: FloatMathValue<'ctx>>(&self, value: T) -> T {
         self.build_fneg_with_name(value, "")
     }
 
     #[inline]
-    pub fn build_fneg_with_name(&self, value: LLVMValueRef, name: &str) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildFNeg(self.llvm_builder, value, val_name_ptr) }
+    pub fn build_fneg_with_name<T: FloatMathValue<'ctx>>(&self, value: T, name: &str) -> T {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            T::from_value_ref(self.with_fast_math(LLVMBuildFNeg(
+                self.llvm_builder,
+                value.as_value_ref(),
+                val_name.as_ptr(),
+            )))
+        }
     }
 
     #[inline]
-    pub fn build_shl(&self, lhs: LLVMValueRef, rhs: LLVMValueRef) -> LLVMValueRef {
+    pub fn build_shl<T: IntMathValue<'ctx>>(&self, lhs: T, rhs: T) -> T {
         self.build_shl_with_name(lhs, rhs, "")
     }
 
     #[inline]
-    pub fn build_shl_with_name(
-        &self,
-        lhs: LLVMValueRef,
-        rhs: LLVMValueRef,
-        name: &str,
-    ) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildShl(self.llvm_builder, lhs, rhs, val_name_ptr) }
+    pub fn build_shl_with_name<T: IntMathValue<'ctx>>(&self, lhs: T, rhs: T, name: &str) -> T {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            T::from_value_ref(LLVMBuildShl(
+                self.llvm_builder,
+                lhs.as_value_ref(),
+                rhs.as_value_ref(),
+                val_name.as_ptr(),
+            ))
+        }
     }
 
     #[inline]
-    pub fn build_ashr(&self, lhs: LLVMValueRef, rhs: LLVMValueRef) -> LLVMValueRef {
+    pub fn build_ashr<T: IntMathValue<'ctx>>(&self, lhs: T, rhs: T) -> T {
         self.build_ashr_with_name(lhs, rhs, "")
     }
 
     #[inline]
-    pub fn build_ashr_with_name(
-        &self,
-        lhs: LLVMValueRef,
-        rhs: LLVMValueRef,
-        name: &str,
-    ) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildAShr(self.llvm_builder, lhs, rhs, val_name_ptr) }
+    pub fn build_ashr_with_name<T: IntMathValue<'ctx>>(&self, lhs: T, rhs: T, name: &str) -> T {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            T::from_value_ref(LLVMBuildAShr(
+                self.llvm_builder,
+                lhs.as_value_ref(),
+                rhs.as_value_ref(),
+                val_name.as_ptr(),
+            ))
+        }
     }
 
     #[inline]
-    pub fn build_lshr(&self, lhs: LLVMValueRef, rhs: LLVMValueRef) -> LLVMValueRef {
+    pub fn build_lshr<T: IntMathValue<'ctx>>(&self, lhs: T, rhs: T) -> T {
         self.build_lshr_with_name(lhs, rhs, "")
     }
 
     #[inline]
-    pub fn build_lshr_with_name(
-        &self,
-        lhs: LLVMValueRef,
-        rhs: LLVMValueRef,
-        name: &str,
-    ) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildLShr(self.llvm_builder, lhs, rhs, val_name_ptr) }
+    pub fn build_lshr_with_name<T: IntMathValue<'ctx>>(&self, lhs: T, rhs: T, name: &str) -> T {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            T::from_value_ref(LLVMBuildLShr(
+                self.llvm_builder,
+                lhs.as_value_ref(),
+                rhs.as_value_ref(),
+                val_name.as_ptr(),
+            ))
+        }
     }
 
     #[inline]
-    pub fn build_not(&self, value: LLVMValueRef) -> LLVMValueRef {
+    pub fn build_not<T: IntMathValue<'ctx>>(&self, value: T) -> T {
         self.build_not_with_name(value, "")
     }
 
     #[inline]
-    pub fn build_not_with_name(&self, value: LLVMValueRef, name: &str) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildNot(self.llvm_builder, value, val_name_ptr) }
+    pub fn build_not_with_name<T: IntMathValue<'ctx>>(&self, value: T, name: &str) -> T {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            T::from_value_ref(LLVMBuildNot(
+                self.llvm_builder,
+                value.as_value_ref(),
+                val_name.as_ptr(),
+            ))
+        }
     }
 
     #[inline]
-    pub fn build_is_not_null(&self, value: LLVMValueRef) -> LLVMValueRef {
+    pub fn build_is_not_null(&self, value: PointerValue<'ctx>) -> IntValue<'ctx> {
         self.build_is_not_null_with_name(value, "")
     }
 
     #[inline]
-    pub fn build_is_not_null_with_name(&self, value: LLVMValueRef, name: &str) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildIsNotNull(self.llvm_builder, value, val_name_ptr) }
+    pub fn build_is_not_null_with_name(
+        &self,
+        value: PointerValue<'ctx>,
+        name: &str,
+    ) -> IntValue<'ctx> {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            IntValue::from_value_ref(LLVMBuildIsNotNull(
+                self.llvm_builder,
+                value.as_value_ref(),
+                val_name.as_ptr(),
+            ))
+        }
     }
 
     #[inline]
-    pub fn build_icmp_eq(&self, lhs: LLVMValueRef, rhs: LLVMValueRef) -> LLVMValueRef {
+    pub fn build_icmp_eq<T: IntCompareValue<'ctx>>(&self, lhs: T, rhs: T) -> T::Mask {
         self.build_icmp_eq_with_name(lhs, rhs, "")
     }
 
     #[inline]
-    pub fn build_icmp_eq_with_name(
+    pub fn build_icmp_eq_with_name<T: IntCompareValue<'ctx>>(
         &self,
-        lhs: LLVMValueRef,
-        rhs: LLVMValueRef,
+        lhs: T,
+        rhs: T,
         name: &str,
-    ) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildICmp(self.llvm_builder, LLVMIntEQ, lhs, rhs, val_name_ptr) }
+    ) -> T::Mask {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            T::Mask::from_value_ref(LLVMBuildICmp(
+                self.llvm_builder,
+                LLVMIntEQ,
+                lhs.as_value_ref(),
+                rhs.as_value_ref(),
+                val_name.as_ptr(),
+            ))
+        }
     }
 
     #[inline]
-    pub fn build_icmp_ne(&self, lhs: LLVMValueRef, rhs: LLVMValueRef) -> LLVMValueRef {
+    pub fn build_icmp_ne<T: IntCompareValue<'ctx>>(&self, lhs: T, rhs: T) -> T::Mask {
         self.build_icmp_ne_with_name(lhs, rhs, "")
     }
 
     #[inline]
-    pub fn build_icmp_ne_with_name(
+    pub fn build_icmp_ne_with_name<T: IntCompareValue<'ctx>>(
         &self,
-        lhs: LLVMValueRef,
-        rhs: LLVMValueRef,
+        lhs: T,
+        rhs: T,
         name: &str,
-    ) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildICmp(self.llvm_builder, LLVMIntNE, lhs, rhs, val_name_ptr) }
+    ) -> T::Mask {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            T::Mask::from_value_ref(LLVMBuildICmp(
+                self.llvm_builder,
+                LLVMIntNE,
+                lhs.as_value_ref(),
+                rhs.as_value_ref(),
+                val_name.as_ptr(),
+            ))
+        }
     }
 
     #[inline]
-    pub fn build_icmp_ugt(&self, lhs: LLVMValueRef, rhs: LLVMValueRef) -> LLVMValueRef {
+    pub fn build_icmp_ugt<T: IntCompareValue<'ctx>>(&self, lhs: T, rhs: T) -> T::Mask {
         self.build_icmp_ugt_with_name(lhs, rhs, "")
     }
 
     #[inline]
-    pub fn build_icmp_ugt_with_name(
+    pub fn build_icmp_ugt_with_name<T: IntCompareValue<'ctx>>(
         &self,
-        lhs: LLVMValueRef,
-        rhs: LLVMValueRef,
+        lhs: T,
+        rhs: T,
         name: &str,
-    ) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildICmp(self.llvm_builder, LLVMIntUGT, lhs, rhs, val_name_ptr) }
+    ) -> T::Mask {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            T::Mask::from_value_ref(LLVMBuildICmp(
+                self.llvm_builder,
+                LLVMIntUGT,
+                lhs.as_value_ref(),
+                rhs.as_value_ref(),
+                val_name.as_ptr(),
+            ))
+        }
     }
 
     #[inline]
-    pub fn build_icmp_uge(&self, lhs: LLVMValueRef, rhs: LLVMValueRef) -> LLVMValueRef {
+    pub fn build_icmp_uge<T: IntCompareValue<'ctx>>(&self, lhs: T, rhs: T) -> T::Mask {
         self.build_icmp_uge_with_name(lhs, rhs, "")
     }
 
     #[inline]
-    pub fn build_icmp_uge_with_name(
+    pub fn build_icmp_uge_with_name<T: IntCompareValue<'ctx>>(
         &self,
-        lhs: LLVMValueRef,
-        rhs: LLVMValueRef,
+        lhs: T,
+        rhs: T,
         name: &str,
-    ) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildICmp(self.llvm_builder, LLVMIntUGE, lhs, rhs, val_name_ptr) }
+    ) -> T::Mask {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            T::Mask::from_value_ref(LLVMBuildICmp(
+                self.llvm_builder,
+                LLVMIntUGE,
+                lhs.as_value_ref(),
+                rhs.as_value_ref(),
+                val_name.as_ptr(),
+            ))
+        }
     }
 
     #[inline]
-    pub fn build_icmp_ult(&self, lhs: LLVMValueRef, rhs: LLVMValueRef) -> LLVMValueRef {
+    pub fn build_icmp_ult<T: IntCompareValue<'ctx>>(&self, lhs: T, rhs: T) -> T::Mask {
         self.build_icmp_ult_with_name(lhs, rhs, "")
     }
 
     #[inline]
-    pub fn build_icmp_ult_with_name(
+    pub fn build_icmp_ult_with_name<T: IntCompareValue<'ctx>>(
         &self,
-        lhs: LLVMValueRef,
-        rhs: LLVMValueRef,
+        lhs: T,
+        rhs: T,
         name: &str,
-    ) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildICmp(self.llvm_builder, LLVMIntULT, lhs, rhs, val_name_ptr) }
+    ) -> T::Mask {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            T::Mask::from_value_ref(LLVMBuildICmp(
+                self.llvm_builder,
+                LLVMIntULT,
+                lhs.as_value_ref(),
+                rhs.as_value_ref(),
+                val_name.as_ptr(),
+            ))
+        }
     }
 
     #[inline]
-    pub fn build_icmp_ule(&self, lhs: LLVMValueRef, rhs: LLVMValueRef) -> LLVMValueRef {
+    pub fn build_icmp_ule<T: IntCompareValue<'ctx>>(&self, lhs: T, rhs: T) -> T::Mask {
         self.build_icmp_ule_with_name(lhs, rhs, "")
     }
 
     #[inline]
-    pub fn build_icmp_ule_with_name(
+    pub fn build_icmp_ule_with_name<T: IntCompareValue<'ctx>>(
         &self,
-        lhs: LLVMValueRef,
-        rhs: LLVMValueRef,
+        lhs: T,
+        rhs: T,
         name: &str,
-    ) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildICmp(self.llvm_builder, LLVMIntULE, lhs, rhs, val_name_ptr) }
+    ) -> T::Mask {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            T::Mask::from_value_ref(LLVMBuildICmp(
+                self.llvm_builder,
+                LLVMIntULE,
+                lhs.as_value_ref(),
+                rhs.as_value_ref(),
+                val_name.as_ptr(),
+            ))
+        }
     }
 
     #[inline]
-    pub fn build_icmp_sgt(&self, lhs: LLVMValueRef, rhs: LLVMValueRef) -> LLVMValueRef {
+    pub fn build_icmp_sgt<T: IntCompareValue<'ctx>>(&self, lhs: T, rhs: T) -> T::Mask {
         self.build_icmp_sgt_with_name(lhs, rhs, "")
     }
 
     #[inline]
-    pub fn build_icmp_sgt_with_name(
+    pub fn build_icmp_sgt_with_name<T: IntCompareValue<'ctx>>(
         &self,
-        lhs: LLVMValueRef,
-        rhs: LLVMValueRef,
+        lhs: T,
+        rhs: T,
         name: &str,
-    ) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildICmp(self.llvm_builder, LLVMIntSGT, lhs, rhs, val_name_ptr) }
+    ) -> T::Mask {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            T::Mask::from_value_ref(LLVMBuildICmp(
+                self.llvm_builder,
+                LLVMIntSGT,
+                lhs.as_value_ref(),
+                rhs.as_value_ref(),
+                val_name.as_ptr(),
+            ))
+        }
     }
 
     #[inline]
-    pub fn build_icmp_sge(&self, lhs: LLVMValueRef, rhs: LLVMValueRef) -> LLVMValueRef {
+    pub fn build_icmp_sge<T: IntCompareValue<'ctx>>(&self, lhs: T, rhs: T) -> T::Mask {
         self.build_icmp_sge_with_name(lhs, rhs, "")
     }
 
     #[inline]
-    pub fn build_icmp_sge_with_name(
+    pub fn build_icmp_sge_with_name<T: IntCompareValue<'ctx>>(
         &self,
-        lhs: LLVMValueRef,
-        rhs: LLVMValueRef,
+        lhs: T,
+        rhs: T,
         name: &str,
-    ) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildICmp(self.llvm_builder, LLVMIntSGE, lhs, rhs, val_name_ptr) }
+    ) -> T::Mask {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            T::Mask::from_value_ref(LLVMBuildICmp(
+                self.llvm_builder,
+                LLVMIntSGE,
+                lhs.as_value_ref(),
+                rhs.as_value_ref(),
+                val_name.as_ptr(),
+            ))
+        }
     }
 
     #[inline]
-    pub fn build_icmp_slt(&self, lhs: LLVMValueRef, rhs: LLVMValueRef) -> LLVMValueRef {
+    pub fn build_icmp_slt<T: IntCompareValue<'ctx>>(&self, lhs: T, rhs: T) -> T::Mask {
         self.build_icmp_slt_with_name(lhs, rhs, "")
     }
 
     #[inline]
-    pub fn build_icmp_slt_with_name(
+    pub fn build_icmp_slt_with_name<T: IntCompareValue<'ctx>>(
         &self,
-        lhs: LLVMValueRef,
-        rhs: LLVMValueRef,
+        lhs: T,
+        rhs: T,
         name: &str,
-    ) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildICmp(self.llvm_builder, LLVMIntSLT, lhs, rhs, val_name_ptr) }
+    ) -> T::Mask {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            T::Mask::from_value_ref(LLVMBuildICmp(
+                self.llvm_builder,
+                LLVMIntSLT,
+                lhs.as_value_ref(),
+                rhs.as_value_ref(),
+                val_name.as_ptr(),
+            ))
+        }
     }
 
     #[inline]
-    pub fn build_icmp_sle(&self, lhs: LLVMValueRef, rhs: LLVMValueRef) -> LLVMValueRef {
+    pub fn build_icmp_sle<T: IntCompareValue<'ctx>>(&self, lhs: T, rhs: T) -> T::Mask {
         self.build_icmp_sle_with_name(lhs, rhs, "")
     }
 
     #[inline]
-    pub fn build_icmp_sle_with_name(
+    pub fn build_icmp_sle_with_name<T: IntCompareValue<'ctx>>(
         &self,
-        lhs: LLVMValueRef,
-        rhs: LLVMValueRef,
+        lhs: T,
+        rhs: T,
         name: &str,
-    ) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildICmp(self.llvm_builder, LLVMIntSLE, lhs, rhs, val_name_ptr) }
+    ) -> T::Mask {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            T::Mask::from_value_ref(LLVMBuildICmp(
+                self.llvm_builder,
+                LLVMIntSLE,
+                lhs.as_value_ref(),
+                rhs.as_value_ref(),
+                val_name.as_ptr(),
+            ))
+        }
     }
 
     #[inline]
-    pub fn build_fcmp_predicate_false(&self, lhs: LLVMValueRef, rhs: LLVMValueRef) -> LLVMValueRef {
+    pub fn build_fcmp_predicate_false<T: FloatMathValue<'ctx>>(&self, lhs: T, rhs: T) -> T::Mask {
         self.build_fcmp_predicate_false_with_name(lhs, rhs, "")
     }
 
     #[inline]
-    pub fn build_fcmp_predicate_false_with_name(
+    pub fn build_fcmp_predicate_false_with_name<T: FloatMathValue<'ctx>>(
         &self,
-        lhs: LLVMValueRef,
-        rhs: LLVMValueRef,
+        lhs: T,
+        rhs: T,
         name: &str,
-    ) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
+    ) -> T::Mask {
+        let val_name = SmallCStr::new(name);
         unsafe {
-            LLVMBuildFCmp(
+            T::Mask::from_value_ref(self.with_fast_math(LLVMBuildFCmp(
                 self.llvm_builder,
                 LLVMRealPredicateFalse,
-                lhs,
-                rhs,
-                val_name_ptr,
-            )
+                lhs.as_value_ref(),
+                rhs.as_value_ref(),
+                val_name.as_ptr(),
+            )))
         }
     }
 
     #[inline]
-    pub fn build_fcmp_predicate_true(&self, lhs: LLVMValueRef, rhs: LLVMValueRef) -> LLVMValueRef {
+    pub fn build_fcmp_predicate_true<T: FloatMathValue<'ctx>>(&self, lhs: T, rhs: T) -> T::Mask {
         self.build_fcmp_predicate_true_with_name(lhs, rhs, "")
     }
 
     #[inline]
-    pub fn build_fcmp_predicate_true_with_name(
+    pub fn build_fcmp_predicate_true_with_name<T: FloatMathValue<'ctx>>(
         &self,
-        lhs: LLVMValueRef,
-        rhs: LLVMValueRef,
+        lhs: T,
+        rhs: T,
         name: &str,
-    ) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
+    ) -> T::Mask {
+        let val_name = SmallCStr::new(name);
         unsafe {
-            LLVMBuildFCmp(
+            T::Mask::from_value_ref(self.with_fast_math(LLVMBuildFCmp(
                 self.llvm_builder,
                 LLVMRealPredicateTrue,
-                lhs,
-                rhs,
-                val_name_ptr,
-            )
+                lhs.as_value_ref(),
+                rhs.as_value_ref(),
+                val_name.as_ptr(),
+            )))
         }
     }
 
     #[inline]
-    pub fn build_fcmp_ord(&self, lhs: LLVMValueRef, rhs: LLVMValueRef) -> LLVMValueRef {
+    pub fn build_fcmp_ord<T: FloatMathValue<'ctx>>(&self, lhs: T, rhs: T) -> T::Mask {
         self.build_fcmp_ord_with_name(lhs, rhs, "")
     }
 
     #[inline]
-    pub fn build_fcmp_ord_with_name(
+    pub fn build_fcmp_ord_with_name<T: FloatMathValue<'ctx>>(
         &self,
-        lhs: LLVMValueRef,
-        rhs: LLVMValueRef,
+        lhs: T,
+        rhs: T,
         name: &str,
-    ) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildFCmp(self.llvm_builder, LLVMRealORD, lhs, rhs, val_name_ptr) }
+    ) -> T::Mask {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            T::Mask::from_value_ref(self.with_fast_math(LLVMBuildFCmp(
+                self.llvm_builder,
+                LLVMRealORD,
+                lhs.as_value_ref(),
+                rhs.as_value_ref(),
+                val_name.as_ptr(),
+            )))
+        }
     }
 
     #[inline]
-    pub fn build_fcmp_oeq(&self, lhs: LLVMValueRef, rhs: LLVMValueRef) -> LLVMValueRef {
+    pub fn build_fcmp_oeq<T: FloatMathValue<'ctx>>(&self, lhs: T, rhs: T) -> T::Mask {
         self.build_fcmp_oeq_with_name(lhs, rhs, "")
     }
 
     #[inline]
-    pub fn build_fcmp_oeq_with_name(
+    pub fn build_fcmp_oeq_with_name<T: FloatMathValue<'ctx>>(
         &self,
-        lhs: LLVMValueRef,
-        rhs: LLVMValueRef,
+        lhs: T,
+        rhs: T,
         name: &str,
-    ) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildFCmp(self.llvm_builder, LLVMRealOEQ, lhs, rhs, val_name_ptr) }
+    ) -> T::Mask {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            T::Mask::from_value_ref(self.with_fast_math(LLVMBuildFCmp(
+                self.llvm_builder,
+                LLVMRealOEQ,
+                lhs.as_value_ref(),
+                rhs.as_value_ref(),
+                val_name.as_ptr(),
+            )))
+        }
     }
 
     #[inline]
-    pub fn build_fcmp_one(&self, lhs: LLVMValueRef, rhs: LLVMValueRef) -> LLVMValueRef {
+    pub fn build_fcmp_one<T: FloatMathValue<'ctx>>(&self, lhs: T, rhs: T) -> T::Mask {
         self.build_fcmp_one_with_name(lhs, rhs, "")
     }
 
     #[inline]
-    pub fn build_fcmp_one_with_name(
+    pub fn build_fcmp_one_with_name<T: FloatMathValue<'ctx>>(
         &self,
-        lhs: LLVMValueRef,
-        rhs: LLVMValueRef,
+        lhs: T,
+        rhs: T,
         name: &str,
-    ) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildFCmp(self.llvm_builder, LLVMRealONE, lhs, rhs, val_name_ptr) }
+    ) -> T::Mask {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            T::Mask::from_value_ref(self.with_fast_math(LLVMBuildFCmp(
+                self.llvm_builder,
+                LLVMRealONE,
+                lhs.as_value_ref(),
+                rhs.as_value_ref(),
+                val_name.as_ptr(),
+            )))
+        }
     }
 
     #[inline]
-    pub fn build_fcmp_ogt(&self, lhs: LLVMValueRef, rhs: LLVMValueRef) -> LLVMValueRef {
+    pub fn build_fcmp_ogt<T: FloatMathValue<'ctx>>(&self, lhs: T, rhs: T) -> T::Mask {
         self.build_fcmp_ogt_with_name(lhs, rhs, "")
     }
 
     #[inline]
-    pub fn build_fcmp_ogt_with_name(
+    pub fn build_fcmp_ogt_with_name<T: FloatMathValue<'ctx>>(
         &self,
-        lhs: LLVMValueRef,
-        rhs: LLVMValueRef,
+        lhs: T,
+        rhs: T,
         name: &str,
-    ) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildFCmp(self.llvm_builder, LLVMRealOGT, lhs, rhs, val_name_ptr) }
+    ) -> T::Mask {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            T::Mask::from_value_ref(self.with_fast_math(LLVMBuildFCmp(
+                self.llvm_builder,
+                LLVMRealOGT,
+                lhs.as_value_ref(),
+                rhs.as_value_ref(),
+                val_name.as_ptr(),
+            )))
+        }
     }
 
     #[inline]
-    pub fn build_fcmp_oge(&self, lhs: LLVMValueRef, rhs: LLVMValueRef) -> LLVMValueRef {
+    pub fn build_fcmp_oge<T: FloatMathValue<'ctx>>(&self, lhs: T, rhs: T) -> T::Mask {
         self.build_fcmp_oge_with_name(lhs, rhs, "")
     }
 
     #[inline]
-    pub fn build_fcmp_oge_with_name(
+    pub fn build_fcmp_oge_with_name<T: FloatMathValue<'ctx>>(
         &self,
-        lhs: LLVMValueRef,
-        rhs: LLVMValueRef,
+        lhs: T,
+        rhs: T,
         name: &str,
-    ) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildFCmp(self.llvm_builder, LLVMRealOGE, lhs, rhs, val_name_ptr) }
+    ) -> T::Mask {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            T::Mask::from_value_ref(self.with_fast_math(LLVMBuildFCmp(
+                self.llvm_builder,
+                LLVMRealOGE,
+                lhs.as_value_ref(),
+                rhs.as_value_ref(),
+                val_name.as_ptr(),
+            )))
+        }
     }
 
     #[inline]
-    pub fn build_fcmp_olt(&self, lhs: LLVMValueRef, rhs: LLVMValueRef) -> LLVMValueRef {
+    pub fn build_fcmp_olt<T: FloatMathValue<'ctx>>(&self, lhs: T, rhs: T) -> T::Mask {
         self.build_fcmp_olt_with_name(lhs, rhs, "")
     }
 
     #[inline]
-    pub fn build_fcmp_olt_with_name(
+    pub fn build_fcmp_olt_with_name<T: FloatMathValue<'ctx>>(
         &self,
-        lhs: LLVMValueRef,
-        rhs: LLVMValueRef,
+        lhs: T,
+        rhs: T,
         name: &str,
-    ) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildFCmp(self.llvm_builder, LLVMRealOLT, lhs, rhs, val_name_ptr) }
+    ) -> T::Mask {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            T::Mask::from_value_ref(self.with_fast_math(LLVMBuildFCmp(
+                self.llvm_builder,
+                LLVMRealOLT,
+                lhs.as_value_ref(),
+                rhs.as_value_ref(),
+                val_name.as_ptr(),
+            )))
+        }
     }
 
     #[inline]
-    pub fn build_fcmp_ole(&self, lhs: LLVMValueRef, rhs: LLVMValueRef) -> LLVMValueRef {
+    pub fn build_fcmp_ole<T: FloatMathValue<'ctx>>(&self, lhs: T, rhs: T) -> T::Mask {
         self.build_fcmp_ole_with_name(lhs, rhs, "")
     }
 
     #[inline]
-    pub fn build_fcmp_ole_with_name(
+    pub fn build_fcmp_ole_with_name<T: FloatMathValue<'ctx>>(
         &self,
-        lhs: LLVMValueRef,
-        rhs: LLVMValueRef,
+        lhs: T,
+        rhs: T,
         name: &str,
-    ) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildFCmp(self.llvm_builder, LLVMRealOLE, lhs, rhs, val_name_ptr) }
+    ) -> T::Mask {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            T::Mask::from_value_ref(self.with_fast_math(LLVMBuildFCmp(
+                self.llvm_builder,
+                LLVMRealOLE,
+                lhs.as_value_ref(),
+                rhs.as_value_ref(),
+                val_name.as_ptr(),
+            )))
+        }
     }
 
     #[inline]
-    pub fn build_fcmp_uno(&self, lhs: LLVMValueRef, rhs: LLVMValueRef) -> LLVMValueRef {
+    pub fn build_fcmp_uno<T: FloatMathValue<'ctx>>(&self, lhs: T, rhs: T) -> T::Mask {
         self.build_fcmp_uno_with_name(lhs, rhs, "")
     }
 
     #[inline]
-    pub fn build_fcmp_uno_with_name(
+    pub fn build_fcmp_uno_with_name<T: FloatMathValue<'ctx>>(
         &self,
-        lhs: LLVMValueRef,
-        rhs: LLVMValueRef,
+        lhs: T,
+        rhs: T,
         name: &str,
-    ) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildFCmp(self.llvm_builder, LLVMRealUNO, lhs, rhs, val_name_ptr) }
+    ) -> T::Mask {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            T::Mask::from_value_ref(self.with_fast_math(LLVMBuildFCmp(
+                self.llvm_builder,
+                LLVMRealUNO,
+                lhs.as_value_ref(),
+                rhs.as_value_ref(),
+                val_name.as_ptr(),
+            )))
+        }
     }
 
     #[inline]
-    pub fn build_fcmp_ueq(&self, lhs: LLVMValueRef, rhs: LLVMValueRef) -> LLVMValueRef {
+    pub fn build_fcmp_ueq<T: FloatMathValue<'ctx>>(&self, lhs: T, rhs: T) -> T::Mask {
         self.build_fcmp_ueq_with_name(lhs, rhs, "")
     }
 
     #[inline]
-    pub fn build_fcmp_ueq_with_name(
+    pub fn build_fcmp_ueq_with_name<T: FloatMathValue<'ctx>>(
         &self,
-        lhs: LLVMValueRef,
-        rhs: LLVMValueRef,
+        lhs: T,
+        rhs: T,
         name: &str,
-    ) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildFCmp(self.llvm_builder, LLVMRealUEQ, lhs, rhs, val_name_ptr) }
+    ) -> T::Mask {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            T::Mask::from_value_ref(self.with_fast_math(LLVMBuildFCmp(
+                self.llvm_builder,
+                LLVMRealUEQ,
+                lhs.as_value_ref(),
+                rhs.as_value_ref(),
+                val_name.as_ptr(),
+            )))
+        }
     }
 
     #[inline]
-    pub fn build_fcmp_une(&self, lhs: LLVMValueRef, rhs: LLVMValueRef) -> LLVMValueRef {
+    pub fn build_fcmp_une<T: FloatMathValue<'ctx>>(&self, lhs: T, rhs: T) -> T::Mask {
         self.build_fcmp_une_with_name(lhs, rhs, "")
     }
 
     #[inline]
-    pub fn build_fcmp_une_with_name(
+    pub fn build_fcmp_une_with_name<T: FloatMathValue<'ctx>>(
         &self,
-        lhs: LLVMValueRef,
-        rhs: LLVMValueRef,
+        lhs: T,
+        rhs: T,
         name: &str,
-    ) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildFCmp(self.llvm_builder, LLVMRealUNE, lhs, rhs, val_name_ptr) }
+    ) -> T::Mask {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            T::Mask::from_value_ref(self.with_fast_math(LLVMBuildFCmp(
+                self.llvm_builder,
+                LLVMRealUNE,
+                lhs.as_value_ref(),
+                rhs.as_value_ref(),
+                val_name.as_ptr(),
+            )))
+        }
     }
 
     #[inline]
-    pub fn build_fcmp_ugt(&self, lhs: LLVMValueRef, rhs: LLVMValueRef) -> LLVMValueRef {
+    pub fn build_fcmp_ugt<T: FloatMathValue<'ctx>>(&self, lhs: T, rhs: T) -> T::Mask {
         self.build_fcmp_ugt_with_name(lhs, rhs, "")
     }
 
     #[inline]
-    pub fn build_fcmp_ugt_with_name(
+    pub fn build_fcmp_ugt_with_name<T: FloatMathValue<'ctx>>(
         &self,
-        lhs: LLVMValueRef,
-        rhs: LLVMValueRef,
+        lhs: T,
+        rhs: T,
         name: &str,
-    ) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildFCmp(self.llvm_builder, LLVMRealUGT, lhs, rhs, val_name_ptr) }
+    ) -> T::Mask {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            T::Mask::from_value_ref(self.with_fast_math(LLVMBuildFCmp(
+                self.llvm_builder,
+                LLVMRealUGT,
+                lhs.as_value_ref(),
+                rhs.as_value_ref(),
+                val_name.as_ptr(),
+            )))
+        }
     }
 
     #[inline]
-    pub fn build_fcmp_uge(&self, lhs: LLVMValueRef, rhs: LLVMValueRef) -> LLVMValueRef {
+    pub fn build_fcmp_uge<T: FloatMathValue<'ctx>>(&self, lhs: T, rhs: T) -> T::Mask {
         self.build_fcmp_uge_with_name(lhs, rhs, "")
     }
 
     #[inline]
-    pub fn build_fcmp_uge_with_name(
+    pub fn build_fcmp_uge_with_name<T: FloatMathValue<'ctx>>(
         &self,
-        lhs: LLVMValueRef,
-        rhs: LLVMValueRef,
+        lhs: T,
+        rhs: T,
         name: &str,
-    ) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildFCmp(self.llvm_builder, LLVMRealUGE, lhs, rhs, val_name_ptr) }
+    ) -> T::Mask {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            T::Mask::from_value_ref(self.with_fast_math(LLVMBuildFCmp(
+                self.llvm_builder,
+                LLVMRealUGE,
+                lhs.as_value_ref(),
+                rhs.as_value_ref(),
+                val_name.as_ptr(),
+            )))
+        }
     }
 
     #[inline]
-    pub fn build_fcmp_ult(&self, lhs: LLVMValueRef, rhs: LLVMValueRef) -> LLVMValueRef {
+    pub fn build_fcmp_ult<T: FloatMathValue<'ctx>>(&self, lhs: T, rhs: T) -> T::Mask {
         self.build_fcmp_ult_with_name(lhs, rhs, "")
     }
 
     #[inline]
-    pub fn build_fcmp_ult_with_name(
+    pub fn build_fcmp_ult_with_name<T: FloatMathValue<'ctx>>(
         &self,
-        lhs: LLVMValueRef,
-        rhs: LLVMValueRef,
+        lhs: T,
+        rhs: T,
         name: &str,
-    ) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildFCmp(self.llvm_builder, LLVMRealULT, lhs, rhs, val_name_ptr) }
+    ) -> T::Mask {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            T::Mask::from_value_ref(self.with_fast_math(LLVMBuildFCmp(
+                self.llvm_builder,
+                LLVMRealULT,
+                lhs.as_value_ref(),
+                rhs.as_value_ref(),
+                val_name.as_ptr(),
+            )))
+        }
     }
 
     #[inline]
-    pub fn build_fcmp_ule(&self, lhs: LLVMValueRef, rhs: LLVMValueRef) -> LLVMValueRef {
+    pub fn build_fcmp_ule<T: FloatMathValue<'ctx>>(&self, lhs: T, rhs: T) -> T::Mask {
         self.build_fcmp_ule_with_name(lhs, rhs, "")
     }
 
     #[inline]
-    pub fn build_fcmp_ule_with_name(
+    pub fn build_fcmp_ule_with_name<T: FloatMathValue<'ctx>>(
         &self,
-        lhs: LLVMValueRef,
-        rhs: LLVMValueRef,
+        lhs: T,
+        rhs: T,
         name: &str,
-    ) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildFCmp(self.llvm_builder, LLVMRealULE, lhs, rhs, val_name_ptr) }
+    ) -> T::Mask {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            T::Mask::from_value_ref(self.with_fast_math(LLVMBuildFCmp(
+                self.llvm_builder,
+                LLVMRealULE,
+                lhs.as_value_ref(),
+                rhs.as_value_ref(),
+                val_name.as_ptr(),
+            )))
+        }
     }
 
     #[inline]
-    pub fn build_global_string_ptr(&self, string: &str) -> LLVMValueRef {
+    pub fn build_global_string_ptr(&self, string: &str) -> PointerValue<'ctx> {
         self.build_global_string_ptr_with_name(string, "")
     }
 
     #[inline]
-    pub fn build_global_string_ptr_with_name(&self, string: &str, name: &str) -> LLVMValueRef {
-        let val_str_ptr = CStringManager::new_cstring_as_ptr(string);
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildGlobalStringPtr(self.llvm_builder, val_str_ptr, val_name_ptr) }
+    pub fn build_global_string_ptr_with_name(
+        &self,
+        string: &str,
+        name: &str,
+    ) -> PointerValue<'ctx> {
+        let val_str = SmallCStr::new(string);
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            PointerValue::from_value_ref(LLVMBuildGlobalStringPtr(
+                self.llvm_builder,
+                val_str.as_ptr(),
+                val_name.as_ptr(),
+            ))
+        }
     }
 
     #[inline]
-    pub fn build_call(&self, func: LLVMValueRef, params: &mut [LLVMValueRef]) -> LLVMValueRef {
+    pub fn build_call(&self, func: Function<'ctx>, params: &[Value<'ctx>]) -> Value<'ctx> {
         self.build_call_with_name(func, params, "")
     }
 
     #[inline]
     pub fn build_call_with_name(
         &self,
-        func: LLVMValueRef,
-        params: &mut [LLVMValueRef],
+        func: Function<'ctx>,
+        params: &[Value<'ctx>],
         name: &str,
-    ) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
+    ) -> Value<'ctx> {
+        let val_name = SmallCStr::new(name);
+        let mut params = value_refs(params);
         unsafe {
-            LLVMBuildCall(
+            let call = LLVMBuildCall(
                 self.llvm_builder,
-                func,
+                func.as_ref(),
                 params.as_mut_ptr(),
                 params.len() as u32,
-                val_name_ptr,
-            )
+                val_name.as_ptr(),
+            );
+            // calling with a convention other than the callee's is undefined behaviour
+            LLVMSetInstructionCallConv(call, LLVMGetFunctionCallConv(func.as_ref()));
+            Value::from_ptr(self.with_fast_math(call))
+        }
+    }
+
+    // Calls through a function pointer, such as a callback or a vtable slot.
+    // The call uses the C convention; set another on the result's CallSite.
+    #[inline]
+    pub fn build_indirect_call(
+        &self,
+        func_type: FunctionType<'ctx>,
+        callee: PointerValue<'ctx>,
+        params: &[Value<'ctx>],
+    ) -> Value<'ctx> {
+        self.build_indirect_call_with_name(func_type, callee, params, "")
+    }
+
+    pub fn build_indirect_call_with_name(
+        &self,
+        func_type: FunctionType<'ctx>,
+        callee: PointerValue<'ctx>,
+        params: &[Value<'ctx>],
+        name: &str,
+    ) -> Value<'ctx> {
+        let val_name = SmallCStr::new(name);
+        let mut params = value_refs(params);
+        unsafe {
+            let call = LLVMBuildCall2(
+                self.llvm_builder,
+                func_type.as_ref(),
+                callee.as_value_ref(),
+                params.as_mut_ptr(),
+                params.len() as u32,
+                val_name.as_ptr(),
+            );
+            Value::from_ptr(self.with_fast_math(call))
         }
     }
 
     #[inline]
-    pub fn build_tail_call(&self, func: LLVMValueRef, params: &mut [LLVMValueRef]) -> LLVMValueRef {
+    pub fn build_tail_call(&self, func: Function<'ctx>, params: &[Value<'ctx>]) -> Value<'ctx> {
         self.build_tail_call_with_name(func, params, "")
     }
 
     #[inline]
     pub fn build_tail_call_with_name(
         &self,
-        func: LLVMValueRef,
-        params: &mut [LLVMValueRef],
+        func: Function<'ctx>,
+        params: &[Value<'ctx>],
         name: &str,
-    ) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
+    ) -> Value<'ctx> {
+        let val_name = SmallCStr::new(name);
+        let mut params = value_refs(params);
         unsafe {
             let call = LLVMBuildCall(
                 self.llvm_builder,
-                func,
+                func.as_ref(),
                 params.as_mut_ptr(),
                 params.len() as u32,
-                val_name_ptr,
+                val_name.as_ptr(),
             );
+            LLVMSetInstructionCallConv(call, LLVMGetFunctionCallConv(func.as_ref()));
             LLVMSetTailCall(call, 1); // set tail call opt
-            call
+            Value::from_ptr(self.with_fast_math(call))
+        }
+    }
+
+    #[inline]
+    pub fn build_indirect_tail_call(
+        &self,
+        func_type: FunctionType<'ctx>,
+        callee: PointerValue<'ctx>,
+        params: &[Value<'ctx>],
+    ) -> Value<'ctx> {
+        self.build_indirect_tail_call_with_name(func_type, callee, params, "")
+    }
+
+    pub fn build_indirect_tail_call_with_name(
+        &self,
+        func_type: FunctionType<'ctx>,
+        callee: PointerValue<'ctx>,
+        params: &[Value<'ctx>],
+        name: &str,
+    ) -> Value<'ctx> {
+        let call = self.build_indirect_call_with_name(func_type, callee, params, name);
+        unsafe { LLVMSetTailCall(call.as_ref(), 1) }
+        call
+    }
+
+    // A call that continues at `then_block` when the callee returns normally and
+    // at `catch_block`, which must start with a landing pad, when it unwinds.
+    #[inline]
+    pub fn build_invoke(
+        &self,
+        func: Function<'ctx>,
+        params: &[Value<'ctx>],
+        then_block: BasicBlock<'ctx>,
+        catch_block: BasicBlock<'ctx>,
+    ) -> Value<'ctx> {
+        self.build_invoke_with_name(func, params, then_block, catch_block, "")
+    }
+
+    #[inline]
+    pub fn build_invoke_with_name(
+        &self,
+        func: Function<'ctx>,
+        params: &[Value<'ctx>],
+        then_block: BasicBlock<'ctx>,
+        catch_block: BasicBlock<'ctx>,
+        name: &str,
+    ) -> Value<'ctx> {
+        let val_name = SmallCStr::new(name);
+        let mut params = value_refs(params);
+        unsafe {
+            let invoke = LLVMBuildInvoke2(
+                self.llvm_builder,
+                LLVMGlobalGetValueType(func.as_ref()),
+                func.as_ref(),
+                params.as_mut_ptr(),
+                params.len() as u32,
+                then_block.as_ref(),
+                catch_block.as_ref(),
+                val_name.as_ptr(),
+            );
+            LLVMSetInstructionCallConv(invoke, LLVMGetFunctionCallConv(func.as_ref()));
+            Value::from_ptr(invoke)
+        }
+    }
+
+    // Like build_indirect_call, for a callee that may throw.
+    #[inline]
+    pub fn build_indirect_invoke(
+        &self,
+        func_type: FunctionType<'ctx>,
+        callee: PointerValue<'ctx>,
+        params: &[Value<'ctx>],
+        then_block: BasicBlock<'ctx>,
+        catch_block: BasicBlock<'ctx>,
+    ) -> Value<'ctx> {
+        self.build_indirect_invoke_with_name(func_type, callee, params, then_block, catch_block, "")
+    }
+
+    pub fn build_indirect_invoke_with_name(
+        &self,
+        func_type: FunctionType<'ctx>,
+        callee: PointerValue<'ctx>,
+        params: &[Value<'ctx>],
+        then_block: BasicBlock<'ctx>,
+        catch_block: BasicBlock<'ctx>,
+        name: &str,
+    ) -> Value<'ctx> {
+        let val_name = SmallCStr::new(name);
+        let mut params = value_refs(params);
+        unsafe {
+            Value::from_ptr(LLVMBuildInvoke2(
+                self.llvm_builder,
+                func_type.as_ref(),
+                callee.as_value_ref(),
+                params.as_mut_ptr(),
+                params.len() as u32,
+                then_block.as_ref(),
+                catch_block.as_ref(),
+                val_name.as_ptr(),
+            ))
+        }
+    }
+
+    // `clauses` are the type infos to catch (or filter arrays), and `is_cleanup`
+    // makes the pad run even when none of them match. `typ` is usually
+    // { i8*, i32 }: the exception and the selector of the matching clause.
+    #[inline]
+    pub fn build_landing_pad<T: AnyType<'ctx>>(
+        &self,
+        typ: T,
+        personality: Function<'ctx>,
+        clauses: &[Value<'ctx>],
+        is_cleanup: bool,
+    ) -> Value<'ctx> {
+        self.build_landing_pad_with_name(typ, personality, clauses, is_cleanup, "")
+    }
+
+    pub fn build_landing_pad_with_name<T: AnyType<'ctx>>(
+        &self,
+        typ: T,
+        personality: Function<'ctx>,
+        clauses: &[Value<'ctx>],
+        is_cleanup: bool,
+        name: &str,
+    ) -> Value<'ctx> {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            let landing_pad = LLVMBuildLandingPad(
+                self.llvm_builder,
+                typ.as_type_ref(),
+                personality.as_ref(),
+                clauses.len() as u32,
+                val_name.as_ptr(),
+            );
+            for clause in clauses {
+                LLVMAddClause(landing_pad, clause.as_ref());
+            }
+            LLVMSetCleanup(landing_pad, is_cleanup as i32);
+            Value::from_ptr(landing_pad)
+        }
+    }
+
+    // Carries on unwinding with the value a landing pad caught.
+    #[inline]
+    pub fn build_resume<V: AnyValue<'ctx>>(&self, value: V) -> Value<'ctx> {
+        unsafe { Value::from_ptr(LLVMBuildResume(self.llvm_builder, value.as_value_ref())) }
+    }
+
+    // The funclet pads below are what Windows' SEH and C++ personalities use.
+    // A `parent_pad` of None means the pad is not nested in another one.
+    #[inline]
+    pub fn build_cleanup_pad(
+        &self,
+        parent_pad: Option<Value<'ctx>>,
+        args: &[Value<'ctx>],
+    ) -> Value<'ctx> {
+        self.build_cleanup_pad_with_name(parent_pad, args, "")
+    }
+
+    pub fn build_cleanup_pad_with_name(
+        &self,
+        parent_pad: Option<Value<'ctx>>,
+        args: &[Value<'ctx>],
+        name: &str,
+    ) -> Value<'ctx> {
+        let val_name = SmallCStr::new(name);
+        let mut args = value_refs(args);
+        unsafe {
+            Value::from_ptr(LLVMBuildCleanupPad(
+                self.llvm_builder,
+                parent_pad.map_or(ptr::null_mut(), |pad| pad.as_ref()),
+                args.as_mut_ptr(),
+                args.len() as u32,
+                val_name.as_ptr(),
+            ))
+        }
+    }
+
+    #[inline]
+    pub fn build_catch_pad(&self, catch_switch: Value<'ctx>, args: &[Value<'ctx>]) -> Value<'ctx> {
+        self.build_catch_pad_with_name(catch_switch, args, "")
+    }
+
+    pub fn build_catch_pad_with_name(
+        &self,
+        catch_switch: Value<'ctx>,
+        args: &[Value<'ctx>],
+        name: &str,
+    ) -> Value<'ctx> {
+        let val_name = SmallCStr::new(name);
+        let mut args = value_refs(args);
+        unsafe {
+            Value::from_ptr(LLVMBuildCatchPad(
+                self.llvm_builder,
+                catch_switch.as_ref(),
+                args.as_mut_ptr(),
+                args.len() as u32,
+                val_name.as_ptr(),
+            ))
+        }
+    }
+
+    // Picks one of `handlers`, each starting with a catch pad. An `unwind` of
+    // None passes unhandled exceptions on to the caller.
+    #[inline]
+    pub fn build_catch_switch(
+        &self,
+        parent_pad: Option<Value<'ctx>>,
+        unwind: Option<BasicBlock<'ctx>>,
+        handlers: &[BasicBlock<'ctx>],
+    ) -> Value<'ctx> {
+        self.build_catch_switch_with_name(parent_pad, unwind, handlers, "")
+    }
+
+    pub fn build_catch_switch_with_name(
+        &self,
+        parent_pad: Option<Value<'ctx>>,
+        unwind: Option<BasicBlock<'ctx>>,
+        handlers: &[BasicBlock<'ctx>],
+        name: &str,
+    ) -> Value<'ctx> {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            let catch_switch = LLVMBuildCatchSwitch(
+                self.llvm_builder,
+                parent_pad.map_or(ptr::null_mut(), |pad| pad.as_ref()),
+                unwind.map_or(ptr::null_mut(), |block| block.as_ref()),
+                handlers.len() as u32,
+                val_name.as_ptr(),
+            );
+            for handler in handlers {
+                LLVMAddHandler(catch_switch, handler.as_ref());
+            }
+            Value::from_ptr(catch_switch)
+        }
+    }
+
+    #[inline]
+    pub fn build_catch_ret(&self, catch_pad: Value<'ctx>, block: BasicBlock<'ctx>) -> Value<'ctx> {
+        unsafe {
+            Value::from_ptr(LLVMBuildCatchRet(
+                self.llvm_builder,
+                catch_pad.as_ref(),
+                block.as_ref(),
+            ))
+        }
+    }
+
+    #[inline]
+    pub fn build_cleanup_ret(
+        &self,
+        cleanup_pad: Value<'ctx>,
+        unwind: Option<BasicBlock<'ctx>>,
+    ) -> Value<'ctx> {
+        unsafe {
+            Value::from_ptr(LLVMBuildCleanupRet(
+                self.llvm_builder,
+                cleanup_pad.as_ref(),
+                unwind.map_or(ptr::null_mut(), |block| block.as_ref()),
+            ))
         }
     }
 
     #[inline]
-    pub fn build_br(&self, dest_block: LLVMBasicBlockRef) -> LLVMValueRef {
-        unsafe { LLVMBuildBr(self.llvm_builder, dest_block) }
+    pub fn build_br(&self, dest_block: BasicBlock<'ctx>) -> Value<'ctx> {
+        unsafe { Value::from_ptr(LLVMBuildBr(self.llvm_builder, dest_block.as_ref())) }
     }
 
     #[inline]
     pub fn build_cond_br(
         &self,
-        condition: LLVMValueRef,
-        then_block: LLVMBasicBlockRef,
-        else_block: LLVMBasicBlockRef,
-    ) -> LLVMValueRef {
-        unsafe { LLVMBuildCondBr(self.llvm_builder, condition, then_block, else_block) }
+        condition: IntValue<'ctx>,
+        then_block: BasicBlock<'ctx>,
+        else_block: BasicBlock<'ctx>,
+    ) -> Value<'ctx> {
+        unsafe {
+            Value::from_ptr(LLVMBuildCondBr(
+                self.llvm_builder,
+                condition.as_value_ref(),
+                then_block.as_ref(),
+                else_block.as_ref(),
+            ))
+        }
     }
 
     #[inline]
     pub fn build_switch(
         &self,
-        value: LLVMValueRef,
-        default: LLVMBasicBlockRef,
-        cases: &[(LLVMValueRef, LLVMBasicBlockRef)],
-    ) -> LLVMValueRef {
+        value: IntValue<'ctx>,
+        default: BasicBlock<'ctx>,
+        cases: &[(IntValue<'ctx>, BasicBlock<'ctx>)],
+    ) -> Value<'ctx> {
         unsafe {
-            let switch = LLVMBuildSwitch(self.llvm_builder, value, default, cases.len() as u32);
+            let switch = LLVMBuildSwitch(
+                self.llvm_builder,
+                value.as_value_ref(),
+                default.as_ref(),
+                cases.len() as u32,
+            );
             for case in cases {
-                LLVMAddCase(switch, case.0, case.1);
+                LLVMAddCase(switch, case.0.as_value_ref(), case.1.as_ref());
             }
-            switch
+            Value::from_ptr(switch)
         }
     }
 
     #[inline]
-    pub fn build_sext(&self, value: LLVMValueRef, dest_type: LLVMTypeRef) -> LLVMValueRef {
+    pub fn build_sext<T: IntMathValue<'ctx>>(&self, value: T, dest_type: T::BaseType) -> T {
         self.build_sext_with_name(value, dest_type, "")
     }
 
     #[inline]
-    pub fn build_sext_with_name(
+    pub fn build_sext_with_name<T: IntMathValue<'ctx>>(
         &self,
-        value: LLVMValueRef,
-        dest_type: LLVMTypeRef,
+        value: T,
+        dest_type: T::BaseType,
         name: &str,
-    ) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildSExt(self.llvm_builder, value, dest_type, val_name_ptr) }
+    ) -> T {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            T::from_value_ref(LLVMBuildSExt(
+                self.llvm_builder,
+                value.as_value_ref(),
+                dest_type.as_type_ref(),
+                val_name.as_ptr(),
+            ))
+        }
     }
 
     #[inline]
     pub fn build_inbounds_gep(
         &self,
-        target: LLVMValueRef,
-        indices: &mut [LLVMValueRef],
-    ) -> LLVMValueRef {
+        target: PointerValue<'ctx>,
+        indices: &[IntValue<'ctx>],
+    ) -> PointerValue<'ctx> {
         self.build_inbounds_gep_with_name(target, indices, "")
     }
 
     #[inline]
     pub fn build_inbounds_gep_with_name(
         &self,
-        target: LLVMValueRef,
-        indices: &mut [LLVMValueRef],
+        target: PointerValue<'ctx>,
+        indices: &[IntValue<'ctx>],
         name: &str,
-    ) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
+    ) -> PointerValue<'ctx> {
+        let val_name = SmallCStr::new(name);
+        let mut indices = value_refs(indices);
         unsafe {
-            LLVMBuildInBoundsGEP(
+            PointerValue::from_value_ref(LLVMBuildInBoundsGEP(
                 self.llvm_builder,
-                target,
+                target.as_value_ref(),
                 indices.as_mut_ptr(),
                 indices.len() as u32,
-                val_name_ptr,
-            )
+                val_name.as_ptr(),
+            ))
         }
     }
 
     #[inline]
-    pub fn build_phi(&self, typ: LLVMTypeRef) -> Phi {
-        Phi::new(self.llvm_builder, typ, "")
+    pub fn build_phi<T: AnyType<'ctx>>(&self, typ: T) -> Phi<'ctx> {
+        self.build_phi_with_name(typ, "")
     }
 
     #[inline]
-    pub fn build_phi_with_name(&self, typ: LLVMTypeRef, name: &str) -> Phi {
-        Phi::new(self.llvm_builder, typ, name)
+    pub fn build_phi_with_name<T: AnyType<'ctx>>(&self, typ: T, name: &str) -> Phi<'ctx> {
+        let phi = Phi::new(self.llvm_builder, typ.as_type_ref(), name);
+        self.with_fast_math(phi.as_ref());
+        phi
     }
 
     #[inline]
-    pub fn build_si_to_fp(&self, val: LLVMValueRef, typ: LLVMTypeRef) -> LLVMValueRef {
+    pub fn build_si_to_fp(&self, val: IntValue<'ctx>, typ: FloatType<'ctx>) -> FloatValue<'ctx> {
         self.build_si_to_fp_with_name(val, typ, "")
     }
 
-    #[inline]
-    pub fn build_ui_to_fp(&self, val: LLVMValueRef, typ: LLVMTypeRef) -> LLVMValueRef {
-        self.build_ui_to_fp_with_name(val, typ, "")
-    }
-
     #[inline]
     pub fn build_si_to_fp_with_name(
         &self,
-        val: LLVMValueRef,
-        typ: LLVMTypeRef,
+        val: IntValue<'ctx>,
+        typ: FloatType<'ctx>,
         name: &str,
-    ) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildSIToFP(self.llvm_builder, val, typ, val_name_ptr) }
+    ) -> FloatValue<'ctx> {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            FloatValue::from_value_ref(LLVMBuildSIToFP(
+                self.llvm_builder,
+                val.as_value_ref(),
+                typ.as_type_ref(),
+                val_name.as_ptr(),
+            ))
+        }
+    }
+
+    #[inline]
+    pub fn build_ui_to_fp(&self, val: IntValue<'ctx>, typ: FloatType<'ctx>) -> FloatValue<'ctx> {
+        self.build_ui_to_fp_with_name(val, typ, "")
     }
 
     #[inline]
     pub fn build_ui_to_fp_with_name(
         &self,
-        val: LLVMValueRef,
-        typ: LLVMTypeRef,
+        val: IntValue<'ctx>,
+        typ: FloatType<'ctx>,
         name: &str,
-    ) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildUIToFP(self.llvm_builder, val, typ, val_name_ptr) }
+    ) -> FloatValue<'ctx> {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            FloatValue::from_value_ref(LLVMBuildUIToFP(
+                self.llvm_builder,
+                val.as_value_ref(),
+                typ.as_type_ref(),
+                val_name.as_ptr(),
+            ))
+        }
     }
 
     #[inline]
-    pub fn build_fp_to_si(&self, val: LLVMValueRef, typ: LLVMTypeRef) -> LLVMValueRef {
+    pub fn build_fp_to_si(&self, val: FloatValue<'ctx>, typ: IntType<'ctx>) -> IntValue<'ctx> {
         self.build_fp_to_si_with_name(val, typ, "")
     }
 
-    #[inline]
-    pub fn build_fp_to_ui(&self, val: LLVMValueRef, typ: LLVMTypeRef) -> LLVMValueRef {
-        self.build_fp_to_ui_with_name(val, typ, "")
-    }
-
     #[inline]
     pub fn build_fp_to_si_with_name(
         &self,
-        val: LLVMValueRef,
-        typ: LLVMTypeRef,
+        val: FloatValue<'ctx>,
+        typ: IntType<'ctx>,
         name: &str,
-    ) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildFPToSI(self.llvm_builder, val, typ, val_name_ptr) }
+    ) -> IntValue<'ctx> {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            IntValue::from_value_ref(LLVMBuildFPToSI(
+                self.llvm_builder,
+                val.as_value_ref(),
+                typ.as_type_ref(),
+                val_name.as_ptr(),
+            ))
+        }
+    }
+
+    #[inline]
+    pub fn build_fp_to_ui(&self, val: FloatValue<'ctx>, typ: IntType<'ctx>) -> IntValue<'ctx> {
+        self.build_fp_to_ui_with_name(val, typ, "")
     }
 
     #[inline]
     pub fn build_fp_to_ui_with_name(
         &self,
-        val: LLVMValueRef,
-        typ: LLVMTypeRef,
+        val: FloatValue<'ctx>,
+        typ: IntType<'ctx>,
         name: &str,
-    ) -> LLVMValueRef {
-        let val_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMBuildFPToUI(self.llvm_builder, val, typ, val_name_ptr) }
+    ) -> IntValue<'ctx> {
+        let val_name = SmallCStr::new(name);
+        unsafe {
+            IntValue::from_value_ref(LLVMBuildFPToUI(
+                self.llvm_builder,
+                val.as_value_ref(),
+                typ.as_type_ref(),
+                val_name.as_ptr(),
+            ))
+        }
     }
 }
 
-impl Drop for Builder {
+impl Default for Builder<'static> {
+    fn default() -> Self {
+        Builder::new()
+    }
+}
+
+impl<'ctx> Drop for Builder<'ctx> {
     #[inline]
     fn drop(&mut self) {
         unsafe { LLVMDisposeBuilder(self.llvm_builder) }
     }
 }
+
+// Returned by Builder::save_insert_point.
+#[derive(Debug)]
+pub struct InsertPointGuard<'b, 'ctx: 'b> {
+    builder: &'b Builder<'ctx>,
+    insert_point: InsertPoint<'ctx>,
+}
+
+impl<'b, 'ctx> Deref for InsertPointGuard<'b, 'ctx> {
+    type Target = Builder<'ctx>;
+
+    #[inline]
+    fn deref(&self) -> &Builder<'ctx> {
+        self.builder
+    }
+}
+
+impl<'b, 'ctx> Drop for InsertPointGuard<'b, 'ctx> {
+    #[inline]
+    fn drop(&mut self) {
+        self.builder.restore_insert_point(self.insert_point)
+    }
+}
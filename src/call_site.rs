@@ -0,0 +1,104 @@
+extern crate llvm_sys;
+
+use self::llvm_sys::core::*;
+use self::llvm_sys::prelude::*;
+use attributes::{Attribute, AttributeLoc, CallConv};
+use context::ContextMarker;
+use std::convert::TryFrom;
+use std::marker::PhantomData;
+use values::{AnyValue, Value};
+
+// A call instruction, for setting attributes and the calling convention per call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CallSite<'ctx> {
+    llvm_call: LLVMValueRef,
+    _context: ContextMarker<'ctx>,
+}
+
+impl<'ctx> CallSite<'ctx> {
+    pub fn as_ref(&self) -> LLVMValueRef {
+        self.llvm_call
+    }
+
+    #[inline]
+    fn context_ref(&self) -> LLVMContextRef {
+        unsafe { LLVMGetTypeContext(LLVMTypeOf(self.llvm_call)) }
+    }
+
+    #[inline]
+    pub fn add_attribute(&self, loc: AttributeLoc, attribute: Attribute<'ctx>) {
+        unsafe {
+            LLVMAddCallSiteAttribute(
+                self.llvm_call,
+                loc.into(),
+                attribute.create(self.context_ref()),
+            )
+        }
+    }
+
+    #[inline]
+    pub fn remove_attribute(&self, loc: AttributeLoc, attribute: Attribute<'ctx>) {
+        unsafe { LLVMRemoveCallSiteEnumAttribute(self.llvm_call, loc.into(), attribute.kind_id()) }
+    }
+
+    #[inline]
+    pub fn has_attribute(&self, loc: AttributeLoc, attribute: Attribute<'ctx>) -> bool {
+        unsafe {
+            !LLVMGetCallSiteEnumAttribute(self.llvm_call, loc.into(), attribute.kind_id()).is_null()
+        }
+    }
+
+    #[inline]
+    pub fn set_call_conv(&self, call_conv: CallConv) {
+        unsafe { LLVMSetInstructionCallConv(self.llvm_call, call_conv.into()) }
+    }
+
+    #[inline]
+    pub fn get_call_conv(&self) -> CallConv {
+        unsafe { LLVMGetInstructionCallConv(self.llvm_call).into() }
+    }
+
+    #[inline]
+    pub fn set_tail_call(&self, tail_call: bool) {
+        unsafe { LLVMSetTailCall(self.llvm_call, if tail_call { 1 } else { 0 }) }
+    }
+
+    #[inline]
+    pub fn is_tail_call(&self) -> bool {
+        unsafe { LLVMIsTailCall(self.llvm_call) == 1 }
+    }
+}
+
+impl<'ctx> AnyValue<'ctx> for CallSite<'ctx> {
+    #[inline]
+    fn as_value_ref(&self) -> LLVMValueRef {
+        self.llvm_call
+    }
+
+    #[inline]
+    unsafe fn from_value_ref(value: LLVMValueRef) -> Self {
+        CallSite {
+            llvm_call: value,
+            _context: PhantomData,
+        }
+    }
+}
+
+impl<'ctx> From<CallSite<'ctx>> for Value<'ctx> {
+    #[inline]
+    fn from(call: CallSite<'ctx>) -> Value<'ctx> {
+        Value::from_ptr(call.llvm_call)
+    }
+}
+
+impl<'ctx> TryFrom<Value<'ctx>> for CallSite<'ctx> {
+    type Error = Value<'ctx>;
+
+    fn try_from(value: Value<'ctx>) -> Result<Self, Self::Error> {
+        if value.is_call_site() {
+            Ok(unsafe { CallSite::from_value_ref(value.as_ref()) })
+        } else {
+            Err(value)
+        }
+    }
+}
@@ -0,0 +1,381 @@
+extern crate llvm_sys;
+
+use self::llvm_sys::prelude::LLVMMemoryBufferRef;
+use self::llvm_sys::target::*;
+use self::llvm_sys::target_machine::*;
+use memory_buffer::MemoryBuffer;
+use message::take_message;
+use module::Module;
+use small_cstr::SmallCStr;
+use std::ffi::CStr;
+use std::os::raw::c_char;
+use std::ptr;
+use CodegenLevel;
+
+fn static_string(string: *const c_char) -> String {
+    if string.is_null() {
+        String::new()
+    } else {
+        unsafe { CStr::from_ptr(string).to_string_lossy().into_owned() }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RelocMode {
+    Default,
+    Static,
+    PIC,
+    DynamicNoPic,
+    ROPI,
+    RWPI,
+    ROPIRWPI,
+}
+
+impl From<RelocMode> for LLVMRelocMode {
+    fn from(reloc_mode: RelocMode) -> LLVMRelocMode {
+        match reloc_mode {
+            RelocMode::Default => LLVMRelocMode::LLVMRelocDefault,
+            RelocMode::Static => LLVMRelocMode::LLVMRelocStatic,
+            RelocMode::PIC => LLVMRelocMode::LLVMRelocPIC,
+            RelocMode::DynamicNoPic => LLVMRelocMode::LLVMRelocDynamicNoPic,
+            RelocMode::ROPI => LLVMRelocMode::LLVMRelocROPI,
+            RelocMode::RWPI => LLVMRelocMode::LLVMRelocRWPI,
+            RelocMode::ROPIRWPI => LLVMRelocMode::LLVMRelocROPI_RWPI,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CodeModel {
+    Default,
+    JITDefault,
+    Tiny,
+    Small,
+    Kernel,
+    Medium,
+    Large,
+}
+
+impl From<CodeModel> for LLVMCodeModel {
+    fn from(code_model: CodeModel) -> LLVMCodeModel {
+        match code_model {
+            CodeModel::Default => LLVMCodeModel::LLVMCodeModelDefault,
+            CodeModel::JITDefault => LLVMCodeModel::LLVMCodeModelJITDefault,
+            CodeModel::Tiny => LLVMCodeModel::LLVMCodeModelTiny,
+            CodeModel::Small => LLVMCodeModel::LLVMCodeModelSmall,
+            CodeModel::Kernel => LLVMCodeModel::LLVMCodeModelKernel,
+            CodeModel::Medium => LLVMCodeModel::LLVMCodeModelMedium,
+            CodeModel::Large => LLVMCodeModel::LLVMCodeModelLarge,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FileType {
+    Assembly,
+    Object,
+}
+
+impl From<FileType> for LLVMCodeGenFileType {
+    fn from(file_type: FileType) -> LLVMCodeGenFileType {
+        match file_type {
+            FileType::Assembly => LLVMCodeGenFileType::LLVMAssemblyFile,
+            FileType::Object => LLVMCodeGenFileType::LLVMObjectFile,
+        }
+    }
+}
+
+// One of the backends compiled into LLVM. Targets are registered by the
+// LLVM::initialize functions and live for the rest of the process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Target {
+    llvm_target: LLVMTargetRef,
+}
+
+impl Target {
+    pub fn from_ptr(target_ptr: LLVMTargetRef) -> Target {
+        Target {
+            llvm_target: target_ptr,
+        }
+    }
+
+    pub fn as_ref(&self) -> LLVMTargetRef {
+        self.llvm_target
+    }
+
+    pub fn from_triple(triple: &str) -> Result<Target, String> {
+        let triple = SmallCStr::new(triple);
+        let mut target: LLVMTargetRef = ptr::null_mut();
+        let mut error: *mut c_char = ptr::null_mut();
+        let failed = unsafe { LLVMGetTargetFromTriple(triple.as_ptr(), &mut target, &mut error) };
+        if failed == 1 {
+            // error
+            Err(take_message(error))
+        } else {
+            // success
+            Ok(Target::from_ptr(target))
+        }
+    }
+
+    // Looks a target up by its short name, such as "x86-64" or "aarch64".
+    pub fn from_name(name: &str) -> Option<Target> {
+        let name = SmallCStr::new(name);
+        let target = unsafe { LLVMGetTargetFromName(name.as_ptr()) };
+        if target.is_null() {
+            None
+        } else {
+            Some(Target::from_ptr(target))
+        }
+    }
+
+    // Every target that has been initialized so far.
+    pub fn all() -> Vec<Target> {
+        let mut targets = Vec::new();
+        let mut target = unsafe { LLVMGetFirstTarget() };
+        while !target.is_null() {
+            targets.push(Target::from_ptr(target));
+            target = unsafe { LLVMGetNextTarget(target) };
+        }
+        targets
+    }
+
+    // The triple of the machine LLVM was built for.
+    pub fn default_triple() -> String {
+        take_message(unsafe { LLVMGetDefaultTargetTriple() })
+    }
+
+    pub fn normalize_triple(triple: &str) -> String {
+        let triple = SmallCStr::new(triple);
+        take_message(unsafe { LLVMNormalizeTargetTriple(triple.as_ptr()) })
+    }
+
+    #[inline]
+    pub fn get_name(&self) -> String {
+        static_string(unsafe { LLVMGetTargetName(self.llvm_target) })
+    }
+
+    #[inline]
+    pub fn get_description(&self) -> String {
+        static_string(unsafe { LLVMGetTargetDescription(self.llvm_target) })
+    }
+
+    #[inline]
+    pub fn has_jit(&self) -> bool {
+        unsafe { LLVMTargetHasJIT(self.llvm_target) != 0 }
+    }
+
+    #[inline]
+    pub fn has_target_machine(&self) -> bool {
+        unsafe { LLVMTargetHasTargetMachine(self.llvm_target) != 0 }
+    }
+
+    #[inline]
+    pub fn has_asm_backend(&self) -> bool {
+        unsafe { LLVMTargetHasAsmBackend(self.llvm_target) != 0 }
+    }
+
+    #[inline]
+    pub fn create_target_machine(&self, triple: &str) -> TargetMachineBuilder {
+        TargetMachineBuilder::new(*self, triple)
+    }
+}
+
+// Collects the settings of a TargetMachine. Everything but the target and
+// triple starts out at LLVM's defaults.
+#[derive(Debug, Clone)]
+pub struct TargetMachineBuilder {
+    target: Target,
+    triple: String,
+    cpu: String,
+    features: String,
+    opt_level: CodegenLevel,
+    reloc_mode: RelocMode,
+    code_model: CodeModel,
+}
+
+impl TargetMachineBuilder {
+    pub fn new(target: Target, triple: &str) -> TargetMachineBuilder {
+        TargetMachineBuilder {
+            target,
+            triple: triple.to_string(),
+            cpu: String::new(),
+            features: String::new(),
+            opt_level: CodegenLevel::O2,
+            reloc_mode: RelocMode::Default,
+            code_model: CodeModel::Default,
+        }
+    }
+
+    #[inline]
+    pub fn cpu(mut self, cpu: &str) -> TargetMachineBuilder {
+        self.cpu = cpu.to_string();
+        self
+    }
+
+    // A comma separated list such as "+avx2,-sse4a".
+    #[inline]
+    pub fn features(mut self, features: &str) -> TargetMachineBuilder {
+        self.features = features.to_string();
+        self
+    }
+
+    #[inline]
+    pub fn opt_level(mut self, opt_level: CodegenLevel) -> TargetMachineBuilder {
+        self.opt_level = opt_level;
+        self
+    }
+
+    #[inline]
+    pub fn reloc_mode(mut self, reloc_mode: RelocMode) -> TargetMachineBuilder {
+        self.reloc_mode = reloc_mode;
+        self
+    }
+
+    #[inline]
+    pub fn code_model(mut self, code_model: CodeModel) -> TargetMachineBuilder {
+        self.code_model = code_model;
+        self
+    }
+
+    pub fn build(self) -> Result<TargetMachine, String> {
+        if !self.target.has_target_machine() {
+            return Err(format!(
+                "target {} cannot generate code",
+                self.target.get_name()
+            ));
+        }
+        let triple = SmallCStr::new(&self.triple);
+        let cpu = SmallCStr::new(&self.cpu);
+        let features = SmallCStr::new(&self.features);
+        let target_machine = unsafe {
+            LLVMCreateTargetMachine(
+                self.target.as_ref(),
+                triple.as_ptr(),
+                cpu.as_ptr(),
+                features.as_ptr(),
+                self.opt_level.into(),
+                self.reloc_mode.into(),
+                self.code_model.into(),
+            )
+        };
+        if target_machine.is_null() {
+            Err(format!(
+                "could not create a target machine for {}",
+                self.triple
+            ))
+        } else {
+            Ok(TargetMachine {
+                llvm_target_machine: target_machine,
+            })
+        }
+    }
+}
+
+// Generates code for one target triple, CPU and feature set. A single machine
+// can compile any number of modules.
+#[derive(Debug)]
+pub struct TargetMachine {
+    llvm_target_machine: LLVMTargetMachineRef,
+}
+
+impl TargetMachine {
+    pub fn as_ref(&self) -> LLVMTargetMachineRef {
+        self.llvm_target_machine
+    }
+
+    #[inline]
+    pub fn get_target(&self) -> Target {
+        Target::from_ptr(unsafe { LLVMGetTargetMachineTarget(self.llvm_target_machine) })
+    }
+
+    #[inline]
+    pub fn get_triple(&self) -> String {
+        take_message(unsafe { LLVMGetTargetMachineTriple(self.llvm_target_machine) })
+    }
+
+    #[inline]
+    pub fn get_cpu(&self) -> String {
+        take_message(unsafe { LLVMGetTargetMachineCPU(self.llvm_target_machine) })
+    }
+
+    #[inline]
+    pub fn get_features(&self) -> String {
+        take_message(unsafe { LLVMGetTargetMachineFeatureString(self.llvm_target_machine) })
+    }
+
+    pub fn get_data_layout(&self) -> String {
+        unsafe {
+            let data_layout = LLVMCreateTargetDataLayout(self.llvm_target_machine);
+            let string = take_message(LLVMCopyStringRepOfTargetData(data_layout));
+            LLVMDisposeTargetData(data_layout);
+            string
+        }
+    }
+
+    // Stamps the module with this machine's triple and data layout, which the
+    // optimizer relies on and code generation expects to match.
+    pub fn configure_module(&self, module: &Module) {
+        module.set_target_triple(&self.get_triple());
+        module.set_data_layout(&self.get_data_layout());
+    }
+
+    // Whether emitted assembly carries explanatory comments.
+    #[inline]
+    pub fn set_asm_verbosity(&self, verbose: bool) {
+        unsafe { LLVMSetTargetMachineAsmVerbosity(self.llvm_target_machine, verbose as i32) }
+    }
+
+    pub fn emit_to_file(
+        &self,
+        module: &Module,
+        filename: &str,
+        file_type: FileType,
+    ) -> Result<(), String> {
+        let fname = SmallCStr::new(filename);
+        let mut error: *mut c_char = ptr::null_mut();
+        let failed = unsafe {
+            LLVMTargetMachineEmitToFile(
+                self.llvm_target_machine,
+                module.as_ref(),
+                fname.as_ptr() as *mut c_char,
+                file_type.into(),
+                &mut error,
+            )
+        };
+        if failed == 1 {
+            // error
+            Err(take_message(error))
+        } else {
+            // success
+            Ok(())
+        }
+    }
+
+    pub fn emit_to_memory(&self, module: &Module, file_type: FileType) -> Result<Vec<u8>, String> {
+        let mut buffer: LLVMMemoryBufferRef = ptr::null_mut();
+        let mut error: *mut c_char = ptr::null_mut();
+        let failed = unsafe {
+            LLVMTargetMachineEmitToMemoryBuffer(
+                self.llvm_target_machine,
+                module.as_ref(),
+                file_type.into(),
+                &mut error,
+                &mut buffer,
+            )
+        };
+        if failed == 1 {
+            // error
+            Err(take_message(error))
+        } else {
+            // success
+            Ok(MemoryBuffer::from_ptr(buffer).as_slice().to_vec())
+        }
+    }
+}
+
+impl Drop for TargetMachine {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe { LLVMDisposeTargetMachine(self.llvm_target_machine) }
+    }
+}
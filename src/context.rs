@@ -5,36 +5,69 @@ extern crate llvm_sys;
 use self::llvm_sys::core::*;
 use self::llvm_sys::prelude::*;
 use builder::Builder;
+use diagnostic::Diagnostic;
 use module::Module;
+use std::marker::PhantomData;
 use std::os::raw::c_uint;
-use struct_type::Struct;
-use LLVM::Type;
+use std::sync::OnceLock;
+use types::{
+    AnyType, ArrayType, FloatType, FunctionType, IntType, PointerType, StructType, Type,
+    VectorType, VoidType,
+};
+use values::{
+    value_refs, AnyValue, ArrayValue, FloatValue, IntValue, PointerValue, Value, VectorValue,
+};
+
+// What every handle holds to tie it to the Context it came from. A plain
+// `&'ctx Context` would make the handles covariant, letting a handle from the
+// longer-lived global context pass for one from any other; a function
+// pointer taking and returning the reference keeps `'ctx` invariant.
+pub(crate) type ContextMarker<'ctx> = PhantomData<fn(&'ctx Context) -> &'ctx Context>;
 
 #[derive(Debug)]
 pub struct Context {
     llvm_context: LLVMContextRef,
+    owned: bool,
 }
 
+// Context is neither Send nor Sync, so the &'static Context handed out stays
+// on the thread that asked for it; the static only holds the pointer.
+struct GlobalContext(Context);
+
+unsafe impl Send for GlobalContext {}
+unsafe impl Sync for GlobalContext {}
+
 #[allow(non_snake_case)]
 impl Context {
-    pub fn global_context() -> Context {
-        let context = unsafe { LLVMGetGlobalContext() };
-        Context {
-            llvm_context: context,
-        }
+    // LLVM's global context lives as long as the program, so it is handed out
+    // as 'static: the only lifetime the LLVM::Type and LLVM::Const handles,
+    // which are made in it, can be used together with.
+    pub fn global_context() -> &'static Context {
+        static GLOBAL: OnceLock<GlobalContext> = OnceLock::new();
+        let global = GLOBAL.get_or_init(|| {
+            GlobalContext(Context {
+                llvm_context: unsafe { LLVMGetGlobalContext() },
+                owned: false,
+            })
+        });
+        &global.0
     }
 
     pub fn new() -> Context {
         let context = unsafe { LLVMContextCreate() };
         Context {
             llvm_context: context,
+            owned: true,
         }
     }
 
+    // Takes the module reference on trust, like the rest of the from_* constructors.
+    #[allow(clippy::not_unsafe_ptr_arg_deref)]
     pub fn from_module(module: LLVMModuleRef) -> Context {
         let context = unsafe { LLVMGetModuleContext(module) };
         Context {
             llvm_context: context,
+            owned: false,
         }
     }
 
@@ -42,215 +75,286 @@ impl Context {
         self.llvm_context
     }
 
-    pub fn create_builder(&self) -> Builder {
-        Builder::new_in_context(self.as_ref())
+    pub fn create_builder<'ctx>(&'ctx self) -> Builder<'ctx> {
+        Builder::new_in_context(self)
+    }
+
+    pub fn create_module<'ctx>(&'ctx self, name: &str) -> Module<'ctx> {
+        Module::new_in_context(name, self)
     }
 
-    pub fn create_module(&self, name: &str) -> Module {
-        Module::new_in_context(name, self.as_ref())
+    #[inline]
+    pub fn parse_ir<'ctx>(&'ctx self, ir: &str) -> Result<Module<'ctx>, Diagnostic> {
+        Module::parse_ir(ir, self)
+    }
+
+    #[inline]
+    pub fn parse_ir_file<'ctx>(&'ctx self, filename: &str) -> Result<Module<'ctx>, Diagnostic> {
+        Module::parse_ir_file(filename, self)
+    }
+
+    #[inline]
+    pub fn function_type<'ctx, T: AnyType<'ctx>>(
+        &'ctx self,
+        return_type: T,
+        param_types: &[Type<'ctx>],
+        is_var_arg: bool,
+    ) -> FunctionType<'ctx> {
+        FunctionType::new(return_type, param_types, is_var_arg)
     }
 
     //
     // get Type
     //
     #[inline]
-    pub fn ArrayType(&self, typ: LLVMTypeRef, count: u32) -> LLVMTypeRef {
-        unsafe { LLVMArrayType(typ, count) }
+    pub fn ArrayType<'ctx, T: AnyType<'ctx>>(&'ctx self, typ: T, count: u32) -> ArrayType<'ctx> {
+        typ.array_type(count)
+    }
+
+    #[inline]
+    pub fn VectorType<'ctx, T: AnyType<'ctx>>(&'ctx self, typ: T, count: u32) -> VectorType<'ctx> {
+        typ.vector_type(count)
     }
 
     #[inline]
-    pub fn StructTypeNamed(&self, name: &str) -> Struct {
-        Struct::new_with_name(self.llvm_context, name)
+    pub fn StructTypeNamed<'ctx>(&'ctx self, name: &str) -> StructType<'ctx> {
+        StructType::new_with_name(self, name)
     }
 
     #[inline]
-    pub fn StructType(&self, fields: &mut [LLVMTypeRef], packed: bool) -> Struct {
-        Struct::new(self.llvm_context, fields, packed)
+    pub fn StructType<'ctx>(&'ctx self, fields: &[Type<'ctx>], packed: bool) -> StructType<'ctx> {
+        StructType::new(self, fields, packed)
     }
 
     #[inline]
-    pub fn VoidType(&self) -> LLVMTypeRef {
-        unsafe { LLVMVoidTypeInContext(self.llvm_context) }
+    pub fn VoidType<'ctx>(&'ctx self) -> VoidType<'ctx> {
+        unsafe { VoidType::from_type_ref(LLVMVoidTypeInContext(self.llvm_context)) }
     }
     #[inline]
-    pub fn IntType(&self, num_bits: c_uint) -> LLVMTypeRef {
-        unsafe { LLVMIntTypeInContext(self.llvm_context, num_bits) }
+    pub fn IntType<'ctx>(&'ctx self, num_bits: c_uint) -> IntType<'ctx> {
+        unsafe { IntType::from_type_ref(LLVMIntTypeInContext(self.llvm_context, num_bits)) }
     }
     #[inline]
-    pub fn Int1Type(&self) -> LLVMTypeRef {
-        unsafe { LLVMInt1TypeInContext(self.llvm_context) }
+    pub fn Int1Type<'ctx>(&'ctx self) -> IntType<'ctx> {
+        unsafe { IntType::from_type_ref(LLVMInt1TypeInContext(self.llvm_context)) }
     }
     #[inline]
-    pub fn Int8Type(&self) -> LLVMTypeRef {
-        unsafe { LLVMInt8TypeInContext(self.llvm_context) }
+    pub fn Int8Type<'ctx>(&'ctx self) -> IntType<'ctx> {
+        unsafe { IntType::from_type_ref(LLVMInt8TypeInContext(self.llvm_context)) }
     }
     #[inline]
-    pub fn Int16Type(&self) -> LLVMTypeRef {
-        unsafe { LLVMInt16TypeInContext(self.llvm_context) }
+    pub fn Int16Type<'ctx>(&'ctx self) -> IntType<'ctx> {
+        unsafe { IntType::from_type_ref(LLVMInt16TypeInContext(self.llvm_context)) }
     }
     #[inline]
-    pub fn Int32Type(&self) -> LLVMTypeRef {
-        unsafe { LLVMInt32TypeInContext(self.llvm_context) }
+    pub fn Int32Type<'ctx>(&'ctx self) -> IntType<'ctx> {
+        unsafe { IntType::from_type_ref(LLVMInt32TypeInContext(self.llvm_context)) }
     }
     #[inline]
-    pub fn Int64Type(&self) -> LLVMTypeRef {
-        unsafe { LLVMInt64TypeInContext(self.llvm_context) }
+    pub fn Int64Type<'ctx>(&'ctx self) -> IntType<'ctx> {
+        unsafe { IntType::from_type_ref(LLVMInt64TypeInContext(self.llvm_context)) }
     }
     #[inline]
-    pub fn Int128Type(&self) -> LLVMTypeRef {
-        unsafe { LLVMInt128TypeInContext(self.llvm_context) }
+    pub fn Int128Type<'ctx>(&'ctx self) -> IntType<'ctx> {
+        unsafe { IntType::from_type_ref(LLVMInt128TypeInContext(self.llvm_context)) }
     }
     #[inline]
-    pub fn HalfType(&self) -> LLVMTypeRef {
-        unsafe { LLVMHalfTypeInContext(self.llvm_context) }
+    pub fn HalfType<'ctx>(&'ctx self) -> FloatType<'ctx> {
+        unsafe { FloatType::from_type_ref(LLVMHalfTypeInContext(self.llvm_context)) }
     }
     #[inline]
-    pub fn FloatType(&self) -> LLVMTypeRef {
-        unsafe { LLVMFloatTypeInContext(self.llvm_context) }
+    pub fn FloatType<'ctx>(&'ctx self) -> FloatType<'ctx> {
+        unsafe { FloatType::from_type_ref(LLVMFloatTypeInContext(self.llvm_context)) }
     }
     #[inline]
-    pub fn DoubleType(&self) -> LLVMTypeRef {
-        unsafe { LLVMDoubleTypeInContext(self.llvm_context) }
+    pub fn DoubleType<'ctx>(&'ctx self) -> FloatType<'ctx> {
+        unsafe { FloatType::from_type_ref(LLVMDoubleTypeInContext(self.llvm_context)) }
     }
     #[inline]
-    pub fn FP128Type(&self) -> LLVMTypeRef {
-        unsafe { LLVMFP128TypeInContext(self.llvm_context) }
+    pub fn FP128Type<'ctx>(&'ctx self) -> FloatType<'ctx> {
+        unsafe { FloatType::from_type_ref(LLVMFP128TypeInContext(self.llvm_context)) }
     }
     #[inline]
-    pub fn X86FP80Type(&self) -> LLVMTypeRef {
-        unsafe { LLVMX86FP80TypeInContext(self.llvm_context) }
+    pub fn X86FP80Type<'ctx>(&'ctx self) -> FloatType<'ctx> {
+        unsafe { FloatType::from_type_ref(LLVMX86FP80TypeInContext(self.llvm_context)) }
     }
     #[inline]
-    pub fn PPCFP128Type(&self) -> LLVMTypeRef {
-        unsafe { LLVMPPCFP128TypeInContext(self.llvm_context) }
+    pub fn PPCFP128Type<'ctx>(&'ctx self) -> FloatType<'ctx> {
+        unsafe { FloatType::from_type_ref(LLVMPPCFP128TypeInContext(self.llvm_context)) }
     }
     #[inline]
-    pub fn X86MMXType(&self) -> LLVMTypeRef {
-        unsafe { LLVMX86MMXTypeInContext(self.llvm_context) }
+    pub fn X86MMXType<'ctx>(&'ctx self) -> Type<'ctx> {
+        unsafe { Type::from_ptr(LLVMX86MMXTypeInContext(self.llvm_context)) }
     }
     #[inline]
-    pub fn LabelType(&self) -> LLVMTypeRef {
-        unsafe { LLVMLabelTypeInContext(self.llvm_context) }
+    pub fn LabelType<'ctx>(&'ctx self) -> Type<'ctx> {
+        unsafe { Type::from_ptr(LLVMLabelTypeInContext(self.llvm_context)) }
     }
     #[inline]
-    pub fn CharPointerType(&self) -> LLVMTypeRef {
-        Type::PointerType(self.Int8Type(), 0)
+    pub fn CharPointerType<'ctx>(&'ctx self) -> PointerType<'ctx> {
+        self.Int8Type().ptr_type(0)
     }
     #[inline]
-    pub fn Int8PointerType(&self) -> LLVMTypeRef {
-        Type::PointerType(self.Int8Type(), 0)
+    pub fn Int8PointerType<'ctx>(&'ctx self) -> PointerType<'ctx> {
+        self.Int8Type().ptr_type(0)
     }
 
     #[inline]
-    pub fn PointerType(&self, typ: LLVMTypeRef) -> LLVMTypeRef {
-        Type::PointerType(typ, 0)
+    pub fn PointerType<'ctx, T: AnyType<'ctx>>(&'ctx self, typ: T) -> PointerType<'ctx> {
+        typ.ptr_type(0)
     }
 
     //
     // define Constant util
     //
     #[inline]
-    pub fn Null(&self, typ: LLVMTypeRef) -> LLVMValueRef {
-        unsafe { LLVMConstNull(typ) }
+    pub fn Null<'ctx, T: AnyType<'ctx>>(&'ctx self, typ: T) -> Value<'ctx> {
+        unsafe { Value::from_ptr(LLVMConstNull(typ.as_type_ref())) }
     }
 
     #[inline]
-    pub fn PointerNull(&self, typ: LLVMTypeRef) -> LLVMValueRef {
-        unsafe { LLVMConstPointerNull(typ) }
+    pub fn PointerNull<'ctx>(&'ctx self, typ: PointerType<'ctx>) -> PointerValue<'ctx> {
+        typ.const_null()
     }
 
     #[inline]
-    pub fn Bitcast(&self, constant: LLVMValueRef, to_type: LLVMTypeRef) -> LLVMValueRef {
-        unsafe { LLVMConstBitCast(constant, to_type) }
+    pub fn Bitcast<'ctx, V: AnyValue<'ctx>, T: AnyType<'ctx>>(
+        &'ctx self,
+        constant: V,
+        to_type: T,
+    ) -> Value<'ctx> {
+        unsafe {
+            Value::from_ptr(LLVMConstBitCast(
+                constant.as_value_ref(),
+                to_type.as_type_ref(),
+            ))
+        }
     }
     #[inline]
-    pub fn SInt(&self, num_bits: c_uint, val: u64) -> LLVMValueRef {
-        unsafe { LLVMConstInt(LLVMIntTypeInContext(self.llvm_context, num_bits), val, 1) }
+    pub fn SInt<'ctx>(&'ctx self, num_bits: c_uint, val: u64) -> IntValue<'ctx> {
+        self.IntType(num_bits).const_int(val, true)
     }
     #[inline]
-    pub fn UInt(&self, num_bits: c_uint, val: u64) -> LLVMValueRef {
-        unsafe { LLVMConstInt(LLVMIntTypeInContext(self.llvm_context, num_bits), val, 0) }
+    pub fn UInt<'ctx>(&'ctx self, num_bits: c_uint, val: u64) -> IntValue<'ctx> {
+        self.IntType(num_bits).const_int(val, false)
     }
     #[inline]
-    pub fn SInt1(&self, val: u64) -> LLVMValueRef {
-        unsafe { LLVMConstInt(LLVMInt1TypeInContext(self.llvm_context), val, 1) }
+    pub fn SInt1<'ctx>(&'ctx self, val: u64) -> IntValue<'ctx> {
+        self.Int1Type().const_int(val, true)
     }
     #[inline]
-    pub fn UInt1(&self, val: u64) -> LLVMValueRef {
-        unsafe { LLVMConstInt(LLVMInt1TypeInContext(self.llvm_context), val, 0) }
+    pub fn UInt1<'ctx>(&'ctx self, val: u64) -> IntValue<'ctx> {
+        self.Int1Type().const_int(val, false)
     }
     #[inline]
-    pub fn SInt8(&self, val: u64) -> LLVMValueRef {
-        unsafe { LLVMConstInt(LLVMInt8TypeInContext(self.llvm_context), val, 1) }
+    pub fn SInt8<'ctx>(&'ctx self, val: u64) -> IntValue<'ctx> {
+        self.Int8Type().const_int(val, true)
     }
     #[inline]
-    pub fn UInt8(&self, val: u64) -> LLVMValueRef {
-        unsafe { LLVMConstInt(LLVMInt8TypeInContext(self.llvm_context), val, 0) }
+    pub fn UInt8<'ctx>(&'ctx self, val: u64) -> IntValue<'ctx> {
+        self.Int8Type().const_int(val, false)
     }
     #[inline]
-    pub fn SInt16(&self, val: u64) -> LLVMValueRef {
-        unsafe { LLVMConstInt(LLVMInt16TypeInContext(self.llvm_context), val, 1) }
+    pub fn SInt16<'ctx>(&'ctx self, val: u64) -> IntValue<'ctx> {
+        self.Int16Type().const_int(val, true)
     }
     #[inline]
-    pub fn UInt16(&self, val: u64) -> LLVMValueRef {
-        unsafe { LLVMConstInt(LLVMInt16TypeInContext(self.llvm_context), val, 0) }
+    pub fn UInt16<'ctx>(&'ctx self, val: u64) -> IntValue<'ctx> {
+        self.Int16Type().const_int(val, false)
     }
     #[inline]
-    pub fn SInt32(&self, val: u64) -> LLVMValueRef {
-        unsafe { LLVMConstInt(LLVMInt32TypeInContext(self.llvm_context), val, 1) }
+    pub fn SInt32<'ctx>(&'ctx self, val: u64) -> IntValue<'ctx> {
+        self.Int32Type().const_int(val, true)
     }
     #[inline]
-    pub fn UInt32(&self, val: u64) -> LLVMValueRef {
-        unsafe { LLVMConstInt(LLVMInt32TypeInContext(self.llvm_context), val, 0) }
+    pub fn UInt32<'ctx>(&'ctx self, val: u64) -> IntValue<'ctx> {
+        self.Int32Type().const_int(val, false)
     }
     #[inline]
-    pub fn SInt64(&self, val: u64) -> LLVMValueRef {
-        unsafe { LLVMConstInt(LLVMInt64TypeInContext(self.llvm_context), val, 1) }
+    pub fn SInt64<'ctx>(&'ctx self, val: u64) -> IntValue<'ctx> {
+        self.Int64Type().const_int(val, true)
     }
     #[inline]
-    pub fn UInt64(&self, val: u64) -> LLVMValueRef {
-        unsafe { LLVMConstInt(LLVMInt64TypeInContext(self.llvm_context), val, 0) }
+    pub fn UInt64<'ctx>(&'ctx self, val: u64) -> IntValue<'ctx> {
+        self.Int64Type().const_int(val, false)
     }
     #[inline]
-    pub fn SInt128(&self, val: u64) -> LLVMValueRef {
-        unsafe { LLVMConstInt(LLVMInt128TypeInContext(self.llvm_context), val, 1) }
+    pub fn SInt128<'ctx>(&'ctx self, val: u64) -> IntValue<'ctx> {
+        self.Int128Type().const_int(val, true)
     }
     #[inline]
-    pub fn UInt128(&self, val: u64) -> LLVMValueRef {
-        unsafe { LLVMConstInt(LLVMInt128TypeInContext(self.llvm_context), val, 0) }
+    pub fn UInt128<'ctx>(&'ctx self, val: u64) -> IntValue<'ctx> {
+        self.Int128Type().const_int(val, false)
     }
 
     #[inline]
-    pub fn Half(&self, val: f64) -> LLVMValueRef {
-        unsafe { LLVMConstReal(LLVMHalfTypeInContext(self.llvm_context), val) }
+    pub fn Half<'ctx>(&'ctx self, val: f64) -> FloatValue<'ctx> {
+        self.HalfType().const_float(val)
+    }
+    #[inline]
+    pub fn Float<'ctx>(&'ctx self, val: f64) -> FloatValue<'ctx> {
+        self.FloatType().const_float(val)
+    }
+    #[inline]
+    pub fn Double<'ctx>(&'ctx self, val: f64) -> FloatValue<'ctx> {
+        self.DoubleType().const_float(val)
+    }
+    #[inline]
+    pub fn FP128<'ctx>(&'ctx self, val: f64) -> FloatValue<'ctx> {
+        self.FP128Type().const_float(val)
     }
     #[inline]
-    pub fn Float(&self, val: f64) -> LLVMValueRef {
-        unsafe { LLVMConstReal(LLVMFloatTypeInContext(self.llvm_context), val) }
+    pub fn X86FP80<'ctx>(&'ctx self, val: f64) -> FloatValue<'ctx> {
+        self.X86FP80Type().const_float(val)
     }
     #[inline]
-    pub fn Double(&self, val: f64) -> LLVMValueRef {
-        unsafe { LLVMConstReal(LLVMDoubleTypeInContext(self.llvm_context), val) }
+    pub fn PPCFP128<'ctx>(&'ctx self, val: f64) -> FloatValue<'ctx> {
+        self.PPCFP128Type().const_float(val)
     }
     #[inline]
-    pub fn FP128(&self, val: f64) -> LLVMValueRef {
-        unsafe { LLVMConstReal(LLVMFP128TypeInContext(self.llvm_context), val) }
+    pub fn ConstArray<'ctx, T: AnyType<'ctx>, V: AnyValue<'ctx>>(
+        &'ctx self,
+        typ: T,
+        vals: &[V],
+    ) -> ArrayValue<'ctx> {
+        let mut vals = value_refs(vals);
+        unsafe {
+            ArrayValue::from_value_ref(LLVMConstArray(
+                typ.as_type_ref(),
+                vals.as_mut_ptr(),
+                vals.len() as u32,
+            ))
+        }
     }
     #[inline]
-    pub fn X86FP80(&self, val: f64) -> LLVMValueRef {
-        unsafe { LLVMConstReal(LLVMX86FP80TypeInContext(self.llvm_context), val) }
+    pub fn ConstVector<'ctx, V: AnyValue<'ctx>>(
+        &'ctx self,
+        vals: &[V],
+    ) -> Option<VectorValue<'ctx>> {
+        VectorType::new_const_vector(vals)
     }
     #[inline]
-    pub fn PPCFP128(&self, val: f64) -> LLVMValueRef {
-        unsafe { LLVMConstReal(LLVMPPCFP128TypeInContext(self.llvm_context), val) }
+    pub fn ConstSplat<'ctx, V: AnyValue<'ctx>>(
+        &'ctx self,
+        val: V,
+        count: u32,
+    ) -> Option<VectorValue<'ctx>> {
+        val.get_type().vector_type(count).const_splat(val)
+    }
+}
+
+impl Default for Context {
+    fn default() -> Self {
+        Context::new()
     }
+}
+
+impl Drop for Context {
     #[inline]
-    pub fn ConstArray(
-        &self,
-        typ: LLVMTypeRef,
-        vals: &mut Vec<LLVMValueRef>,
-        len: u32,
-    ) -> LLVMValueRef {
-        unsafe { LLVMConstArray(typ, vals.as_mut_ptr(), len) }
+    fn drop(&mut self) {
+        // the global context and contexts borrowed from a module are not ours
+        if self.owned {
+            unsafe { LLVMContextDispose(self.llvm_context) }
+        }
     }
 }
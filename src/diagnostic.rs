@@ -0,0 +1,107 @@
+use std::error::Error;
+use std::fmt;
+
+// An error LLVM reported against a piece of source text, such as textual IR
+// that failed to parse.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    // Both 1-based, and 0 when the error is not tied to a position in the
+    // source (an unreadable file, for instance).
+    pub line: u32,
+    pub column: u32,
+    pub message: String,
+    // The offending line of source as LLVM quoted it, if it did.
+    pub source_line: Option<String>,
+}
+
+impl Diagnostic {
+    pub fn new(message: &str) -> Diagnostic {
+        Diagnostic {
+            line: 0,
+            column: 0,
+            message: message.to_string(),
+            source_line: None,
+        }
+    }
+
+    // Picks apart the "<buffer>:<line>:<column>: error: <message>" report LLVM
+    // prints for an SMDiagnostic, followed by the source line and a caret.
+    pub(crate) fn from_report(buffer_name: &str, report: &str) -> Diagnostic {
+        let mut lines = report.lines();
+        let first = lines.next().unwrap_or("");
+        let located = first
+            .strip_prefix(buffer_name)
+            .and_then(|rest| rest.strip_prefix(':'))
+            .unwrap_or(first);
+
+        let mut parts = located.splitn(3, ':');
+        let position = match (parts.next(), parts.next(), parts.next()) {
+            (Some(line), Some(column), Some(rest)) => {
+                match (line.parse::<u32>(), column.parse::<u32>()) {
+                    (Ok(line), Ok(column)) => Some((line, column, rest)),
+                    _ => None,
+                }
+            }
+            _ => None,
+        };
+        let (line, column, rest) = position.unwrap_or((0, 0, located));
+
+        let rest = rest.trim_start();
+        let message = rest.strip_prefix("error:").unwrap_or(rest).trim_start();
+        Diagnostic {
+            line,
+            column,
+            message: message.to_string(),
+            source_line: lines.next().map(|source| source.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.line == 0 {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "{}:{}: {}", self.line, self.column, self.message)
+        }
+    }
+}
+
+impl Error for Diagnostic {}
+
+// Why linking one module into another failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LinkError {
+    // Both modules define a global or comdat of this name.
+    SymbolConflict { name: String, message: String },
+    // Anything else the linker rejected, such as mismatched appending globals.
+    Other(String),
+}
+
+impl LinkError {
+    pub(crate) fn from_message(message: &str) -> LinkError {
+        let conflict = ["Linking globals named '", "Linking COMDATs named '"]
+            .iter()
+            .filter_map(|prefix| message.strip_prefix(prefix))
+            .filter_map(|rest| rest.find("':").map(|end| &rest[..end]))
+            .next();
+        match conflict {
+            Some(name) => LinkError::SymbolConflict {
+                name: name.to_string(),
+                message: message.to_string(),
+            },
+            None => LinkError::Other(message.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for LinkError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            LinkError::SymbolConflict { ref message, .. } => write!(f, "{}", message),
+            LinkError::Other(ref message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl Error for LinkError {}
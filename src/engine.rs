@@ -4,19 +4,28 @@ extern crate llvm_sys;
 use self::libc::c_void;
 use self::llvm_sys::execution_engine::*;
 use self::llvm_sys::prelude::*;
-use std::ffi::CString;
+use context::ContextMarker;
+use function::Function;
+use message::take_message;
+use module::Module;
+use small_cstr::SmallCStr;
+use std::marker::PhantomData;
 use std::os::raw::{c_char, c_uint, c_ulonglong};
+use std::ptr;
 use LLVM::Type;
 
 #[derive(Debug)]
-pub struct Engine {
+pub struct Engine<'ctx> {
     llvm_execute_engine: LLVMExecutionEngineRef,
+    _context: ContextMarker<'ctx>,
 }
 
-impl Engine {
-    pub fn create_interpreter(module: LLVMModuleRef) -> Result<Engine, String> {
-        let mut error: *mut c_char = 0 as *mut c_char;
-        let mut engine: LLVMExecutionEngineRef = 0 as LLVMExecutionEngineRef;
+impl<'ctx> Engine<'ctx> {
+    pub fn create_interpreter(module: Module<'ctx>) -> Result<Engine<'ctx>, String> {
+        let mut error: *mut c_char = ptr::null_mut();
+        let mut engine: LLVMExecutionEngineRef = ptr::null_mut();
+        // LLVM owns the module from here on, even if creating the engine fails.
+        let module = module.into_raw();
         let result = unsafe {
             let buf: *mut *mut c_char = &mut error;
             let engine_ref: *mut LLVMExecutionEngineRef = &mut engine;
@@ -26,40 +35,36 @@ impl Engine {
 
         if result == 1 {
             // error
-            let err_msg = unsafe { CString::from_raw(error).into_string().unwrap() };
-            Err(err_msg)
+            Err(take_message(error))
         } else {
             // ok
             Ok(Engine {
                 llvm_execute_engine: engine,
+                _context: PhantomData,
             })
         }
     }
 
-    pub fn create_jit_engine(module: LLVMModuleRef) -> Result<Engine, String> {
-        let mut error: *mut c_char = 0 as *mut c_char;
-        let mut engine: LLVMExecutionEngineRef = 0 as LLVMExecutionEngineRef;
+    pub fn create_jit_engine(module: Module<'ctx>) -> Result<Engine<'ctx>, String> {
+        let mut error: *mut c_char = ptr::null_mut();
+        let mut engine: LLVMExecutionEngineRef = ptr::null_mut();
+        // LLVM owns the module from here on, even if creating the engine fails.
+        let module = module.into_raw();
         let result = unsafe {
             let buf: *mut *mut c_char = &mut error;
             let engine_ref: *mut LLVMExecutionEngineRef = &mut engine;
             LLVMLinkInMCJIT();
-            LLVMCreateMCJITCompilerForModule(
-                engine_ref,
-                module,
-                0 as *mut LLVMMCJITCompilerOptions,
-                0,
-                buf,
-            )
+            LLVMCreateMCJITCompilerForModule(engine_ref, module, ptr::null_mut(), 0, buf)
         };
 
         if result == 1 {
             // error
-            let err_msg = unsafe { CString::from_raw(error).into_string().unwrap() };
-            Err(err_msg)
+            Err(take_message(error))
         } else {
             // ok
             Ok(Engine {
                 llvm_execute_engine: engine,
+                _context: PhantomData,
             })
         }
     }
@@ -68,15 +73,29 @@ impl Engine {
         self.llvm_execute_engine
     }
 
+    // Looks a function up in any of the modules owned by the engine.
+    pub fn named_function(&self, name: &str) -> Option<Function<'ctx>> {
+        let func_name = SmallCStr::new(name);
+        let mut function: LLVMValueRef = ptr::null_mut();
+        let not_found = unsafe {
+            LLVMFindFunction(self.llvm_execute_engine, func_name.as_ptr(), &mut function)
+        };
+        if not_found == 0 {
+            Some(Function::from_ptr(function))
+        } else {
+            None
+        }
+    }
+
     pub fn run_function(
         &self,
-        function: LLVMValueRef,
+        function: Function<'ctx>,
         args: &mut [LLVMGenericValueRef],
     ) -> FuncallResult {
         let func_result = unsafe {
             LLVMRunFunction(
                 self.llvm_execute_engine,
-                function,
+                function.as_ref(),
                 args.len() as u32,
                 args.as_mut_ptr(),
             )
@@ -85,6 +104,14 @@ impl Engine {
     }
 }
 
+impl<'ctx> Drop for Engine<'ctx> {
+    #[inline]
+    fn drop(&mut self) {
+        // disposes every module owned by the engine as well
+        unsafe { LLVMDisposeExecutionEngine(self.llvm_execute_engine) }
+    }
+}
+
 pub struct FuncallResult {
     value: LLVMGenericValueRef,
 }
@@ -115,11 +142,18 @@ impl FuncallResult {
 
     #[inline]
     pub fn to_float(&self) -> f32 {
-        unsafe { LLVMGenericValueToFloat(Type::Float(), self.value) as f32 }
+        unsafe { LLVMGenericValueToFloat(Type::Float().as_ref(), self.value) as f32 }
     }
 
     #[inline]
     pub fn to_double(&self) -> f64 {
-        unsafe { LLVMGenericValueToFloat(Type::Double(), self.value) }
+        unsafe { LLVMGenericValueToFloat(Type::Double().as_ref(), self.value) }
+    }
+}
+
+impl Drop for FuncallResult {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe { LLVMDisposeGenericValue(self.value) }
     }
 }
@@ -0,0 +1,82 @@
+extern crate llvm_sys;
+
+use self::llvm_sys::prelude::*;
+use std::ops::{BitOr, BitOrAssign};
+
+// Assumptions float operations may make for the optimizer. The bits match
+// LLVM's own FastMathFlags, and FAST is all of them, like -ffast-math.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct FastMathFlags(u32);
+
+impl FastMathFlags {
+    pub const NONE: FastMathFlags = FastMathFlags(0);
+    pub const ALLOW_REASSOC: FastMathFlags = FastMathFlags(1 << 0);
+    pub const NO_NANS: FastMathFlags = FastMathFlags(1 << 1);
+    pub const NO_INFS: FastMathFlags = FastMathFlags(1 << 2);
+    pub const NO_SIGNED_ZEROS: FastMathFlags = FastMathFlags(1 << 3);
+    pub const ALLOW_RECIPROCAL: FastMathFlags = FastMathFlags(1 << 4);
+    pub const ALLOW_CONTRACT: FastMathFlags = FastMathFlags(1 << 5);
+    pub const APPROX_FUNC: FastMathFlags = FastMathFlags(1 << 6);
+    pub const FAST: FastMathFlags = FastMathFlags((1 << 7) - 1);
+
+    #[inline]
+    pub fn from_bits(bits: u32) -> FastMathFlags {
+        FastMathFlags(bits & FastMathFlags::FAST.0)
+    }
+
+    #[inline]
+    pub fn bits(self) -> u32 {
+        self.0
+    }
+
+    #[inline]
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    #[inline]
+    pub fn contains(self, other: FastMathFlags) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl BitOr for FastMathFlags {
+    type Output = FastMathFlags;
+
+    #[inline]
+    fn bitor(self, other: FastMathFlags) -> FastMathFlags {
+        FastMathFlags(self.0 | other.0)
+    }
+}
+
+impl BitOrAssign for FastMathFlags {
+    #[inline]
+    fn bitor_assign(&mut self, other: FastMathFlags) {
+        self.0 |= other.0
+    }
+}
+
+// Built from src/wrapper.cpp; the C API has no way to reach these.
+extern "C" {
+    fn LLVMWrapperCanUseFastMathFlags(val: LLVMValueRef) -> LLVMBool;
+    fn LLVMWrapperGetFastMathFlags(val: LLVMValueRef) -> u32;
+    fn LLVMWrapperSetFastMathFlags(val: LLVMValueRef, flags: u32);
+}
+
+// Whether `val` is an instruction that takes fast-math flags: float
+// arithmetic and compares, and calls, phis and selects of float type.
+#[inline]
+pub(crate) fn can_use(val: LLVMValueRef) -> bool {
+    unsafe { LLVMWrapperCanUseFastMathFlags(val) != 0 }
+}
+
+#[inline]
+pub(crate) fn get(val: LLVMValueRef) -> FastMathFlags {
+    FastMathFlags::from_bits(unsafe { LLVMWrapperGetFastMathFlags(val) })
+}
+
+// Does nothing to values that cannot take the flags.
+#[inline]
+pub(crate) fn set(val: LLVMValueRef, flags: FastMathFlags) {
+    unsafe { LLVMWrapperSetFastMathFlags(val, flags.bits()) }
+}
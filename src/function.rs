@@ -4,42 +4,99 @@ extern crate llvm_sys;
 
 use self::llvm_sys::core::*;
 use self::llvm_sys::prelude::*;
-use cstring_manager::CStringManager;
+use attributes::{Attribute, AttributeLoc, CallConv};
+use basic_block::BasicBlock;
+use context::ContextMarker;
+use linkage::{Linkage, Visibility};
+use message::take_message;
+use module::Module;
+use small_cstr::SmallCStr;
+use std::fs;
+use std::marker::PhantomData;
+use std::os::raw::c_char;
+use std::ptr;
+use types::{AnyType, FunctionType, Type};
+use values::{AnyValue, Value};
 
-#[derive(Debug, Clone)]
-pub struct Function {
-    llvm_function: LLVMValueRef,
-    llvm_module: LLVMModuleRef,
-    function_type: LLVMTypeRef,
+// Built from src/wrapper.cpp; LLVMVerifyFunction cannot return its message.
+extern "C" {
+    fn LLVMWrapperVerifyFunction(function: LLVMValueRef, out_message: *mut *mut c_char)
+        -> LLVMBool;
 }
 
-impl Function {
-    pub fn new(module: LLVMModuleRef, name: &str, function_type: LLVMTypeRef) -> Function {
-        let function_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        let function = unsafe { LLVMAddFunction(module, function_name_ptr, function_type) };
-        Function {
-            llvm_function: function,
-            llvm_module: module,
-            function_type: function_type,
+// Inside a Graphviz quoted string only quotes and backslashes need escaping.
+fn escape_quoted(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if c == '"' || c == '\\' {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+// Graphviz record labels give braces, bars and angle brackets a meaning of their own.
+fn escape_record(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '{' | '}' | '|' | '<' | '>' | '"' | '\\' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            _ => escaped.push(c),
         }
     }
+    escaped
+}
+
+// The function type and module are always read back from the value itself,
+// so functions looked up by name or pointer are as usable as created ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Function<'ctx> {
+    llvm_function: LLVMValueRef,
+    _context: ContextMarker<'ctx>,
+}
+
+impl<'ctx> Function<'ctx> {
+    pub fn new(
+        module: &Module<'ctx>,
+        name: &str,
+        function_type: FunctionType<'ctx>,
+    ) -> Function<'ctx> {
+        let function_name = SmallCStr::new(name);
+        let function = unsafe {
+            LLVMAddFunction(
+                module.as_ref(),
+                function_name.as_ptr(),
+                function_type.as_type_ref(),
+            )
+        };
+        Function::from_ptr(function)
+    }
 
-    pub fn from_ptr(func_ptr: LLVMValueRef) -> Function {
+    pub fn from_ptr(func_ptr: LLVMValueRef) -> Function<'ctx> {
         Function {
             llvm_function: func_ptr,
-            llvm_module: 0 as LLVMModuleRef,
-            function_type: 0 as LLVMTypeRef,
+            _context: PhantomData,
         }
     }
 
-    pub fn append_basic_block(&self, name: &str) -> LLVMBasicBlockRef {
-        let label_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        if self.llvm_module.is_null() {
-            unsafe { LLVMAppendBasicBlock(self.llvm_function, label_name_ptr) }
-        } else {
-            let context = unsafe { LLVMGetModuleContext(self.llvm_module) };
-            unsafe { LLVMAppendBasicBlockInContext(context, self.llvm_function, label_name_ptr) }
-        }
+    #[inline]
+    fn context_ref(&self) -> LLVMContextRef {
+        unsafe { LLVMGetTypeContext(LLVMTypeOf(self.llvm_function)) }
+    }
+
+    pub fn append_basic_block(&self, name: &str) -> BasicBlock<'ctx> {
+        let label_name = SmallCStr::new(name);
+        BasicBlock::from_ptr(unsafe {
+            LLVMAppendBasicBlockInContext(
+                self.context_ref(),
+                self.llvm_function,
+                label_name.as_ptr(),
+            )
+        })
     }
 
     pub fn as_ref(&self) -> LLVMValueRef {
@@ -47,8 +104,25 @@ impl Function {
     }
 
     #[inline]
-    pub fn get_param(&self, index: u32) -> LLVMValueRef {
-        unsafe { LLVMGetParam(self.llvm_function, index) }
+    pub fn get_name(&self) -> String {
+        AnyValue::get_name(self)
+    }
+
+    // The module this function was added to.
+    #[inline]
+    pub fn get_parent(&self) -> LLVMModuleRef {
+        unsafe { LLVMGetGlobalParent(self.llvm_function) }
+    }
+
+    #[inline]
+    pub fn get_param(&self, index: u32) -> Value<'ctx> {
+        unsafe { Value::from_ptr(LLVMGetParam(self.llvm_function, index)) }
+    }
+
+    pub fn get_params(&self) -> Vec<Value<'ctx>> {
+        let mut params = vec![ptr::null_mut(); self.params_count() as usize];
+        unsafe { LLVMGetParams(self.llvm_function, params.as_mut_ptr()) };
+        params.into_iter().map(Value::from_ptr).collect()
     }
 
     #[inline]
@@ -57,22 +131,200 @@ impl Function {
     }
 
     #[inline]
-    pub fn get_function_type(&self) -> LLVMTypeRef {
-        self.function_type
+    pub fn get_function_type(&self) -> FunctionType<'ctx> {
+        unsafe { FunctionType::from_type_ref(LLVMGlobalGetValueType(self.llvm_function)) }
+    }
+
+    #[inline]
+    pub fn get_return_type(&self) -> Type<'ctx> {
+        self.get_function_type().get_return_type()
+    }
+
+    #[inline]
+    pub fn get_param_types(&self) -> Vec<Type<'ctx>> {
+        self.get_function_type().get_param_types()
+    }
+
+    #[inline]
+    pub fn count_basic_blocks(&self) -> u32 {
+        unsafe { LLVMCountBasicBlocks(self.llvm_function) }
+    }
+
+    pub fn get_basic_blocks(&self) -> Vec<BasicBlock<'ctx>> {
+        let mut blocks = vec![ptr::null_mut(); self.count_basic_blocks() as usize];
+        unsafe { LLVMGetBasicBlocks(self.llvm_function, blocks.as_mut_ptr()) };
+        blocks.into_iter().map(BasicBlock::from_ptr).collect()
+    }
+
+    // Declarations have no body and so no entry block.
+    #[inline]
+    pub fn get_entry_block(&self) -> Option<BasicBlock<'ctx>> {
+        if self.count_basic_blocks() == 0 {
+            None
+        } else {
+            Some(BasicBlock::from_ptr(unsafe {
+                LLVMGetEntryBasicBlock(self.llvm_function)
+            }))
+        }
+    }
+
+    #[inline]
+    pub fn get_last_basic_block(&self) -> Option<BasicBlock<'ctx>> {
+        BasicBlock::from_nullable(unsafe { LLVMGetLastBasicBlock(self.llvm_function) })
+    }
+
+    pub fn count_instructions(&self) -> usize {
+        self.get_basic_blocks()
+            .iter()
+            .map(|block| block.count_instructions())
+            .sum()
+    }
+
+    // Checks just this function, so the broken one can be told apart from the
+    // rest. The error is the verifier's complaint, as Module::verify gives it.
+    pub fn verify(&self) -> Result<(), String> {
+        let mut error: *mut c_char = ptr::null_mut();
+        let broken = unsafe { LLVMWrapperVerifyFunction(self.llvm_function, &mut error) };
+        let err_msg = take_message(error);
+        if broken != 0 {
+            Err(err_msg)
+        } else {
+            Ok(())
+        }
+    }
+
+    // Writes the control flow graph as a Graphviz file, with every block's
+    // instructions. LLVM's own viewer wants a display; this works anywhere.
+    pub fn view_cfg(&self, filename: &str) -> Result<(), String> {
+        fs::write(filename, self.cfg_dot(false)).map_err(|err| err.to_string())
+    }
+
+    // Like view_cfg, but the blocks only show their names.
+    pub fn view_cfg_only(&self, filename: &str) -> Result<(), String> {
+        fs::write(filename, self.cfg_dot(true)).map_err(|err| err.to_string())
+    }
+
+    fn cfg_dot(&self, names_only: bool) -> String {
+        let name = escape_quoted(&self.get_name());
+        let blocks = self.get_basic_blocks();
+        let mut dot = format!("digraph \"CFG for '{}' function\" {{\n", name);
+        dot.push_str(&format!("\tlabel=\"CFG for '{}' function\";\n\n", name));
+        for (index, block) in blocks.iter().enumerate() {
+            let block_name = block.get_name();
+            let mut label = if block_name.is_empty() {
+                format!("bb{}:", index)
+            } else {
+                format!("{}:", block_name)
+            };
+            label = escape_record(&label);
+            if !names_only {
+                for instruction in block.get_instructions() {
+                    label.push_str("\\l  ");
+                    label.push_str(&escape_record(instruction.print_to_string().trim()));
+                }
+            }
+            dot.push_str(&format!(
+                "\tNode{} [shape=record,label=\"{{{}\\l}}\"];\n",
+                index, label
+            ));
+
+            let terminator = match block.get_terminator() {
+                Some(terminator) => terminator,
+                None => continue,
+            };
+            for successor in 0..unsafe { LLVMGetNumSuccessors(terminator.as_ref()) } {
+                let target = unsafe { LLVMGetSuccessor(terminator.as_ref(), successor) };
+                if let Some(target_index) = blocks.iter().position(|b| b.as_ref() == target) {
+                    dot.push_str(&format!("\tNode{} -> Node{};\n", index, target_index));
+                }
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Removes the function from its module and frees it.
+    ///
+    /// # Safety
+    ///
+    /// Nothing may use the function any more, and no copy of this handle, or
+    /// of any handle to its blocks, params or instructions, may be used again.
+    #[inline]
+    pub unsafe fn delete(self) {
+        LLVMDeleteFunction(self.llvm_function)
+    }
+
+    #[inline]
+    pub fn add_attribute(&self, loc: AttributeLoc, attribute: Attribute<'ctx>) {
+        unsafe {
+            LLVMAddAttributeAtIndex(
+                self.llvm_function,
+                loc.into(),
+                attribute.create(self.context_ref()),
+            )
+        }
     }
 
     #[inline]
-    pub fn get_return_type(&self) -> LLVMTypeRef {
-        unsafe { LLVMGetReturnType(self.function_type) }
+    pub fn remove_attribute(&self, loc: AttributeLoc, attribute: Attribute<'ctx>) {
+        unsafe {
+            LLVMRemoveEnumAttributeAtIndex(self.llvm_function, loc.into(), attribute.kind_id())
+        }
     }
 
     #[inline]
-    pub fn get_param_types(&self) -> LLVMTypeRef {
-        let mut types: LLVMTypeRef = 0 as LLVMTypeRef;
-        let ptr: *mut LLVMTypeRef = &mut types;
+    pub fn has_attribute(&self, loc: AttributeLoc, attribute: Attribute<'ctx>) -> bool {
         unsafe {
-            LLVMGetParamTypes(self.function_type, ptr);
+            !LLVMGetEnumAttributeAtIndex(self.llvm_function, loc.into(), attribute.kind_id())
+                .is_null()
         }
-        types
+    }
+
+    #[inline]
+    pub fn set_call_conv(&self, call_conv: CallConv) {
+        unsafe { LLVMSetFunctionCallConv(self.llvm_function, call_conv.into()) }
+    }
+
+    #[inline]
+    pub fn get_call_conv(&self) -> CallConv {
+        unsafe { LLVMGetFunctionCallConv(self.llvm_function).into() }
+    }
+
+    // The routine the unwinder asks whether this function's landing pads want
+    // an exception, such as __gxx_personality_v0 for C++.
+    #[inline]
+    pub fn set_personality_function(&self, personality: Function<'ctx>) {
+        unsafe { LLVMSetPersonalityFn(self.llvm_function, personality.as_ref()) }
+    }
+
+    #[inline]
+    pub fn get_personality_function(&self) -> Option<Function<'ctx>> {
+        unsafe {
+            if LLVMHasPersonalityFn(self.llvm_function) == 0 {
+                None
+            } else {
+                Some(Function::from_ptr(LLVMGetPersonalityFn(self.llvm_function)))
+            }
+        }
+    }
+
+    #[inline]
+    pub fn set_linkage(&self, linkage: Linkage) {
+        unsafe { LLVMSetLinkage(self.llvm_function, linkage.into()) }
+    }
+
+    #[inline]
+    pub fn get_linkage(&self) -> Linkage {
+        unsafe { LLVMGetLinkage(self.llvm_function).into() }
+    }
+
+    #[inline]
+    pub fn set_visibility(&self, visibility: Visibility) {
+        unsafe { LLVMSetVisibility(self.llvm_function, visibility.into()) }
+    }
+
+    #[inline]
+    pub fn get_visibility(&self) -> Visibility {
+        unsafe { LLVMGetVisibility(self.llvm_function).into() }
     }
 }
@@ -0,0 +1,139 @@
+extern crate llvm_sys;
+
+use self::llvm_sys::core::*;
+use self::llvm_sys::prelude::*;
+use context::ContextMarker;
+use function::Function;
+use std::convert::TryFrom;
+use std::marker::PhantomData;
+use values::{AnyValue, Value};
+
+// Built from src/wrapper.cpp; the C API has no way to erase an alias.
+extern "C" {
+    fn LLVMWrapperEraseGlobalAlias(alias: LLVMValueRef);
+}
+
+// Another name for a global variable, function or constant expression.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GlobalAlias<'ctx> {
+    llvm_alias: LLVMValueRef,
+    _context: ContextMarker<'ctx>,
+}
+
+impl<'ctx> GlobalAlias<'ctx> {
+    pub fn from_ptr(alias_ptr: LLVMValueRef) -> GlobalAlias<'ctx> {
+        GlobalAlias {
+            llvm_alias: alias_ptr,
+            _context: PhantomData,
+        }
+    }
+
+    pub fn as_ref(&self) -> LLVMValueRef {
+        self.llvm_alias
+    }
+
+    #[inline]
+    pub fn get_aliasee(&self) -> Value<'ctx> {
+        unsafe { Value::from_ptr(LLVMAliasGetAliasee(self.llvm_alias)) }
+    }
+
+    #[inline]
+    pub fn set_aliasee<V: AnyValue<'ctx>>(&self, aliasee: V) {
+        unsafe { LLVMAliasSetAliasee(self.llvm_alias, aliasee.as_value_ref()) }
+    }
+
+    /// Removes the alias from its module and frees it.
+    ///
+    /// # Safety
+    ///
+    /// Nothing may use the alias any more, and no copy of this handle may be
+    /// used again.
+    #[inline]
+    pub unsafe fn delete(self) {
+        LLVMWrapperEraseGlobalAlias(self.llvm_alias)
+    }
+}
+
+// A function whose address is picked at load time by calling its resolver.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GlobalIFunc<'ctx> {
+    llvm_ifunc: LLVMValueRef,
+    _context: ContextMarker<'ctx>,
+}
+
+impl<'ctx> GlobalIFunc<'ctx> {
+    pub fn from_ptr(ifunc_ptr: LLVMValueRef) -> GlobalIFunc<'ctx> {
+        GlobalIFunc {
+            llvm_ifunc: ifunc_ptr,
+            _context: PhantomData,
+        }
+    }
+
+    pub fn as_ref(&self) -> LLVMValueRef {
+        self.llvm_ifunc
+    }
+
+    #[inline]
+    pub fn get_resolver(&self) -> Option<Function<'ctx>> {
+        let resolver = unsafe { LLVMGetGlobalIFuncResolver(self.llvm_ifunc) };
+        if resolver.is_null() {
+            None
+        } else {
+            Some(Function::from_ptr(resolver))
+        }
+    }
+
+    #[inline]
+    pub fn set_resolver(&self, resolver: Function<'ctx>) {
+        unsafe { LLVMSetGlobalIFuncResolver(self.llvm_ifunc, resolver.as_ref()) }
+    }
+
+    /// Removes the ifunc from its module and frees it.
+    ///
+    /// # Safety
+    ///
+    /// Nothing may use the ifunc any more, and no copy of this handle may be
+    /// used again.
+    #[inline]
+    pub unsafe fn delete(self) {
+        LLVMEraseGlobalIFunc(self.llvm_ifunc)
+    }
+}
+
+macro_rules! global_value {
+    ($name:ident, $field:ident, $check:ident) => {
+        impl<'ctx> AnyValue<'ctx> for $name<'ctx> {
+            #[inline]
+            fn as_value_ref(&self) -> LLVMValueRef {
+                self.$field
+            }
+
+            #[inline]
+            unsafe fn from_value_ref(value: LLVMValueRef) -> Self {
+                $name::from_ptr(value)
+            }
+        }
+
+        impl<'ctx> From<$name<'ctx>> for Value<'ctx> {
+            #[inline]
+            fn from(global: $name<'ctx>) -> Value<'ctx> {
+                Value::from_ptr(global.$field)
+            }
+        }
+
+        impl<'ctx> TryFrom<Value<'ctx>> for $name<'ctx> {
+            type Error = Value<'ctx>;
+
+            fn try_from(value: Value<'ctx>) -> Result<Self, Self::Error> {
+                if unsafe { $check(value.as_ref()).is_null() } {
+                    Err(value)
+                } else {
+                    Ok($name::from_ptr(value.as_ref()))
+                }
+            }
+        }
+    };
+}
+
+global_value!(GlobalAlias, llvm_alias, LLVMIsAGlobalAlias);
+global_value!(GlobalIFunc, llvm_ifunc, LLVMIsAGlobalIFunc);
@@ -0,0 +1,365 @@
+extern crate llvm_sys;
+
+use self::llvm_sys::comdat::*;
+use self::llvm_sys::core::*;
+use self::llvm_sys::prelude::*;
+use self::llvm_sys::LLVMThreadLocalMode;
+use context::ContextMarker;
+use linkage::{Linkage, UnnamedAddr, Visibility};
+use module::Module;
+use small_cstr::SmallCStr;
+use std::convert::TryFrom;
+use std::ffi::CStr;
+use std::marker::PhantomData;
+use types::{AnyType, PointerType};
+use values::{AnyValue, PointerValue, Value};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ThreadLocalMode {
+    GeneralDynamic,
+    LocalDynamic,
+    InitialExec,
+    LocalExec,
+}
+
+impl From<ThreadLocalMode> for LLVMThreadLocalMode {
+    fn from(mode: ThreadLocalMode) -> LLVMThreadLocalMode {
+        match mode {
+            ThreadLocalMode::GeneralDynamic => LLVMThreadLocalMode::LLVMGeneralDynamicTLSModel,
+            ThreadLocalMode::LocalDynamic => LLVMThreadLocalMode::LLVMLocalDynamicTLSModel,
+            ThreadLocalMode::InitialExec => LLVMThreadLocalMode::LLVMInitialExecTLSModel,
+            ThreadLocalMode::LocalExec => LLVMThreadLocalMode::LLVMLocalExecTLSModel,
+        }
+    }
+}
+
+// How the linker picks between comdats of the same name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ComdatSelectionKind {
+    Any,
+    ExactMatch,
+    Largest,
+    NoDuplicates,
+    SameSize,
+}
+
+impl From<ComdatSelectionKind> for LLVMComdatSelectionKind {
+    fn from(kind: ComdatSelectionKind) -> LLVMComdatSelectionKind {
+        match kind {
+            ComdatSelectionKind::Any => LLVMComdatSelectionKind::LLVMAnyComdatSelectionKind,
+            ComdatSelectionKind::ExactMatch => {
+                LLVMComdatSelectionKind::LLVMExactMatchComdatSelectionKind
+            }
+            ComdatSelectionKind::Largest => LLVMComdatSelectionKind::LLVMLargestComdatSelectionKind,
+            ComdatSelectionKind::NoDuplicates => {
+                LLVMComdatSelectionKind::LLVMNoDuplicatesComdatSelectionKind
+            }
+            ComdatSelectionKind::SameSize => {
+                LLVMComdatSelectionKind::LLVMSameSizeComdatSelectionKind
+            }
+        }
+    }
+}
+
+impl From<LLVMComdatSelectionKind> for ComdatSelectionKind {
+    fn from(kind: LLVMComdatSelectionKind) -> ComdatSelectionKind {
+        match kind {
+            LLVMComdatSelectionKind::LLVMAnyComdatSelectionKind => ComdatSelectionKind::Any,
+            LLVMComdatSelectionKind::LLVMExactMatchComdatSelectionKind => {
+                ComdatSelectionKind::ExactMatch
+            }
+            LLVMComdatSelectionKind::LLVMLargestComdatSelectionKind => ComdatSelectionKind::Largest,
+            LLVMComdatSelectionKind::LLVMNoDuplicatesComdatSelectionKind => {
+                ComdatSelectionKind::NoDuplicates
+            }
+            LLVMComdatSelectionKind::LLVMSameSizeComdatSelectionKind => {
+                ComdatSelectionKind::SameSize
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Comdat<'ctx> {
+    llvm_comdat: LLVMComdatRef,
+    _context: ContextMarker<'ctx>,
+}
+
+impl<'ctx> Comdat<'ctx> {
+    pub fn from_ptr(comdat_ptr: LLVMComdatRef) -> Comdat<'ctx> {
+        Comdat {
+            llvm_comdat: comdat_ptr,
+            _context: PhantomData,
+        }
+    }
+
+    pub fn as_ref(&self) -> LLVMComdatRef {
+        self.llvm_comdat
+    }
+
+    #[inline]
+    pub fn get_selection_kind(&self) -> ComdatSelectionKind {
+        unsafe { LLVMGetComdatSelectionKind(self.llvm_comdat).into() }
+    }
+
+    #[inline]
+    pub fn set_selection_kind(&self, kind: ComdatSelectionKind) {
+        unsafe { LLVMSetComdatSelectionKind(self.llvm_comdat, kind.into()) }
+    }
+}
+
+// A global variable. Used as a value it is a pointer to its contents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GlobalVariable<'ctx> {
+    llvm_global: LLVMValueRef,
+    _context: ContextMarker<'ctx>,
+}
+
+impl<'ctx> GlobalVariable<'ctx> {
+    pub fn new<T: AnyType<'ctx>>(
+        module: &Module<'ctx>,
+        typ: T,
+        name: &str,
+    ) -> GlobalVariable<'ctx> {
+        let glob_name = SmallCStr::new(name);
+        let global =
+            unsafe { LLVMAddGlobal(module.as_ref(), typ.as_type_ref(), glob_name.as_ptr()) };
+        GlobalVariable::from_ptr(global)
+    }
+
+    pub fn new_in_address_space<T: AnyType<'ctx>>(
+        module: &Module<'ctx>,
+        typ: T,
+        name: &str,
+        address_space: u32,
+    ) -> GlobalVariable<'ctx> {
+        let glob_name = SmallCStr::new(name);
+        let global = unsafe {
+            LLVMAddGlobalInAddressSpace(
+                module.as_ref(),
+                typ.as_type_ref(),
+                glob_name.as_ptr(),
+                address_space,
+            )
+        };
+        GlobalVariable::from_ptr(global)
+    }
+
+    pub fn from_ptr(global_ptr: LLVMValueRef) -> GlobalVariable<'ctx> {
+        GlobalVariable {
+            llvm_global: global_ptr,
+            _context: PhantomData,
+        }
+    }
+
+    pub fn as_ref(&self) -> LLVMValueRef {
+        self.llvm_global
+    }
+
+    #[inline]
+    pub fn as_pointer_value(&self) -> PointerValue<'ctx> {
+        unsafe { PointerValue::from_value_ref(self.llvm_global) }
+    }
+
+    #[inline]
+    pub fn get_type(&self) -> PointerType<'ctx> {
+        self.as_pointer_value().get_type()
+    }
+
+    #[inline]
+    pub fn get_address_space(&self) -> u32 {
+        self.get_type().get_address_space()
+    }
+
+    #[inline]
+    pub fn set_initializer<V: AnyValue<'ctx>>(&self, value: V) {
+        unsafe { LLVMSetInitializer(self.llvm_global, value.as_value_ref()) }
+    }
+
+    // Declarations have no initializer.
+    #[inline]
+    pub fn get_initializer(&self) -> Option<Value<'ctx>> {
+        let value = unsafe { LLVMGetInitializer(self.llvm_global) };
+        if value.is_null() {
+            None
+        } else {
+            Some(Value::from_ptr(value))
+        }
+    }
+
+    // Whether the contents never change, as in `@x = constant ...`.
+    #[inline]
+    pub fn set_constant(&self, is_constant: bool) {
+        unsafe { LLVMSetGlobalConstant(self.llvm_global, if is_constant { 1 } else { 0 }) }
+    }
+
+    #[inline]
+    pub fn is_constant(&self) -> bool {
+        unsafe { LLVMIsGlobalConstant(self.llvm_global) == 1 }
+    }
+
+    #[inline]
+    pub fn set_linkage(&self, linkage: Linkage) {
+        unsafe { LLVMSetLinkage(self.llvm_global, linkage.into()) }
+    }
+
+    #[inline]
+    pub fn get_linkage(&self) -> Linkage {
+        unsafe { LLVMGetLinkage(self.llvm_global).into() }
+    }
+
+    #[inline]
+    pub fn set_visibility(&self, visibility: Visibility) {
+        unsafe { LLVMSetVisibility(self.llvm_global, visibility.into()) }
+    }
+
+    #[inline]
+    pub fn get_visibility(&self) -> Visibility {
+        unsafe { LLVMGetVisibility(self.llvm_global).into() }
+    }
+
+    #[inline]
+    pub fn set_alignment(&self, bytes: u32) {
+        unsafe { LLVMSetAlignment(self.llvm_global, bytes) }
+    }
+
+    #[inline]
+    pub fn get_alignment(&self) -> u32 {
+        unsafe { LLVMGetAlignment(self.llvm_global) }
+    }
+
+    // `None` puts the global back in the default section.
+    pub fn set_section(&self, section: Option<&str>) {
+        let section = SmallCStr::new(section.unwrap_or(""));
+        unsafe { LLVMSetSection(self.llvm_global, section.as_ptr()) }
+    }
+
+    pub fn get_section(&self) -> Option<String> {
+        let section = unsafe { LLVMGetSection(self.llvm_global) };
+        if section.is_null() {
+            return None;
+        }
+        let section = unsafe { CStr::from_ptr(section).to_string_lossy().into_owned() };
+        if section.is_empty() {
+            None
+        } else {
+            Some(section)
+        }
+    }
+
+    // `None` makes the global an ordinary, not thread local, variable.
+    #[inline]
+    pub fn set_thread_local_mode(&self, mode: Option<ThreadLocalMode>) {
+        let mode = match mode {
+            Some(mode) => mode.into(),
+            None => LLVMThreadLocalMode::LLVMNotThreadLocal,
+        };
+        unsafe { LLVMSetThreadLocalMode(self.llvm_global, mode) }
+    }
+
+    pub fn get_thread_local_mode(&self) -> Option<ThreadLocalMode> {
+        match unsafe { LLVMGetThreadLocalMode(self.llvm_global) } {
+            LLVMThreadLocalMode::LLVMNotThreadLocal => None,
+            LLVMThreadLocalMode::LLVMGeneralDynamicTLSModel => {
+                Some(ThreadLocalMode::GeneralDynamic)
+            }
+            LLVMThreadLocalMode::LLVMLocalDynamicTLSModel => Some(ThreadLocalMode::LocalDynamic),
+            LLVMThreadLocalMode::LLVMInitialExecTLSModel => Some(ThreadLocalMode::InitialExec),
+            LLVMThreadLocalMode::LLVMLocalExecTLSModel => Some(ThreadLocalMode::LocalExec),
+        }
+    }
+
+    #[inline]
+    pub fn is_thread_local(&self) -> bool {
+        unsafe { LLVMIsThreadLocal(self.llvm_global) == 1 }
+    }
+
+    #[inline]
+    pub fn set_unnamed_addr(&self, unnamed_addr: UnnamedAddr) {
+        unsafe { LLVMSetUnnamedAddress(self.llvm_global, unnamed_addr.into()) }
+    }
+
+    #[inline]
+    pub fn get_unnamed_addr(&self) -> UnnamedAddr {
+        unsafe { LLVMGetUnnamedAddress(self.llvm_global).into() }
+    }
+
+    #[inline]
+    pub fn set_externally_initialized(&self, externally_initialized: bool) {
+        unsafe {
+            LLVMSetExternallyInitialized(
+                self.llvm_global,
+                if externally_initialized { 1 } else { 0 },
+            )
+        }
+    }
+
+    #[inline]
+    pub fn is_externally_initialized(&self) -> bool {
+        unsafe { LLVMIsExternallyInitialized(self.llvm_global) == 1 }
+    }
+
+    #[inline]
+    pub fn set_comdat(&self, comdat: Comdat<'ctx>) {
+        unsafe { LLVMSetComdat(self.llvm_global, comdat.as_ref()) }
+    }
+
+    #[inline]
+    pub fn get_comdat(&self) -> Option<Comdat<'ctx>> {
+        let comdat = unsafe { LLVMGetComdat(self.llvm_global) };
+        if comdat.is_null() {
+            None
+        } else {
+            Some(Comdat::from_ptr(comdat))
+        }
+    }
+
+    /// Removes the global from its module and frees it.
+    ///
+    /// # Safety
+    ///
+    /// Nothing may use the global any more, and no copy of this handle, or of
+    /// its pointer value, may be used again.
+    #[inline]
+    pub unsafe fn delete(self) {
+        LLVMDeleteGlobal(self.llvm_global)
+    }
+}
+
+impl<'ctx> AnyValue<'ctx> for GlobalVariable<'ctx> {
+    #[inline]
+    fn as_value_ref(&self) -> LLVMValueRef {
+        self.llvm_global
+    }
+
+    #[inline]
+    unsafe fn from_value_ref(value: LLVMValueRef) -> Self {
+        GlobalVariable::from_ptr(value)
+    }
+}
+
+impl<'ctx> From<GlobalVariable<'ctx>> for Value<'ctx> {
+    #[inline]
+    fn from(global: GlobalVariable<'ctx>) -> Value<'ctx> {
+        Value::from_ptr(global.llvm_global)
+    }
+}
+
+impl<'ctx> From<GlobalVariable<'ctx>> for PointerValue<'ctx> {
+    #[inline]
+    fn from(global: GlobalVariable<'ctx>) -> PointerValue<'ctx> {
+        global.as_pointer_value()
+    }
+}
+
+impl<'ctx> TryFrom<Value<'ctx>> for GlobalVariable<'ctx> {
+    type Error = Value<'ctx>;
+
+    fn try_from(value: Value<'ctx>) -> Result<Self, Self::Error> {
+        if unsafe { LLVMIsAGlobalVariable(value.as_ref()).is_null() } {
+            Err(value)
+        } else {
+            Ok(GlobalVariable::from_ptr(value.as_ref()))
+        }
+    }
+}
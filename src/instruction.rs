@@ -0,0 +1,204 @@
+extern crate llvm_sys;
+
+use self::llvm_sys::core::*;
+use self::llvm_sys::prelude::*;
+use self::llvm_sys::LLVMOpcode;
+use basic_block::BasicBlock;
+use context::ContextMarker;
+use fast_math::{self, FastMathFlags};
+use std::convert::TryFrom;
+use std::marker::PhantomData;
+use values::{AnyValue, Value};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Instruction<'ctx> {
+    llvm_instruction: LLVMValueRef,
+    _context: ContextMarker<'ctx>,
+}
+
+impl<'ctx> Instruction<'ctx> {
+    pub fn from_ptr(instruction_ptr: LLVMValueRef) -> Instruction<'ctx> {
+        Instruction {
+            llvm_instruction: instruction_ptr,
+            _context: PhantomData,
+        }
+    }
+
+    pub fn as_ref(&self) -> LLVMValueRef {
+        self.llvm_instruction
+    }
+
+    #[inline]
+    pub fn get_opcode(&self) -> LLVMOpcode {
+        unsafe { LLVMGetInstructionOpcode(self.llvm_instruction) }
+    }
+
+    #[inline]
+    pub fn is_terminator(&self) -> bool {
+        unsafe { !LLVMIsATerminatorInst(self.llvm_instruction).is_null() }
+    }
+
+    // Float arithmetic and compares, and calls, phis and selects of float
+    // type, can carry fast-math flags; nothing else can.
+    #[inline]
+    pub fn can_use_fast_math_flags(&self) -> bool {
+        fast_math::can_use(self.llvm_instruction)
+    }
+
+    #[inline]
+    pub fn get_fast_math_flags(&self) -> FastMathFlags {
+        fast_math::get(self.llvm_instruction)
+    }
+
+    // Replaces the flags. Ignored when the instruction cannot carry them.
+    #[inline]
+    pub fn set_fast_math_flags(&self, flags: FastMathFlags) {
+        fast_math::set(self.llvm_instruction, flags)
+    }
+
+    // None once the instruction has been taken out of its block.
+    #[inline]
+    pub fn get_parent(&self) -> Option<BasicBlock<'ctx>> {
+        BasicBlock::from_nullable(unsafe { LLVMGetInstructionParent(self.llvm_instruction) })
+    }
+
+    #[inline]
+    pub fn get_next_instruction(&self) -> Option<Instruction<'ctx>> {
+        Instruction::from_nullable(unsafe { LLVMGetNextInstruction(self.llvm_instruction) })
+    }
+
+    #[inline]
+    pub fn get_previous_instruction(&self) -> Option<Instruction<'ctx>> {
+        Instruction::from_nullable(unsafe { LLVMGetPreviousInstruction(self.llvm_instruction) })
+    }
+
+    #[inline]
+    pub fn get_num_operands(&self) -> u32 {
+        unsafe { LLVMGetNumOperands(self.llvm_instruction) as u32 }
+    }
+
+    // Basic block operands, such as a branch's destinations, come back as
+    // values too; BasicBlock::from_value turns them back into blocks.
+    pub fn get_operand(&self, index: u32) -> Option<Value<'ctx>> {
+        if index >= self.get_num_operands() {
+            return None;
+        }
+        let operand = unsafe { LLVMGetOperand(self.llvm_instruction, index) };
+        if operand.is_null() {
+            None
+        } else {
+            Some(Value::from_ptr(operand))
+        }
+    }
+
+    pub fn get_operands(&self) -> Vec<Value<'ctx>> {
+        (0..self.get_num_operands())
+            .filter_map(|index| self.get_operand(index))
+            .collect()
+    }
+
+    // Returns false when there is no operand at that index.
+    pub fn set_operand<V: AnyValue<'ctx>>(&self, index: u32, value: V) -> bool {
+        if index >= self.get_num_operands() {
+            return false;
+        }
+        unsafe { LLVMSetOperand(self.llvm_instruction, index, value.as_value_ref()) }
+        true
+    }
+
+    // Every instruction or constant that has this instruction as an operand,
+    // once for each time it does.
+    pub fn get_users(&self) -> Vec<Value<'ctx>> {
+        let mut users = Vec::new();
+        let mut use_ref = unsafe { LLVMGetFirstUse(self.llvm_instruction) };
+        while !use_ref.is_null() {
+            users.push(Value::from_ptr(unsafe { LLVMGetUser(use_ref) }));
+            use_ref = unsafe { LLVMGetNextUse(use_ref) };
+        }
+        users
+    }
+
+    #[inline]
+    pub fn has_uses(&self) -> bool {
+        unsafe { !LLVMGetFirstUse(self.llvm_instruction).is_null() }
+    }
+
+    #[inline]
+    pub fn replace_all_uses_with<V: AnyValue<'ctx>>(&self, value: V) {
+        unsafe { LLVMReplaceAllUsesWith(self.llvm_instruction, value.as_value_ref()) }
+    }
+
+    /// Unlinks the instruction from its block and frees it.
+    ///
+    /// # Safety
+    ///
+    /// The instruction must not have uses left, and no copy of this handle
+    /// may be used again.
+    #[inline]
+    pub unsafe fn erase_from_parent(self) {
+        LLVMInstructionEraseFromParent(self.llvm_instruction)
+    }
+
+    pub(crate) fn from_nullable(instruction: LLVMValueRef) -> Option<Instruction<'ctx>> {
+        if instruction.is_null() {
+            None
+        } else {
+            Some(Instruction::from_ptr(instruction))
+        }
+    }
+}
+
+impl<'ctx> AnyValue<'ctx> for Instruction<'ctx> {
+    #[inline]
+    fn as_value_ref(&self) -> LLVMValueRef {
+        self.llvm_instruction
+    }
+
+    #[inline]
+    unsafe fn from_value_ref(value: LLVMValueRef) -> Self {
+        Instruction::from_ptr(value)
+    }
+}
+
+impl<'ctx> From<Instruction<'ctx>> for Value<'ctx> {
+    #[inline]
+    fn from(instruction: Instruction<'ctx>) -> Value<'ctx> {
+        Value::from_ptr(instruction.llvm_instruction)
+    }
+}
+
+impl<'ctx> TryFrom<Value<'ctx>> for Instruction<'ctx> {
+    type Error = Value<'ctx>;
+
+    fn try_from(value: Value<'ctx>) -> Result<Self, Self::Error> {
+        if unsafe { LLVMIsAInstruction(value.as_ref()).is_null() } {
+            Err(value)
+        } else {
+            Ok(Instruction::from_ptr(value.as_ref()))
+        }
+    }
+}
+
+// Walks the instructions of a block from first to last.
+#[derive(Debug)]
+pub struct InstructionIter<'ctx> {
+    next: Option<Instruction<'ctx>>,
+}
+
+impl<'ctx> InstructionIter<'ctx> {
+    pub(crate) fn new(first: Option<Instruction<'ctx>>) -> InstructionIter<'ctx> {
+        InstructionIter { next: first }
+    }
+}
+
+impl<'ctx> Iterator for InstructionIter<'ctx> {
+    type Item = Instruction<'ctx>;
+
+    // The next instruction is looked up before handing this one out, so the
+    // caller may erase it without cutting the walk short.
+    fn next(&mut self) -> Option<Instruction<'ctx>> {
+        let current = self.next?;
+        self.next = current.get_next_instruction();
+        Some(current)
+    }
+}
@@ -1,44 +1,92 @@
 extern crate libc;
 extern crate llvm_sys;
 
+mod atomic;
+mod attributes;
+mod basic_block;
 mod builder;
+mod call_site;
+mod codegen;
 mod context;
-mod cstring_manager;
+mod diagnostic;
 mod engine;
+mod fast_math;
 mod function;
+mod global_alias;
+mod global_variable;
+mod instruction;
+mod linkage;
+mod memory_buffer;
+mod message;
 mod module;
+mod pass_manager;
 mod phi;
-mod struct_type;
+mod reduction;
+mod small_cstr;
+mod types;
+mod values;
 
-pub use self::builder::Builder;
+pub use self::atomic::{AtomicOrdering, AtomicRMWBinOp, SyncScope};
+pub use self::attributes::{Attribute, AttributeLoc, CallConv};
+pub use self::basic_block::BasicBlock;
+pub use self::builder::{Builder, InsertPointGuard};
+pub use self::call_site::CallSite;
+pub use self::codegen::{
+    CodeModel, FileType, RelocMode, Target, TargetMachine, TargetMachineBuilder,
+};
 pub use self::context::Context;
+pub use self::diagnostic::{Diagnostic, LinkError};
 pub use self::engine::{Engine, FuncallResult};
+pub use self::fast_math::FastMathFlags;
 pub use self::function::Function;
+pub use self::global_alias::{GlobalAlias, GlobalIFunc};
+pub use self::global_variable::{Comdat, ComdatSelectionKind, GlobalVariable, ThreadLocalMode};
+pub use self::instruction::{Instruction, InstructionIter};
+pub use self::linkage::{Linkage, UnnamedAddr, Visibility};
 pub use self::llvm_sys::core::*;
 pub use self::llvm_sys::prelude::*;
 pub use self::llvm_sys::*;
-pub use self::module::Module;
+pub use self::module::{
+    AliasIter, FunctionIter, GlobalIter, IFuncIter, Module, ModuleFlagBehavior,
+};
+pub use self::pass_manager::{FunctionPassManager, PassManager, PassManagerBuilder};
 pub use self::phi::Phi;
-pub use self::struct_type::Struct;
+pub use self::reduction::VectorReduction;
+pub use self::types::{
+    AnyType, ArrayType, FloatType, FunctionType, IntType, PointerType, StructType, Type,
+    VectorType, VoidType,
+};
+pub use self::values::{
+    AnyValue, ArrayValue, FloatMathValue, FloatValue, FloatVectorValue, FunctionValue,
+    IntCompareValue, IntMathValue, IntValue, IntVectorValue, PointerValue, StructValue, Value,
+    VectorValue,
+};
 use llvm_sys::target_machine::LLVMCodeGenOptLevel;
 
-pub enum CPU {
-    Native,
-    X86_64,
-    I686,
-}
-
-use std::ffi::CString;
-impl From<CPU> for CString {
-    fn from(cpu: CPU) -> CString {
-        match cpu {
-            CPU::Native => CString::new("native").expect(""),
-            CPU::X86_64 => CString::new("x86-64").expect(""),
-            CPU::I686 => CString::new("i686").expect(""),
-        }
-    }
+// The backends LLVM can be built with. Each one is only linked in when its
+// target-* cargo feature is enabled, so a wrapper built against an LLVM
+// without some of them still links.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Arch {
+    AArch64,
+    AMDGPU,
+    ARM,
+    BPF,
+    Hexagon,
+    Lanai,
+    Mips,
+    MSP430,
+    NVPTX,
+    PowerPC,
+    RISCV,
+    Sparc,
+    SystemZ,
+    WebAssembly,
+    X86,
+    XCore,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum CodegenLevel {
     O0,
     O1,
@@ -59,268 +107,446 @@ impl From<CodegenLevel> for LLVMCodeGenOptLevel {
 
 #[allow(non_snake_case)]
 pub mod LLVM {
+    use codegen::{FileType, Target};
     use llvm_sys::core::*;
-    use llvm_sys::prelude::*;
     use llvm_sys::target;
-    use llvm_sys::target_machine::*;
+    use llvm_sys::target_machine::{LLVMGetHostCPUFeatures, LLVMGetHostCPUName};
+    use message::take_message;
     use module::Module;
-    use std::ffi::CStr;
-    use std::ffi::CString;
     use std::os::raw::c_uint;
-    use std::ptr;
+    use Arch;
     use CodegenLevel;
-    use CPU;
 
-    pub fn initialize() {
+    // Sets up the target of the machine we are running on, which is all the
+    // JIT and emitting for the host need.
+    pub fn initialize() -> Result<(), String> {
         unsafe {
             if target::LLVM_InitializeNativeTarget() != 0 {
-                panic!("Could not initialise target");
+                return Err("could not initialize the native target".to_string());
             }
             if target::LLVM_InitializeNativeAsmPrinter() != 0 {
-                panic!("Could not initialise ASM Printer");
+                return Err("could not initialize the native asm printer".to_string());
+            }
+            if target::LLVM_InitializeNativeAsmParser() != 0 {
+                return Err("could not initialize the native asm parser".to_string());
             }
         }
+        Ok(())
     }
 
-    pub fn emit(
-        module: &Module,
-        opt_level: CodegenLevel,
-        out: String,
-        cpu: CPU,
-    ) -> Result<(), String> {
-        let features = CString::new("").expect("");
-        let cpu: CString = cpu.into();
-        let out = CString::new(out).expect("");
+    // Every target LLVM was built with, for cross compiling to any of them.
+    pub fn initialize_all_targets() {
         unsafe {
-            let triple = LLVMGetDefaultTargetTriple();
-            let target = LLVMGetFirstTarget();
-            let reloc_mode = LLVMRelocMode::LLVMRelocDefault;
-            let code_model = LLVMCodeModel::LLVMCodeModelDefault;
-            let target_machine = LLVMCreateTargetMachine(
-                target,
-                triple,
-                cpu.as_ptr(),
-                features.as_ptr(),
-                opt_level.into(),
-                reloc_mode,
-                code_model,
-            );
-            let file_type = LLVMCodeGenFileType::LLVMObjectFile;
-
-            let mut err_str = ptr::null_mut();
-            let res = LLVMTargetMachineEmitToFile(
-                target_machine,
-                module.as_ref(),
-                out.as_ptr() as *mut i8,
-                file_type,
-                &mut err_str,
-            );
+            target::LLVM_InitializeAllTargetInfos();
+            target::LLVM_InitializeAllTargets();
+            target::LLVM_InitializeAllTargetMCs();
+            target::LLVM_InitializeAllAsmPrinters();
+            target::LLVM_InitializeAllAsmParsers();
+            target::LLVM_InitializeAllDisassemblers();
+        }
+    }
 
-            if res == 1 {
-                let err = CStr::from_ptr(err_str);
-                Err(err.to_string_lossy().into_owned())
-            } else {
+    macro_rules! initialize_arch {
+        ($arch:expr, $feature:literal, $($init:ident),*) => {{
+            #[cfg(feature = $feature)]
+            unsafe {
+                $( target::$init(); )*
                 Ok(())
             }
+            #[cfg(not(feature = $feature))]
+            Err(format!(
+                "{:?} support is not built in, enable the {} feature",
+                $arch, $feature
+            ))
+        }};
+    }
+
+    pub fn initialize_target(arch: Arch) -> Result<(), String> {
+        match arch {
+            Arch::AArch64 => initialize_arch!(
+                arch,
+                "target-aarch64",
+                LLVMInitializeAArch64TargetInfo,
+                LLVMInitializeAArch64Target,
+                LLVMInitializeAArch64TargetMC,
+                LLVMInitializeAArch64AsmPrinter,
+                LLVMInitializeAArch64AsmParser
+            ),
+            Arch::AMDGPU => initialize_arch!(
+                arch,
+                "target-amdgpu",
+                LLVMInitializeAMDGPUTargetInfo,
+                LLVMInitializeAMDGPUTarget,
+                LLVMInitializeAMDGPUTargetMC,
+                LLVMInitializeAMDGPUAsmPrinter,
+                LLVMInitializeAMDGPUAsmParser
+            ),
+            Arch::ARM => initialize_arch!(
+                arch,
+                "target-arm",
+                LLVMInitializeARMTargetInfo,
+                LLVMInitializeARMTarget,
+                LLVMInitializeARMTargetMC,
+                LLVMInitializeARMAsmPrinter,
+                LLVMInitializeARMAsmParser
+            ),
+            Arch::BPF => initialize_arch!(
+                arch,
+                "target-bpf",
+                LLVMInitializeBPFTargetInfo,
+                LLVMInitializeBPFTarget,
+                LLVMInitializeBPFTargetMC,
+                LLVMInitializeBPFAsmPrinter
+            ),
+            Arch::Hexagon => initialize_arch!(
+                arch,
+                "target-hexagon",
+                LLVMInitializeHexagonTargetInfo,
+                LLVMInitializeHexagonTarget,
+                LLVMInitializeHexagonTargetMC,
+                LLVMInitializeHexagonAsmPrinter
+            ),
+            Arch::Lanai => initialize_arch!(
+                arch,
+                "target-lanai",
+                LLVMInitializeLanaiTargetInfo,
+                LLVMInitializeLanaiTarget,
+                LLVMInitializeLanaiTargetMC,
+                LLVMInitializeLanaiAsmPrinter,
+                LLVMInitializeLanaiAsmParser
+            ),
+            Arch::Mips => initialize_arch!(
+                arch,
+                "target-mips",
+                LLVMInitializeMipsTargetInfo,
+                LLVMInitializeMipsTarget,
+                LLVMInitializeMipsTargetMC,
+                LLVMInitializeMipsAsmPrinter,
+                LLVMInitializeMipsAsmParser
+            ),
+            Arch::MSP430 => initialize_arch!(
+                arch,
+                "target-msp430",
+                LLVMInitializeMSP430TargetInfo,
+                LLVMInitializeMSP430Target,
+                LLVMInitializeMSP430TargetMC,
+                LLVMInitializeMSP430AsmPrinter
+            ),
+            Arch::NVPTX => initialize_arch!(
+                arch,
+                "target-nvptx",
+                LLVMInitializeNVPTXTargetInfo,
+                LLVMInitializeNVPTXTarget,
+                LLVMInitializeNVPTXTargetMC,
+                LLVMInitializeNVPTXAsmPrinter
+            ),
+            Arch::PowerPC => initialize_arch!(
+                arch,
+                "target-powerpc",
+                LLVMInitializePowerPCTargetInfo,
+                LLVMInitializePowerPCTarget,
+                LLVMInitializePowerPCTargetMC,
+                LLVMInitializePowerPCAsmPrinter,
+                LLVMInitializePowerPCAsmParser
+            ),
+            Arch::RISCV => initialize_arch!(
+                arch,
+                "target-riscv",
+                LLVMInitializeRISCVTargetInfo,
+                LLVMInitializeRISCVTarget,
+                LLVMInitializeRISCVTargetMC,
+                LLVMInitializeRISCVAsmPrinter,
+                LLVMInitializeRISCVAsmParser
+            ),
+            Arch::Sparc => initialize_arch!(
+                arch,
+                "target-sparc",
+                LLVMInitializeSparcTargetInfo,
+                LLVMInitializeSparcTarget,
+                LLVMInitializeSparcTargetMC,
+                LLVMInitializeSparcAsmPrinter,
+                LLVMInitializeSparcAsmParser
+            ),
+            Arch::SystemZ => initialize_arch!(
+                arch,
+                "target-systemz",
+                LLVMInitializeSystemZTargetInfo,
+                LLVMInitializeSystemZTarget,
+                LLVMInitializeSystemZTargetMC,
+                LLVMInitializeSystemZAsmPrinter,
+                LLVMInitializeSystemZAsmParser
+            ),
+            Arch::WebAssembly => initialize_arch!(
+                arch,
+                "target-webassembly",
+                LLVMInitializeWebAssemblyTargetInfo,
+                LLVMInitializeWebAssemblyTarget,
+                LLVMInitializeWebAssemblyTargetMC,
+                LLVMInitializeWebAssemblyAsmPrinter,
+                LLVMInitializeWebAssemblyAsmParser
+            ),
+            Arch::X86 => initialize_arch!(
+                arch,
+                "target-x86",
+                LLVMInitializeX86TargetInfo,
+                LLVMInitializeX86Target,
+                LLVMInitializeX86TargetMC,
+                LLVMInitializeX86AsmPrinter,
+                LLVMInitializeX86AsmParser
+            ),
+            Arch::XCore => initialize_arch!(
+                arch,
+                "target-xcore",
+                LLVMInitializeXCoreTargetInfo,
+                LLVMInitializeXCoreTarget,
+                LLVMInitializeXCoreTargetMC,
+                LLVMInitializeXCoreAsmPrinter
+            ),
         }
     }
 
+    // The name LLVM uses for the CPU we are running on, such as "skylake".
+    pub fn host_cpu_name() -> String {
+        take_message(unsafe { LLVMGetHostCPUName() })
+    }
+
+    // The features of the CPU we are running on, as "+sse2,-avx512f,...".
+    pub fn host_cpu_features() -> String {
+        take_message(unsafe { LLVMGetHostCPUFeatures() })
+    }
+
+    // Compiles the module to an object file for the host.
+    pub fn emit(
+        module: &Module,
+        opt_level: CodegenLevel,
+        out: String,
+        cpu: &str,
+    ) -> Result<(), String> {
+        let triple = Target::default_triple();
+        let target_machine = Target::from_triple(&triple)?
+            .create_target_machine(&triple)
+            .cpu(cpu)
+            .opt_level(opt_level)
+            .build()?;
+        target_machine.emit_to_file(module, &out, FileType::Object)
+    }
+
     pub mod Type {
         use super::*;
+        use types::{AnyType, FloatType, IntType, PointerType, Type, VectorType, VoidType};
 
         #[inline]
-        pub fn PointerType(elem_type: LLVMTypeRef, address_space: c_uint) -> LLVMTypeRef {
-            unsafe { LLVMPointerType(elem_type, address_space) }
+        pub fn PointerType<'ctx, T: AnyType<'ctx>>(
+            elem_type: T,
+            address_space: c_uint,
+        ) -> PointerType<'ctx> {
+            elem_type.ptr_type(address_space)
         }
         #[inline]
-        pub fn Pointer(elem_type: LLVMTypeRef, address_space: c_uint) -> LLVMTypeRef {
-            unsafe { LLVMPointerType(elem_type, address_space) }
+        pub fn Pointer<'ctx, T: AnyType<'ctx>>(
+            elem_type: T,
+            address_space: c_uint,
+        ) -> PointerType<'ctx> {
+            elem_type.ptr_type(address_space)
         }
         #[inline]
-        pub fn Void() -> LLVMTypeRef {
-            unsafe { LLVMVoidType() }
+        pub fn Vector<'ctx, T: AnyType<'ctx>>(elem_type: T, count: c_uint) -> VectorType<'ctx> {
+            elem_type.vector_type(count)
         }
         #[inline]
-        pub fn Int(num_bits: c_uint) -> LLVMTypeRef {
-            unsafe { LLVMIntType(num_bits) }
+        pub fn Void() -> VoidType<'static> {
+            unsafe { VoidType::from_type_ref(LLVMVoidType()) }
         }
         #[inline]
-        pub fn Int1() -> LLVMTypeRef {
-            unsafe { LLVMInt1Type() }
+        pub fn Int(num_bits: c_uint) -> IntType<'static> {
+            unsafe { IntType::from_type_ref(LLVMIntType(num_bits)) }
         }
         #[inline]
-        pub fn Int8() -> LLVMTypeRef {
-            unsafe { LLVMInt8Type() }
+        pub fn Int1() -> IntType<'static> {
+            unsafe { IntType::from_type_ref(LLVMInt1Type()) }
         }
         #[inline]
-        pub fn Int16() -> LLVMTypeRef {
-            unsafe { LLVMInt16Type() }
+        pub fn Int8() -> IntType<'static> {
+            unsafe { IntType::from_type_ref(LLVMInt8Type()) }
         }
         #[inline]
-        pub fn Int32() -> LLVMTypeRef {
-            unsafe { LLVMInt32Type() }
+        pub fn Int16() -> IntType<'static> {
+            unsafe { IntType::from_type_ref(LLVMInt16Type()) }
         }
         #[inline]
-        pub fn Int64() -> LLVMTypeRef {
-            unsafe { LLVMInt64Type() }
+        pub fn Int32() -> IntType<'static> {
+            unsafe { IntType::from_type_ref(LLVMInt32Type()) }
         }
         #[inline]
-        pub fn Int128() -> LLVMTypeRef {
-            unsafe { LLVMInt128Type() }
+        pub fn Int64() -> IntType<'static> {
+            unsafe { IntType::from_type_ref(LLVMInt64Type()) }
         }
         #[inline]
-        pub fn Half() -> LLVMTypeRef {
-            unsafe { LLVMHalfType() }
+        pub fn Int128() -> IntType<'static> {
+            unsafe { IntType::from_type_ref(LLVMInt128Type()) }
         }
         #[inline]
-        pub fn Float() -> LLVMTypeRef {
-            unsafe { LLVMFloatType() }
+        pub fn Half() -> FloatType<'static> {
+            unsafe { FloatType::from_type_ref(LLVMHalfType()) }
         }
         #[inline]
-        pub fn Double() -> LLVMTypeRef {
-            unsafe { LLVMDoubleType() }
+        pub fn Float() -> FloatType<'static> {
+            unsafe { FloatType::from_type_ref(LLVMFloatType()) }
         }
         #[inline]
-        pub fn FP128() -> LLVMTypeRef {
-            unsafe { LLVMFP128Type() }
+        pub fn Double() -> FloatType<'static> {
+            unsafe { FloatType::from_type_ref(LLVMDoubleType()) }
         }
         #[inline]
-        pub fn X86FP80() -> LLVMTypeRef {
-            unsafe { LLVMX86FP80Type() }
+        pub fn FP128() -> FloatType<'static> {
+            unsafe { FloatType::from_type_ref(LLVMFP128Type()) }
         }
         #[inline]
-        pub fn PPCFP128() -> LLVMTypeRef {
-            unsafe { LLVMPPCFP128Type() }
+        pub fn X86FP80() -> FloatType<'static> {
+            unsafe { FloatType::from_type_ref(LLVMX86FP80Type()) }
         }
         #[inline]
-        pub fn X86MMX() -> LLVMTypeRef {
-            unsafe { LLVMX86MMXType() }
+        pub fn PPCFP128() -> FloatType<'static> {
+            unsafe { FloatType::from_type_ref(LLVMPPCFP128Type()) }
         }
         #[inline]
-        pub fn Label() -> LLVMTypeRef {
-            unsafe { LLVMLabelType() }
+        pub fn X86MMX() -> Type<'static> {
+            unsafe { Type::from_ptr(LLVMX86MMXType()) }
         }
         #[inline]
-        pub fn CharPointer() -> LLVMTypeRef {
-            Type::PointerType(Type::Int8(), 0)
+        pub fn Label() -> Type<'static> {
+            unsafe { Type::from_ptr(LLVMLabelType()) }
         }
         #[inline]
-        pub fn Int8Pointer() -> LLVMTypeRef {
-            Type::PointerType(Type::Int8(), 0)
+        pub fn CharPointer() -> PointerType<'static> {
+            Int8().ptr_type(0)
+        }
+        #[inline]
+        pub fn Int8Pointer() -> PointerType<'static> {
+            Int8().ptr_type(0)
         }
     }
 
     pub mod Const {
         use super::*;
+        use types::{AnyType, VectorType};
+        use values::{AnyValue, FloatValue, IntValue, VectorValue};
 
         #[inline]
-        pub fn SInt(num_bits: c_uint, val: u64) -> LLVMValueRef {
-            unsafe { LLVMConstInt(LLVMIntType(num_bits), val, 1) }
+        pub fn SInt(num_bits: c_uint, val: u64) -> IntValue<'static> {
+            Type::Int(num_bits).const_int(val, true)
         }
         #[inline]
-        pub fn UInt(num_bits: c_uint, val: u64) -> LLVMValueRef {
-            unsafe { LLVMConstInt(LLVMIntType(num_bits), val, 0) }
+        pub fn UInt(num_bits: c_uint, val: u64) -> IntValue<'static> {
+            Type::Int(num_bits).const_int(val, false)
         }
         #[inline]
-        pub fn SInt1(val: u64) -> LLVMValueRef {
-            unsafe { LLVMConstInt(LLVMInt1Type(), val, 1) }
+        pub fn SInt1(val: u64) -> IntValue<'static> {
+            Type::Int1().const_int(val, true)
         }
         #[inline]
-        pub fn UInt1(val: u64) -> LLVMValueRef {
-            unsafe { LLVMConstInt(LLVMInt1Type(), val, 0) }
+        pub fn UInt1(val: u64) -> IntValue<'static> {
+            Type::Int1().const_int(val, false)
         }
         #[inline]
-        pub fn SInt8(val: u64) -> LLVMValueRef {
-            unsafe { LLVMConstInt(LLVMInt8Type(), val, 1) }
+        pub fn SInt8(val: u64) -> IntValue<'static> {
+            Type::Int8().const_int(val, true)
         }
         #[inline]
-        pub fn UInt8(val: u64) -> LLVMValueRef {
-            unsafe { LLVMConstInt(LLVMInt8Type(), val, 0) }
+        pub fn UInt8(val: u64) -> IntValue<'static> {
+            Type::Int8().const_int(val, false)
         }
         #[inline]
-        pub fn SInt16(val: u64) -> LLVMValueRef {
-            unsafe { LLVMConstInt(LLVMInt16Type(), val, 1) }
+        pub fn SInt16(val: u64) -> IntValue<'static> {
+            Type::Int16().const_int(val, true)
         }
         #[inline]
-        pub fn UInt16(val: u64) -> LLVMValueRef {
-            unsafe { LLVMConstInt(LLVMInt16Type(), val, 0) }
+        pub fn UInt16(val: u64) -> IntValue<'static> {
+            Type::Int16().const_int(val, false)
         }
         #[inline]
-        pub fn SInt32(val: u64) -> LLVMValueRef {
-            unsafe { LLVMConstInt(LLVMInt32Type(), val, 1) }
+        pub fn SInt32(val: u64) -> IntValue<'static> {
+            Type::Int32().const_int(val, true)
         }
         #[inline]
-        pub fn UInt32(val: u64) -> LLVMValueRef {
-            unsafe { LLVMConstInt(LLVMInt32Type(), val, 0) }
+        pub fn UInt32(val: u64) -> IntValue<'static> {
+            Type::Int32().const_int(val, false)
         }
         #[inline]
-        pub fn SInt64(val: u64) -> LLVMValueRef {
-            unsafe { LLVMConstInt(LLVMInt64Type(), val, 1) }
+        pub fn SInt64(val: u64) -> IntValue<'static> {
+            Type::Int64().const_int(val, true)
         }
         #[inline]
-        pub fn UInt64(val: u64) -> LLVMValueRef {
-            unsafe { LLVMConstInt(LLVMInt64Type(), val, 0) }
+        pub fn UInt64(val: u64) -> IntValue<'static> {
+            Type::Int64().const_int(val, false)
         }
         #[inline]
-        pub fn SInt128(val: u64) -> LLVMValueRef {
-            unsafe { LLVMConstInt(LLVMInt128Type(), val, 1) }
+        pub fn SInt128(val: u64) -> IntValue<'static> {
+            Type::Int128().const_int(val, true)
         }
         #[inline]
-        pub fn UInt128(val: u64) -> LLVMValueRef {
-            unsafe { LLVMConstInt(LLVMInt128Type(), val, 0) }
+        pub fn UInt128(val: u64) -> IntValue<'static> {
+            Type::Int128().const_int(val, false)
         }
 
         #[inline]
-        pub fn Half(val: f64) -> LLVMValueRef {
-            unsafe { LLVMConstReal(LLVMHalfType(), val) }
+        pub fn Half(val: f64) -> FloatValue<'static> {
+            Type::Half().const_float(val)
+        }
+        #[inline]
+        pub fn Float(val: f64) -> FloatValue<'static> {
+            Type::Float().const_float(val)
+        }
+        #[inline]
+        pub fn Double(val: f64) -> FloatValue<'static> {
+            Type::Double().const_float(val)
         }
         #[inline]
-        pub fn Float(val: f64) -> LLVMValueRef {
-            unsafe { LLVMConstReal(LLVMFloatType(), val) }
+        pub fn FP128(val: f64) -> FloatValue<'static> {
+            Type::FP128().const_float(val)
         }
         #[inline]
-        pub fn Double(val: f64) -> LLVMValueRef {
-            unsafe { LLVMConstReal(LLVMDoubleType(), val) }
+        pub fn X86FP80(val: f64) -> FloatValue<'static> {
+            Type::X86FP80().const_float(val)
         }
         #[inline]
-        pub fn FP128(val: f64) -> LLVMValueRef {
-            unsafe { LLVMConstReal(LLVMFP128Type(), val) }
+        pub fn PPCFP128(val: f64) -> FloatValue<'static> {
+            Type::PPCFP128().const_float(val)
         }
+
         #[inline]
-        pub fn X86FP80(val: f64) -> LLVMValueRef {
-            unsafe { LLVMConstReal(LLVMX86FP80Type(), val) }
+        pub fn Vector<'ctx, V: AnyValue<'ctx>>(vals: &[V]) -> Option<VectorValue<'ctx>> {
+            VectorType::new_const_vector(vals)
         }
         #[inline]
-        pub fn PPCFP128(val: f64) -> LLVMValueRef {
-            unsafe { LLVMConstReal(LLVMPPCFP128Type(), val) }
+        pub fn Splat<'ctx, V: AnyValue<'ctx>>(val: V, count: c_uint) -> Option<VectorValue<'ctx>> {
+            val.get_type().vector_type(count).const_splat(val)
         }
     }
 }
 
+// Shorthand for `FunctionType::new`, kept for existing callers:
+// `fn_type!(ret, params...)`, with a trailing `,,,` for varargs.
 #[macro_export]
 macro_rules! fn_type {
     ($result_type:expr) => (
-        unsafe {
-            let mut param_types = [];
-            LLVMFunctionType($result_type, param_types.as_mut_ptr(), param_types.len() as u32, 0)
-        }
+        $crate::FunctionType::new($result_type, &[], false)
     );
     ($result_type:expr,,,) => (
-        unsafe {
-            let mut param_types = [];
-            LLVMFunctionType($result_type, param_types.as_mut_ptr(), param_types.len() as u32, 1)
-        }
+        $crate::FunctionType::new($result_type, &[], true)
     );
     ($result_type:expr, $( $param_type:expr ),* ) => (
-        unsafe {
-            let mut param_types = [ $( $param_type ),* ];
-            LLVMFunctionType($result_type, param_types.as_mut_ptr(), param_types.len() as u32, 0)
-        }
+        $crate::FunctionType::new(
+            $result_type,
+            &[ $( $crate::AnyType::as_any_type(&$param_type) ),* ],
+            false,
+        )
     );
     ($result_type:expr, $( $param_type:expr ),* ,,,) => (
-        unsafe {
-            let mut param_types = [ $( $param_type ),* ];
-            LLVMFunctionType($result_type, param_types.as_mut_ptr(), param_types.len() as u32, 1)
-        }
+        $crate::FunctionType::new(
+            $result_type,
+            &[ $( $crate::AnyType::as_any_type(&$param_type) ),* ],
+            true,
+        )
     )
 }
@@ -0,0 +1,117 @@
+extern crate llvm_sys;
+
+use self::llvm_sys::{LLVMLinkage, LLVMUnnamedAddr, LLVMVisibility};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Linkage {
+    External,
+    AvailableExternally,
+    LinkOnceAny,
+    LinkOnceODR,
+    WeakAny,
+    WeakODR,
+    Appending,
+    Internal,
+    Private,
+    ExternalWeak,
+    Common,
+}
+
+impl From<Linkage> for LLVMLinkage {
+    fn from(linkage: Linkage) -> LLVMLinkage {
+        match linkage {
+            Linkage::External => LLVMLinkage::LLVMExternalLinkage,
+            Linkage::AvailableExternally => LLVMLinkage::LLVMAvailableExternallyLinkage,
+            Linkage::LinkOnceAny => LLVMLinkage::LLVMLinkOnceAnyLinkage,
+            Linkage::LinkOnceODR => LLVMLinkage::LLVMLinkOnceODRLinkage,
+            Linkage::WeakAny => LLVMLinkage::LLVMWeakAnyLinkage,
+            Linkage::WeakODR => LLVMLinkage::LLVMWeakODRLinkage,
+            Linkage::Appending => LLVMLinkage::LLVMAppendingLinkage,
+            Linkage::Internal => LLVMLinkage::LLVMInternalLinkage,
+            Linkage::Private => LLVMLinkage::LLVMPrivateLinkage,
+            Linkage::ExternalWeak => LLVMLinkage::LLVMExternalWeakLinkage,
+            Linkage::Common => LLVMLinkage::LLVMCommonLinkage,
+        }
+    }
+}
+
+impl From<LLVMLinkage> for Linkage {
+    // The obsolete linkages LLVM still lists are reported as their modern equivalents.
+    fn from(linkage: LLVMLinkage) -> Linkage {
+        match linkage {
+            LLVMLinkage::LLVMAvailableExternallyLinkage => Linkage::AvailableExternally,
+            LLVMLinkage::LLVMLinkOnceAnyLinkage => Linkage::LinkOnceAny,
+            LLVMLinkage::LLVMLinkOnceODRLinkage | LLVMLinkage::LLVMLinkOnceODRAutoHideLinkage => {
+                Linkage::LinkOnceODR
+            }
+            LLVMLinkage::LLVMWeakAnyLinkage => Linkage::WeakAny,
+            LLVMLinkage::LLVMWeakODRLinkage => Linkage::WeakODR,
+            LLVMLinkage::LLVMAppendingLinkage => Linkage::Appending,
+            LLVMLinkage::LLVMInternalLinkage => Linkage::Internal,
+            LLVMLinkage::LLVMPrivateLinkage
+            | LLVMLinkage::LLVMLinkerPrivateLinkage
+            | LLVMLinkage::LLVMLinkerPrivateWeakLinkage => Linkage::Private,
+            LLVMLinkage::LLVMExternalWeakLinkage => Linkage::ExternalWeak,
+            LLVMLinkage::LLVMCommonLinkage => Linkage::Common,
+            LLVMLinkage::LLVMExternalLinkage
+            | LLVMLinkage::LLVMDLLImportLinkage
+            | LLVMLinkage::LLVMDLLExportLinkage
+            | LLVMLinkage::LLVMGhostLinkage => Linkage::External,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Visibility {
+    Default,
+    Hidden,
+    Protected,
+}
+
+impl From<Visibility> for LLVMVisibility {
+    fn from(visibility: Visibility) -> LLVMVisibility {
+        match visibility {
+            Visibility::Default => LLVMVisibility::LLVMDefaultVisibility,
+            Visibility::Hidden => LLVMVisibility::LLVMHiddenVisibility,
+            Visibility::Protected => LLVMVisibility::LLVMProtectedVisibility,
+        }
+    }
+}
+
+impl From<LLVMVisibility> for Visibility {
+    fn from(visibility: LLVMVisibility) -> Visibility {
+        match visibility {
+            LLVMVisibility::LLVMDefaultVisibility => Visibility::Default,
+            LLVMVisibility::LLVMHiddenVisibility => Visibility::Hidden,
+            LLVMVisibility::LLVMProtectedVisibility => Visibility::Protected,
+        }
+    }
+}
+
+// Whether the address of a global is significant, and if not, to whom.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UnnamedAddr {
+    No,
+    Local,
+    Global,
+}
+
+impl From<UnnamedAddr> for LLVMUnnamedAddr {
+    fn from(unnamed_addr: UnnamedAddr) -> LLVMUnnamedAddr {
+        match unnamed_addr {
+            UnnamedAddr::No => LLVMUnnamedAddr::LLVMNoUnnamedAddr,
+            UnnamedAddr::Local => LLVMUnnamedAddr::LLVMLocalUnnamedAddr,
+            UnnamedAddr::Global => LLVMUnnamedAddr::LLVMGlobalUnnamedAddr,
+        }
+    }
+}
+
+impl From<LLVMUnnamedAddr> for UnnamedAddr {
+    fn from(unnamed_addr: LLVMUnnamedAddr) -> UnnamedAddr {
+        match unnamed_addr {
+            LLVMUnnamedAddr::LLVMNoUnnamedAddr => UnnamedAddr::No,
+            LLVMUnnamedAddr::LLVMLocalUnnamedAddr => UnnamedAddr::Local,
+            LLVMUnnamedAddr::LLVMGlobalUnnamedAddr => UnnamedAddr::Global,
+        }
+    }
+}
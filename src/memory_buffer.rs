@@ -0,0 +1,85 @@
+extern crate llvm_sys;
+
+use self::llvm_sys::core::*;
+use self::llvm_sys::prelude::*;
+use message::take_message;
+use small_cstr::SmallCStr;
+use std::mem;
+use std::os::raw::c_char;
+use std::ptr;
+use std::slice;
+
+// An owned block of bytes in LLVM's own buffer type, used to hand files and
+// in-memory data to the readers and to get the writers' output back.
+#[derive(Debug)]
+pub struct MemoryBuffer {
+    llvm_buffer: LLVMMemoryBufferRef,
+}
+
+impl MemoryBuffer {
+    pub fn from_ptr(buffer_ptr: LLVMMemoryBufferRef) -> MemoryBuffer {
+        MemoryBuffer {
+            llvm_buffer: buffer_ptr,
+        }
+    }
+
+    pub fn from_file(path: &str) -> Result<MemoryBuffer, String> {
+        let fname = SmallCStr::new(path);
+        let mut buffer: LLVMMemoryBufferRef = ptr::null_mut();
+        let mut error: *mut c_char = ptr::null_mut();
+        let ok = unsafe {
+            LLVMCreateMemoryBufferWithContentsOfFile(fname.as_ptr(), &mut buffer, &mut error)
+        };
+        if ok == 1 {
+            // error
+            Err(take_message(error))
+        } else {
+            // success
+            Ok(MemoryBuffer::from_ptr(buffer))
+        }
+    }
+
+    // The bytes are copied, so the buffer does not borrow from `data`.
+    pub fn from_slice(data: &[u8], name: &str) -> MemoryBuffer {
+        let buf_name = SmallCStr::new(name);
+        let buffer = unsafe {
+            LLVMCreateMemoryBufferWithMemoryRangeCopy(
+                data.as_ptr() as *const c_char,
+                data.len(),
+                buf_name.as_ptr(),
+            )
+        };
+        MemoryBuffer::from_ptr(buffer)
+    }
+
+    #[inline]
+    pub fn as_ref(&self) -> LLVMMemoryBufferRef {
+        self.llvm_buffer
+    }
+
+    pub fn as_slice(&self) -> &[u8] {
+        unsafe {
+            let start = LLVMGetBufferStart(self.llvm_buffer) as *const u8;
+            let size = LLVMGetBufferSize(self.llvm_buffer);
+            if size == 0 {
+                &[]
+            } else {
+                slice::from_raw_parts(start, size)
+            }
+        }
+    }
+
+    // For the readers that take ownership of the buffer they are given.
+    pub fn into_raw(self) -> LLVMMemoryBufferRef {
+        let buffer = self.llvm_buffer;
+        mem::forget(self);
+        buffer
+    }
+}
+
+impl Drop for MemoryBuffer {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe { LLVMDisposeMemoryBuffer(self.llvm_buffer) }
+    }
+}
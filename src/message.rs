@@ -0,0 +1,16 @@
+extern crate llvm_sys;
+
+use self::llvm_sys::core::LLVMDisposeMessage;
+use std::ffi::CStr;
+use std::os::raw::c_char;
+
+// Copies a message LLVM allocated for us and frees the original. Only strings
+// LLVM hands over for LLVMDisposeMessage may be passed in; null reads as "".
+pub(crate) fn take_message(message: *mut c_char) -> String {
+    if message.is_null() {
+        return String::new();
+    }
+    let string = unsafe { CStr::from_ptr(message).to_string_lossy().into_owned() };
+    unsafe { LLVMDisposeMessage(message) }
+    string
+}
@@ -3,33 +3,85 @@
 extern crate llvm_sys;
 
 use self::llvm_sys::analysis::{LLVMVerifierFailureAction, LLVMVerifyModule};
+use self::llvm_sys::bit_writer::{LLVMWriteBitcodeToFile, LLVMWriteBitcodeToMemoryBuffer};
+use self::llvm_sys::comdat::LLVMGetOrInsertComdat;
 use self::llvm_sys::core::*;
+use self::llvm_sys::ir_reader::LLVMParseIRInContext;
+use self::llvm_sys::linker::LLVMLinkModules2;
 use self::llvm_sys::prelude::*;
-use cstring_manager::CStringManager;
+use self::llvm_sys::LLVMDiagnosticSeverity;
+use context::{Context, ContextMarker};
+use diagnostic::{Diagnostic, LinkError};
 use engine::Engine;
-use function;
-use std::ffi::CString;
-use std::os::raw::c_char;
+use function::Function;
+use global_alias::{GlobalAlias, GlobalIFunc};
+use global_variable::{Comdat, GlobalVariable};
+use memory_buffer::MemoryBuffer;
+use message::take_message;
+use small_cstr::SmallCStr;
+use std::ffi::CStr;
+use std::marker::PhantomData;
+use std::mem;
+use std::os::raw::{c_char, c_void};
+use std::ptr;
+use std::slice;
+use types::{AnyType, FunctionType};
+use values::{AnyValue, Value};
+
+// How a module flag is combined with the same flag of another module when the
+// two are linked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ModuleFlagBehavior {
+    Error,
+    Warning,
+    Require,
+    Override,
+    Append,
+    AppendUnique,
+    Max,
+}
+
+impl ModuleFlagBehavior {
+    // The numbers LLVM stores in the first field of each llvm.module.flags entry.
+    // The C API has no Max yet, so the entries are built here instead.
+    fn code(self) -> u64 {
+        match self {
+            ModuleFlagBehavior::Error => 1,
+            ModuleFlagBehavior::Warning => 2,
+            ModuleFlagBehavior::Require => 3,
+            ModuleFlagBehavior::Override => 4,
+            ModuleFlagBehavior::Append => 5,
+            ModuleFlagBehavior::AppendUnique => 6,
+            ModuleFlagBehavior::Max => 7,
+        }
+    }
+}
 
 #[derive(Debug)]
-pub struct Module {
+pub struct Module<'ctx> {
     llvm_module: LLVMModuleRef,
+    _context: ContextMarker<'ctx>,
 }
 
-impl Module {
-    pub fn new(name: &str) -> Module {
-        let mod_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        let module = unsafe { LLVMModuleCreateWithName(mod_name_ptr) };
+impl Module<'static> {
+    pub fn new(name: &str) -> Module<'static> {
+        let mod_name = SmallCStr::new(name);
+        let module = unsafe { LLVMModuleCreateWithName(mod_name.as_ptr()) };
         Module {
             llvm_module: module,
+            _context: PhantomData,
         }
     }
+}
 
-    pub fn new_in_context(name: &str, context: LLVMContextRef) -> Module {
-        let mod_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        let module = unsafe { LLVMModuleCreateWithNameInContext(mod_name_ptr, context) };
+impl<'ctx> Module<'ctx> {
+    pub fn new_in_context(name: &str, context: &'ctx Context) -> Module<'ctx> {
+        let mod_name = SmallCStr::new(name);
+        let module =
+            unsafe { LLVMModuleCreateWithNameInContext(mod_name.as_ptr(), context.as_ref()) };
         Module {
             llvm_module: module,
+            _context: PhantomData,
         }
     }
 
@@ -37,44 +89,230 @@ impl Module {
         self.llvm_module
     }
 
-    pub fn add_function(&self, name: &str, function_type: LLVMTypeRef) -> function::Function {
-        function::Function::new(self.llvm_module, name, function_type)
+    pub fn add_function(&self, name: &str, function_type: FunctionType<'ctx>) -> Function<'ctx> {
+        Function::new(self, name, function_type)
     }
 
     #[inline]
-    pub fn named_function(&self, name: &str) -> function::Function {
-        let func_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        let named_function = unsafe { LLVMGetNamedFunction(self.llvm_module, func_name_ptr) };
-        function::Function::from_ptr(named_function)
+    pub fn named_function(&self, name: &str) -> Option<Function<'ctx>> {
+        let func_name = SmallCStr::new(name);
+        let named_function = unsafe { LLVMGetNamedFunction(self.llvm_module, func_name.as_ptr()) };
+        if named_function.is_null() {
+            None
+        } else {
+            Some(Function::from_ptr(named_function))
+        }
+    }
+
+    #[inline]
+    pub fn get_functions(&self) -> FunctionIter<'ctx> {
+        FunctionIter::new(self.llvm_module)
+    }
+
+    #[inline]
+    pub fn count_functions(&self) -> usize {
+        self.get_functions().count()
+    }
+
+    // Deletes the named function, returning whether there was one.
+    pub fn delete_function(&self, name: &str) -> bool {
+        match self.named_function(name) {
+            Some(function) => {
+                // the handle was looked up here and goes nowhere else
+                unsafe { function.delete() };
+                true
+            }
+            None => false,
+        }
     }
 
     pub fn get_or_add_function(
         &self,
         name: &str,
-        function_type: LLVMTypeRef,
-    ) -> function::Function {
-        let func_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        let named_function = unsafe { LLVMGetNamedFunction(self.llvm_module, func_name_ptr) };
+        function_type: FunctionType<'ctx>,
+    ) -> Function<'ctx> {
+        let func_name = SmallCStr::new(name);
+        let named_function = unsafe { LLVMGetNamedFunction(self.llvm_module, func_name.as_ptr()) };
         if named_function.is_null() {
-            function::Function::new(self.llvm_module, name, function_type)
+            Function::new(self, name, function_type)
+        } else {
+            Function::from_ptr(named_function)
+        }
+    }
+
+    #[inline]
+    pub fn add_global<T: AnyType<'ctx>>(&self, ty: T, name: &str) -> GlobalVariable<'ctx> {
+        GlobalVariable::new(self, ty, name)
+    }
+
+    #[inline]
+    pub fn add_global_in_address_space<T: AnyType<'ctx>>(
+        &self,
+        ty: T,
+        name: &str,
+        address_space: u32,
+    ) -> GlobalVariable<'ctx> {
+        GlobalVariable::new_in_address_space(self, ty, name, address_space)
+    }
+
+    #[inline]
+    pub fn set_initializer<V: AnyValue<'ctx>>(&self, global: GlobalVariable<'ctx>, val: V) {
+        global.set_initializer(val)
+    }
+
+    #[inline]
+    pub fn named_global(&self, name: &str) -> Option<GlobalVariable<'ctx>> {
+        let glob_name = SmallCStr::new(name);
+        let global = unsafe { LLVMGetNamedGlobal(self.llvm_module, glob_name.as_ptr()) };
+        if global.is_null() {
+            None
+        } else {
+            Some(GlobalVariable::from_ptr(global))
+        }
+    }
+
+    #[inline]
+    pub fn get_globals(&self) -> GlobalIter<'ctx> {
+        GlobalIter::new(self.llvm_module)
+    }
+
+    #[inline]
+    pub fn count_globals(&self) -> usize {
+        self.get_globals().count()
+    }
+
+    // `ty` is the pointer type the alias has as a value.
+    pub fn add_alias<T: AnyType<'ctx>, V: AnyValue<'ctx>>(
+        &self,
+        ty: T,
+        aliasee: V,
+        name: &str,
+    ) -> GlobalAlias<'ctx> {
+        let alias_name = SmallCStr::new(name);
+        unsafe {
+            GlobalAlias::from_ptr(LLVMAddAlias(
+                self.llvm_module,
+                ty.as_type_ref(),
+                aliasee.as_value_ref(),
+                alias_name.as_ptr(),
+            ))
+        }
+    }
+
+    #[inline]
+    pub fn named_alias(&self, name: &str) -> Option<GlobalAlias<'ctx>> {
+        let alias = unsafe {
+            LLVMGetNamedGlobalAlias(self.llvm_module, name.as_ptr() as *const c_char, name.len())
+        };
+        if alias.is_null() {
+            None
         } else {
-            function::Function::from_ptr(named_function)
+            Some(GlobalAlias::from_ptr(alias))
         }
     }
 
     #[inline]
-    pub fn add_global(&self, ty: LLVMTypeRef, name: &str) -> LLVMValueRef {
-        let glob_name_ptr = CStringManager::new_cstring_as_ptr(name);
-        unsafe { LLVMAddGlobal(self.as_ref(), ty, glob_name_ptr) }
+    pub fn get_aliases(&self) -> AliasIter<'ctx> {
+        AliasIter::new(self.llvm_module)
     }
 
     #[inline]
-    pub fn set_initializer(&self, ptr: LLVMValueRef, val: LLVMValueRef) {
-        unsafe { LLVMSetInitializer(ptr, val) }
+    pub fn count_aliases(&self) -> usize {
+        self.get_aliases().count()
+    }
+
+    pub fn add_ifunc(
+        &self,
+        name: &str,
+        function_type: FunctionType<'ctx>,
+        address_space: u32,
+        resolver: Function<'ctx>,
+    ) -> GlobalIFunc<'ctx> {
+        unsafe {
+            GlobalIFunc::from_ptr(LLVMAddGlobalIFunc(
+                self.llvm_module,
+                name.as_ptr() as *const c_char,
+                name.len(),
+                function_type.as_type_ref(),
+                address_space,
+                resolver.as_ref(),
+            ))
+        }
+    }
+
+    #[inline]
+    pub fn named_ifunc(&self, name: &str) -> Option<GlobalIFunc<'ctx>> {
+        let ifunc = unsafe {
+            LLVMGetNamedGlobalIFunc(self.llvm_module, name.as_ptr() as *const c_char, name.len())
+        };
+        if ifunc.is_null() {
+            None
+        } else {
+            Some(GlobalIFunc::from_ptr(ifunc))
+        }
+    }
+
+    #[inline]
+    pub fn get_ifuncs(&self) -> IFuncIter<'ctx> {
+        IFuncIter::new(self.llvm_module)
+    }
+
+    #[inline]
+    pub fn count_ifuncs(&self) -> usize {
+        self.get_ifuncs().count()
+    }
+
+    pub fn get_named_metadata_names(&self) -> Vec<String> {
+        let mut names = Vec::new();
+        let mut node = unsafe { LLVMGetFirstNamedMetadata(self.llvm_module) };
+        while !node.is_null() {
+            let mut len: usize = 0;
+            unsafe {
+                // llvm-sys declares the length as *const, but LLVM writes through it
+                let ptr = LLVMGetNamedMetadataName(node, &mut len as *mut usize as *const usize);
+                names.push(string_from_raw(ptr, len));
+                node = LLVMGetNextNamedMetadata(node);
+            }
+        }
+        names
+    }
+
+    #[inline]
+    pub fn count_named_metadata_operands(&self, name: &str) -> u32 {
+        let md_name = SmallCStr::new(name);
+        unsafe { LLVMGetNamedMetadataNumOperands(self.llvm_module, md_name.as_ptr()) }
+    }
+
+    pub fn get_named_metadata_operands(&self, name: &str) -> Vec<Value<'ctx>> {
+        let md_name = SmallCStr::new(name);
+        let count = self.count_named_metadata_operands(name);
+        let mut operands = vec![ptr::null_mut(); count as usize];
+        unsafe {
+            LLVMGetNamedMetadataOperands(self.llvm_module, md_name.as_ptr(), operands.as_mut_ptr())
+        };
+        operands.into_iter().map(Value::from_ptr).collect()
+    }
+
+    // Creates the named metadata if it does not exist yet.
+    #[inline]
+    pub fn add_named_metadata_operand(&self, name: &str, operand: Value<'ctx>) {
+        let md_name = SmallCStr::new(name);
+        unsafe { LLVMAddNamedMetadataOperand(self.llvm_module, md_name.as_ptr(), operand.as_ref()) }
+    }
+
+    #[inline]
+    pub fn get_or_insert_comdat(&self, name: &str) -> Comdat<'ctx> {
+        let comdat_name = SmallCStr::new(name);
+        unsafe {
+            Comdat::from_ptr(LLVMGetOrInsertComdat(
+                self.llvm_module,
+                comdat_name.as_ptr(),
+            ))
+        }
     }
 
     pub fn verify(&self) -> Result<(), String> {
-        let mut error: *mut c_char = 0 as *mut c_char;
+        let mut error: *mut c_char = ptr::null_mut();
         let ok = unsafe {
             let buf: *mut *mut c_char = &mut error;
             LLVMVerifyModule(
@@ -83,9 +321,10 @@ impl Module {
                 buf,
             )
         };
+        // the verifier hands back a message, if only an empty one, either way
+        let err_msg = take_message(error);
         if ok == 1 {
             // error
-            let err_msg = unsafe { CString::from_raw(error).into_string().unwrap() };
             Err(err_msg)
         } else {
             // success
@@ -93,50 +332,393 @@ impl Module {
         }
     }
 
+    pub fn get_identifier(&self) -> String {
+        let mut len = 0;
+        unsafe {
+            let ptr = LLVMGetModuleIdentifier(self.llvm_module, &mut len);
+            string_from_raw(ptr, len)
+        }
+    }
+
+    #[inline]
+    pub fn set_identifier(&self, identifier: &str) {
+        unsafe {
+            LLVMSetModuleIdentifier(
+                self.llvm_module,
+                identifier.as_ptr() as *const c_char,
+                identifier.len(),
+            )
+        }
+    }
+
+    pub fn get_source_file_name(&self) -> String {
+        let mut len = 0;
+        unsafe {
+            let ptr = LLVMGetSourceFileName(self.llvm_module, &mut len);
+            string_from_raw(ptr, len)
+        }
+    }
+
+    #[inline]
+    pub fn set_source_file_name(&self, filename: &str) {
+        unsafe {
+            LLVMSetSourceFileName(
+                self.llvm_module,
+                filename.as_ptr() as *const c_char,
+                filename.len(),
+            )
+        }
+    }
+
+    pub fn get_target_triple(&self) -> String {
+        unsafe {
+            CStr::from_ptr(LLVMGetTarget(self.llvm_module))
+                .to_string_lossy()
+                .into_owned()
+        }
+    }
+
+    #[inline]
+    pub fn set_target_triple(&self, triple: &str) {
+        let triple = SmallCStr::new(triple);
+        unsafe { LLVMSetTarget(self.llvm_module, triple.as_ptr()) }
+    }
+
+    pub fn get_data_layout(&self) -> String {
+        unsafe {
+            CStr::from_ptr(LLVMGetDataLayoutStr(self.llvm_module))
+                .to_string_lossy()
+                .into_owned()
+        }
+    }
+
+    #[inline]
+    pub fn set_data_layout(&self, data_layout: &str) {
+        let data_layout = SmallCStr::new(data_layout);
+        unsafe { LLVMSetDataLayout(self.llvm_module, data_layout.as_ptr()) }
+    }
+
+    pub fn get_inline_asm(&self) -> String {
+        let mut len = 0;
+        unsafe {
+            let ptr = LLVMGetModuleInlineAsm(self.llvm_module, &mut len);
+            string_from_raw(ptr, len)
+        }
+    }
+
+    #[inline]
+    pub fn set_inline_asm(&self, asm: &str) {
+        unsafe {
+            LLVMSetModuleInlineAsm2(self.llvm_module, asm.as_ptr() as *const c_char, asm.len())
+        }
+    }
+
+    // Starts a new line of module level assembly if there is some already.
+    #[inline]
+    pub fn append_inline_asm(&self, asm: &str) {
+        unsafe {
+            LLVMAppendModuleInlineAsm(self.llvm_module, asm.as_ptr() as *const c_char, asm.len())
+        }
+    }
+
+    // `value` is usually an integer constant, but metadata works as well.
+    pub fn add_module_flag<V: AnyValue<'ctx>>(
+        &self,
+        behavior: ModuleFlagBehavior,
+        key: &str,
+        value: V,
+    ) {
+        unsafe {
+            let context = LLVMGetModuleContext(self.llvm_module);
+            let behavior = LLVMConstInt(LLVMInt32TypeInContext(context), behavior.code(), 0);
+            let mut fields = [
+                LLVMValueAsMetadata(behavior),
+                LLVMMDStringInContext2(context, key.as_ptr() as *const c_char, key.len()),
+                LLVMValueAsMetadata(value.as_value_ref()),
+            ];
+            let flag = LLVMMDNodeInContext2(context, fields.as_mut_ptr(), fields.len());
+            self.add_named_metadata_operand(
+                "llvm.module.flags",
+                Value::from_ptr(LLVMMetadataAsValue(context, flag)),
+            );
+        }
+    }
+
+    pub fn get_module_flag(&self, key: &str) -> Option<Value<'ctx>> {
+        for flag in self.get_named_metadata_operands("llvm.module.flags") {
+            unsafe {
+                if LLVMGetMDNodeNumOperands(flag.as_ref()) != 3 {
+                    continue;
+                }
+                let mut fields = [ptr::null_mut(); 3];
+                LLVMGetMDNodeOperands(flag.as_ref(), fields.as_mut_ptr());
+                let mut len = 0;
+                let ptr = LLVMGetMDString(fields[1], &mut len);
+                if !ptr.is_null() && string_from_raw(ptr, len as usize) == key {
+                    return Some(Value::from_ptr(fields[2]));
+                }
+            }
+        }
+        None
+    }
+
     #[inline]
     pub fn dump(&self) {
         unsafe { LLVMDumpModule(self.llvm_module) }
     }
 
     pub fn print_module_to_string(&self) -> String {
-        let ptr = unsafe { LLVMPrintModuleToString(self.llvm_module) };
-        let string = unsafe { CString::from_raw(ptr).into_string().unwrap() };
-        unsafe {
-            LLVMDisposeMessage(ptr);
-        }
-        string
+        take_message(unsafe { LLVMPrintModuleToString(self.llvm_module) })
     }
 
     pub fn print_module_to_file(&self, filename: &str) -> Result<(), String> {
-        let fname_ptr = CStringManager::new_cstring_as_ptr(filename);
-        let mut error: *mut c_char = 0 as *mut c_char;
+        let fname = SmallCStr::new(filename);
+        let mut error: *mut c_char = ptr::null_mut();
         let ok = unsafe {
             let buf: *mut *mut c_char = &mut error;
-            LLVMPrintModuleToFile(self.llvm_module, fname_ptr, buf)
+            LLVMPrintModuleToFile(self.llvm_module, fname.as_ptr(), buf)
         };
         if ok == 1 {
             // error
-            let err_msg = unsafe { CString::from_raw(error).into_string().unwrap() };
-            unsafe { LLVMDisposeMessage(error) }
-            Err(err_msg)
+            Err(take_message(error))
         } else {
             // success
             Ok(())
         }
     }
 
+    pub fn write_bitcode_to_file(&self, filename: &str) -> Result<(), String> {
+        let fname = SmallCStr::new(filename);
+        let ok = unsafe { LLVMWriteBitcodeToFile(self.llvm_module, fname.as_ptr()) };
+        if ok == 0 {
+            Ok(())
+        } else {
+            Err(format!("could not write bitcode to {}", filename))
+        }
+    }
+
+    pub fn write_bitcode_to_memory(&self) -> Vec<u8> {
+        let buffer =
+            MemoryBuffer::from_ptr(unsafe { LLVMWriteBitcodeToMemoryBuffer(self.llvm_module) });
+        buffer.as_slice().to_vec()
+    }
+
+    pub fn parse_bitcode(bitcode: &[u8], context: &'ctx Context) -> Result<Module<'ctx>, String> {
+        let buffer = MemoryBuffer::from_slice(bitcode, "bitcode");
+        Module::parse_bitcode_buffer(&buffer, context)
+    }
+
+    pub fn parse_bitcode_from_file(
+        filename: &str,
+        context: &'ctx Context,
+    ) -> Result<Module<'ctx>, String> {
+        let buffer = MemoryBuffer::from_file(filename)?;
+        Module::parse_bitcode_buffer(&buffer, context)
+    }
+
+    // The newer LLVMParseBitcodeInContext2 reports failures through the context's
+    // diagnostic handler, which by default prints the error and exits the process.
+    // This one hands the message back instead. The buffer stays ours either way.
+    #[allow(deprecated)]
+    fn parse_bitcode_buffer(
+        buffer: &MemoryBuffer,
+        context: &'ctx Context,
+    ) -> Result<Module<'ctx>, String> {
+        let mut module: LLVMModuleRef = ptr::null_mut();
+        let mut error: *mut c_char = ptr::null_mut();
+        let failed = unsafe {
+            llvm_sys::bit_reader::LLVMParseBitcodeInContext(
+                context.as_ref(),
+                buffer.as_ref(),
+                &mut module,
+                &mut error,
+            )
+        };
+        if failed == 1 {
+            // error
+            Err(take_message(error))
+        } else {
+            // success
+            Ok(Module {
+                llvm_module: module,
+                _context: PhantomData,
+            })
+        }
+    }
+
+    pub fn parse_ir(ir: &str, context: &'ctx Context) -> Result<Module<'ctx>, Diagnostic> {
+        let buffer = MemoryBuffer::from_slice(ir.as_bytes(), "ir");
+        Module::parse_ir_buffer(buffer, "ir", context)
+    }
+
+    pub fn parse_ir_file(
+        filename: &str,
+        context: &'ctx Context,
+    ) -> Result<Module<'ctx>, Diagnostic> {
+        let buffer = MemoryBuffer::from_file(filename).map_err(|msg| Diagnostic::new(&msg))?;
+        Module::parse_ir_buffer(buffer, filename, context)
+    }
+
+    // LLVMParseIRInContext takes the buffer over, whether or not parsing succeeds.
+    fn parse_ir_buffer(
+        buffer: MemoryBuffer,
+        buffer_name: &str,
+        context: &'ctx Context,
+    ) -> Result<Module<'ctx>, Diagnostic> {
+        let mut module: LLVMModuleRef = ptr::null_mut();
+        let mut error: *mut c_char = ptr::null_mut();
+        let failed = unsafe {
+            LLVMParseIRInContext(context.as_ref(), buffer.into_raw(), &mut module, &mut error)
+        };
+        if failed == 1 {
+            // error
+            Err(Diagnostic::from_report(buffer_name, &take_message(error)))
+        } else {
+            // success
+            Ok(Module {
+                llvm_module: module,
+                _context: PhantomData,
+            })
+        }
+    }
+
+    // Moves everything in `other` into this module; `other` is consumed even if
+    // linking fails. LLVM reports link errors through the context's diagnostic
+    // handler, whose default exits the process, so one that collects them is
+    // installed for the duration of the call.
+    pub fn link_in(&self, other: Module<'ctx>) -> Result<(), LinkError> {
+        let context = unsafe { LLVMGetModuleContext(self.llvm_module) };
+        let mut errors: Vec<String> = Vec::new();
+        let failed = unsafe {
+            let handler = LLVMContextGetDiagnosticHandler(context);
+            let handler_context = LLVMContextGetDiagnosticContext(context);
+            LLVMContextSetDiagnosticHandler(
+                context,
+                Some(collect_errors),
+                &mut errors as *mut Vec<String> as *mut c_void,
+            );
+            let failed = LLVMLinkModules2(self.llvm_module, other.into_raw());
+            LLVMContextSetDiagnosticHandler(context, handler, handler_context);
+            failed
+        };
+        if failed == 1 {
+            // error
+            let message = errors
+                .into_iter()
+                .next()
+                .unwrap_or_else(|| "modules could not be linked".to_string());
+            Err(LinkError::from_message(&message))
+        } else {
+            // success
+            Ok(())
+        }
+    }
+
+    // The engine takes ownership of the module and disposes it along with itself.
     #[inline]
-    pub fn create_interpreter(&self) -> Result<Engine, String> {
-        Engine::create_interpreter(self.as_ref())
+    pub fn create_interpreter(self) -> Result<Engine<'ctx>, String> {
+        Engine::create_interpreter(self)
     }
 
     #[inline]
-    pub fn create_jit_engine(&self) -> Result<Engine, String> {
-        Engine::create_jit_engine(self.as_ref())
+    pub fn create_jit_engine(self) -> Result<Engine<'ctx>, String> {
+        Engine::create_jit_engine(self)
+    }
+
+    // Hands the module over to LLVM, which becomes responsible for disposing it.
+    pub(crate) fn into_raw(self) -> LLVMModuleRef {
+        let module = self.llvm_module;
+        mem::forget(self);
+        module
+    }
+}
+
+// Walks one kind of global value of a module in definition order.
+macro_rules! module_iter {
+    ($name:ident, $item:ident, $first:ident, $next:ident) => {
+        #[derive(Debug)]
+        pub struct $name<'ctx> {
+            next: LLVMValueRef,
+            _context: ContextMarker<'ctx>,
+        }
+
+        impl<'ctx> $name<'ctx> {
+            fn new(module: LLVMModuleRef) -> $name<'ctx> {
+                $name {
+                    next: unsafe { $first(module) },
+                    _context: PhantomData,
+                }
+            }
+        }
+
+        impl<'ctx> Iterator for $name<'ctx> {
+            type Item = $item<'ctx>;
+
+            fn next(&mut self) -> Option<$item<'ctx>> {
+                if self.next.is_null() {
+                    return None;
+                }
+                let current = self.next;
+                self.next = unsafe { $next(current) };
+                Some($item::from_ptr(current))
+            }
+        }
+    };
+}
+
+module_iter!(
+    FunctionIter,
+    Function,
+    LLVMGetFirstFunction,
+    LLVMGetNextFunction
+);
+module_iter!(
+    GlobalIter,
+    GlobalVariable,
+    LLVMGetFirstGlobal,
+    LLVMGetNextGlobal
+);
+module_iter!(
+    AliasIter,
+    GlobalAlias,
+    LLVMGetFirstGlobalAlias,
+    LLVMGetNextGlobalAlias
+);
+module_iter!(
+    IFuncIter,
+    GlobalIFunc,
+    LLVMGetFirstGlobalIFunc,
+    LLVMGetNextGlobalIFunc
+);
+
+impl<'ctx> Clone for Module<'ctx> {
+    // A deep copy in the same context.
+    fn clone(&self) -> Module<'ctx> {
+        Module {
+            llvm_module: unsafe { LLVMCloneModule(self.llvm_module) },
+            _context: PhantomData,
+        }
+    }
+}
+
+unsafe fn string_from_raw(ptr: *const c_char, len: usize) -> String {
+    if ptr.is_null() {
+        return String::new();
+    }
+    let bytes = slice::from_raw_parts(ptr as *const u8, len);
+    String::from_utf8_lossy(bytes).into_owned()
+}
+
+extern "C" fn collect_errors(info: LLVMDiagnosticInfoRef, errors: *mut c_void) {
+    unsafe {
+        if let LLVMDiagnosticSeverity::LLVMDSError = LLVMGetDiagInfoSeverity(info) {
+            let errors = &mut *(errors as *mut Vec<String>);
+            errors.push(take_message(LLVMGetDiagInfoDescription(info)));
+        }
     }
 }
 
-impl Drop for Module {
+impl<'ctx> Drop for Module<'ctx> {
     #[inline]
     fn drop(&mut self) {
         unsafe { LLVMDisposeModule(self.llvm_module) }
@@ -0,0 +1,256 @@
+extern crate llvm_sys;
+
+use self::llvm_sys::core::*;
+use self::llvm_sys::prelude::*;
+use self::llvm_sys::transforms::instcombine::LLVMAddInstructionCombiningPass;
+use self::llvm_sys::transforms::ipo::*;
+use self::llvm_sys::transforms::pass_manager_builder::*;
+use self::llvm_sys::transforms::scalar::*;
+use self::llvm_sys::transforms::util::*;
+use self::llvm_sys::transforms::vectorize::*;
+use function::Function;
+use module::Module;
+use std::marker::PhantomData;
+use std::os::raw::c_uint;
+
+macro_rules! passes {
+    ($($method:ident => $add:ident,)*) => {
+        $(
+            #[inline]
+            pub fn $method(&self) {
+                unsafe { $add(self.llvm_pass_manager) }
+            }
+        )*
+    };
+}
+
+// Passes that work one function at a time, so either kind of manager can run them.
+macro_rules! function_passes {
+    () => {
+        passes! {
+            // mem2reg: turns allocas that are only loaded and stored into SSA values
+            add_promote_memory_to_register_pass => LLVMAddPromoteMemoryToRegisterPass,
+            add_demote_memory_to_register_pass => LLVMAddDemoteMemoryToRegisterPass,
+            add_scalar_repl_aggregates_pass => LLVMAddScalarReplAggregatesPass,
+            add_instruction_combining_pass => LLVMAddInstructionCombiningPass,
+            add_reassociate_pass => LLVMAddReassociatePass,
+            add_gvn_pass => LLVMAddGVNPass,
+            add_new_gvn_pass => LLVMAddNewGVNPass,
+            add_early_cse_pass => LLVMAddEarlyCSEPass,
+            add_sccp_pass => LLVMAddSCCPPass,
+            add_correlated_value_propagation_pass => LLVMAddCorrelatedValuePropagationPass,
+            add_jump_threading_pass => LLVMAddJumpThreadingPass,
+            add_cfg_simplification_pass => LLVMAddCFGSimplificationPass,
+            add_dce_pass => LLVMAddDCEPass,
+            add_aggressive_dce_pass => LLVMAddAggressiveDCEPass,
+            add_dead_store_elimination_pass => LLVMAddDeadStoreEliminationPass,
+            add_memcpy_opt_pass => LLVMAddMemCpyOptPass,
+            add_tail_call_elimination_pass => LLVMAddTailCallEliminationPass,
+            add_licm_pass => LLVMAddLICMPass,
+            add_loop_rotate_pass => LLVMAddLoopRotatePass,
+            add_loop_deletion_pass => LLVMAddLoopDeletionPass,
+            add_loop_unroll_pass => LLVMAddLoopUnrollPass,
+            add_ind_var_simplify_pass => LLVMAddIndVarSimplifyPass,
+            add_loop_vectorize_pass => LLVMAddLoopVectorizePass,
+            add_slp_vectorize_pass => LLVMAddSLPVectorizePass,
+            add_lower_switch_pass => LLVMAddLowerSwitchPass,
+            add_basic_alias_analysis_pass => LLVMAddBasicAliasAnalysisPass,
+            add_type_based_alias_analysis_pass => LLVMAddTypeBasedAliasAnalysisPass,
+            add_verifier_pass => LLVMAddVerifierPass,
+        }
+    };
+}
+
+// Runs passes over a whole module, including the ones that work across functions.
+#[derive(Debug)]
+pub struct PassManager {
+    llvm_pass_manager: LLVMPassManagerRef,
+}
+
+impl PassManager {
+    pub fn new() -> PassManager {
+        PassManager {
+            llvm_pass_manager: unsafe { LLVMCreatePassManager() },
+        }
+    }
+
+    // The passes clang runs at -O2.
+    pub fn o2() -> PassManager {
+        let pass_manager = PassManager::new();
+        PassManagerBuilder::o2().populate_module_pass_manager(&pass_manager);
+        pass_manager
+    }
+
+    pub fn as_ref(&self) -> LLVMPassManagerRef {
+        self.llvm_pass_manager
+    }
+
+    // Returns whether any pass changed the module.
+    #[inline]
+    pub fn run(&self, module: &Module) -> bool {
+        unsafe { LLVMRunPassManager(self.llvm_pass_manager, module.as_ref()) != 0 }
+    }
+
+    function_passes!();
+
+    passes! {
+        add_function_inlining_pass => LLVMAddFunctionInliningPass,
+        add_always_inliner_pass => LLVMAddAlwaysInlinerPass,
+        add_global_dce_pass => LLVMAddGlobalDCEPass,
+        add_global_optimizer_pass => LLVMAddGlobalOptimizerPass,
+        add_ipsccp_pass => LLVMAddIPSCCPPass,
+        add_constant_merge_pass => LLVMAddConstantMergePass,
+        add_dead_arg_elimination_pass => LLVMAddDeadArgEliminationPass,
+        add_function_attrs_pass => LLVMAddFunctionAttrsPass,
+        add_merge_functions_pass => LLVMAddMergeFunctionsPass,
+        add_strip_dead_prototypes_pass => LLVMAddStripDeadPrototypesPass,
+        add_strip_symbols_pass => LLVMAddStripSymbolsPass,
+    }
+}
+
+impl Default for PassManager {
+    fn default() -> PassManager {
+        PassManager::new()
+    }
+}
+
+impl Drop for PassManager {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe { LLVMDisposePassManager(self.llvm_pass_manager) }
+    }
+}
+
+// Runs passes over the functions of one module, a function at a time.
+#[derive(Debug)]
+pub struct FunctionPassManager<'m> {
+    llvm_pass_manager: LLVMPassManagerRef,
+    _module: PhantomData<&'m Module<'m>>,
+}
+
+impl<'m> FunctionPassManager<'m> {
+    pub fn new(module: &'m Module) -> FunctionPassManager<'m> {
+        FunctionPassManager {
+            llvm_pass_manager: unsafe { LLVMCreateFunctionPassManagerForModule(module.as_ref()) },
+            _module: PhantomData,
+        }
+    }
+
+    // The function passes clang runs at -O2.
+    pub fn o2(module: &'m Module) -> FunctionPassManager<'m> {
+        let pass_manager = FunctionPassManager::new(module);
+        PassManagerBuilder::o2().populate_function_pass_manager(&pass_manager);
+        pass_manager
+    }
+
+    pub fn as_ref(&self) -> LLVMPassManagerRef {
+        self.llvm_pass_manager
+    }
+
+    // Call once after adding passes and before the first run.
+    #[inline]
+    pub fn initialize(&self) -> bool {
+        unsafe { LLVMInitializeFunctionPassManager(self.llvm_pass_manager) != 0 }
+    }
+
+    // Returns whether any pass changed the function.
+    #[inline]
+    pub fn run(&self, function: Function) -> bool {
+        unsafe { LLVMRunFunctionPassManager(self.llvm_pass_manager, function.as_ref()) != 0 }
+    }
+
+    #[inline]
+    pub fn finalize(&self) -> bool {
+        unsafe { LLVMFinalizeFunctionPassManager(self.llvm_pass_manager) != 0 }
+    }
+
+    function_passes!();
+}
+
+impl<'m> Drop for FunctionPassManager<'m> {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe { LLVMDisposePassManager(self.llvm_pass_manager) }
+    }
+}
+
+// Fills pass managers with LLVM's standard pipeline for an optimization level.
+#[derive(Debug)]
+pub struct PassManagerBuilder {
+    llvm_builder: LLVMPassManagerBuilderRef,
+}
+
+impl PassManagerBuilder {
+    pub fn new() -> PassManagerBuilder {
+        PassManagerBuilder {
+            llvm_builder: unsafe { LLVMPassManagerBuilderCreate() },
+        }
+    }
+
+    // Level 2 with the inliner threshold clang uses for it.
+    pub fn o2() -> PassManagerBuilder {
+        let builder = PassManagerBuilder::new();
+        builder.set_opt_level(2);
+        builder.use_inliner_with_threshold(225);
+        builder
+    }
+
+    pub fn as_ref(&self) -> LLVMPassManagerBuilderRef {
+        self.llvm_builder
+    }
+
+    // 0 to 3, as in -O0 to -O3.
+    #[inline]
+    pub fn set_opt_level(&self, opt_level: c_uint) {
+        unsafe { LLVMPassManagerBuilderSetOptLevel(self.llvm_builder, opt_level) }
+    }
+
+    // 1 for -Os, 2 for -Oz.
+    #[inline]
+    pub fn set_size_level(&self, size_level: c_uint) {
+        unsafe { LLVMPassManagerBuilderSetSizeLevel(self.llvm_builder, size_level) }
+    }
+
+    #[inline]
+    pub fn set_disable_unroll_loops(&self, disable: bool) {
+        unsafe { LLVMPassManagerBuilderSetDisableUnrollLoops(self.llvm_builder, disable as i32) }
+    }
+
+    #[inline]
+    pub fn use_inliner_with_threshold(&self, threshold: c_uint) {
+        unsafe { LLVMPassManagerBuilderUseInlinerWithThreshold(self.llvm_builder, threshold) }
+    }
+
+    #[inline]
+    pub fn populate_module_pass_manager(&self, pass_manager: &PassManager) {
+        unsafe {
+            LLVMPassManagerBuilderPopulateModulePassManager(
+                self.llvm_builder,
+                pass_manager.as_ref(),
+            )
+        }
+    }
+
+    #[inline]
+    pub fn populate_function_pass_manager(&self, pass_manager: &FunctionPassManager) {
+        unsafe {
+            LLVMPassManagerBuilderPopulateFunctionPassManager(
+                self.llvm_builder,
+                pass_manager.as_ref(),
+            )
+        }
+    }
+}
+
+impl Default for PassManagerBuilder {
+    fn default() -> PassManagerBuilder {
+        PassManagerBuilder::new()
+    }
+}
+
+impl Drop for PassManagerBuilder {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe { LLVMPassManagerBuilderDispose(self.llvm_builder) }
+    }
+}
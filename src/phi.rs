@@ -2,18 +2,27 @@ extern crate llvm_sys;
 
 use self::llvm_sys::core::*;
 use self::llvm_sys::prelude::*;
-use cstring_manager::CStringManager;
+use basic_block::BasicBlock;
+use context::ContextMarker;
+use small_cstr::SmallCStr;
+use std::marker::PhantomData;
+use values::{value_refs, AnyValue, Value};
 
-#[derive(Debug)]
-pub struct Phi {
+#[derive(Debug, Clone, Copy)]
+pub struct Phi<'ctx> {
     llvm_phi: LLVMValueRef,
+    _context: ContextMarker<'ctx>,
 }
 
-impl Phi {
-    pub fn new(builder: LLVMBuilderRef, typ: LLVMTypeRef, name: &str) -> Phi {
-        let name_ptr = CStringManager::new_cstring_as_ptr(name);
-        let phi = unsafe { LLVMBuildPhi(builder, typ, name_ptr) };
-        Phi { llvm_phi: phi }
+impl<'ctx> Phi<'ctx> {
+    #[allow(clippy::not_unsafe_ptr_arg_deref)]
+    pub fn new(builder: LLVMBuilderRef, typ: LLVMTypeRef, name: &str) -> Phi<'ctx> {
+        let name = SmallCStr::new(name);
+        let phi = unsafe { LLVMBuildPhi(builder, typ, name.as_ptr()) };
+        Phi {
+            llvm_phi: phi,
+            _context: PhantomData,
+        }
     }
 
     pub fn as_ref(&self) -> LLVMValueRef {
@@ -21,18 +30,19 @@ impl Phi {
     }
 
     #[inline]
-    pub fn add_incoming(&self, value: LLVMValueRef, block: LLVMBasicBlockRef) {
-        let mut values = [value];
-        let mut blocks = [block];
-        self.add_incomings(&mut values, &mut blocks);
+    pub fn add_incoming<V: AnyValue<'ctx>>(&self, value: V, block: BasicBlock<'ctx>) {
+        self.add_incomings(&[value.as_any_value()], &[block]);
     }
 
     #[inline]
-    pub fn add_incomings(&self, values: &mut [LLVMValueRef], blocks: &mut [LLVMBasicBlockRef]) {
+    pub fn add_incomings(&self, values: &[Value<'ctx>], blocks: &[BasicBlock<'ctx>]) {
         let count = values.len();
         if count != blocks.len() {
             panic!("values count not equal blocks count.");
         }
+        let mut values = value_refs(values);
+        let mut blocks: Vec<LLVMBasicBlockRef> =
+            blocks.iter().map(|block| block.as_ref()).collect();
         unsafe {
             LLVMAddIncoming(
                 self.llvm_phi,
@@ -49,9 +59,31 @@ impl Phi {
     }
 
     #[inline]
-    pub fn get_incoming(&self, index: u32) -> (LLVMValueRef, LLVMBasicBlockRef) {
+    pub fn get_incoming(&self, index: u32) -> (Value<'ctx>, BasicBlock<'ctx>) {
         let value = unsafe { LLVMGetIncomingValue(self.llvm_phi, index) };
         let block = unsafe { LLVMGetIncomingBlock(self.llvm_phi, index) };
-        (value, block)
+        (Value::from_ptr(value), BasicBlock::from_ptr(block))
+    }
+}
+
+impl<'ctx> AnyValue<'ctx> for Phi<'ctx> {
+    #[inline]
+    fn as_value_ref(&self) -> LLVMValueRef {
+        self.llvm_phi
+    }
+
+    #[inline]
+    unsafe fn from_value_ref(value: LLVMValueRef) -> Self {
+        Phi {
+            llvm_phi: value,
+            _context: PhantomData,
+        }
+    }
+}
+
+impl<'ctx> From<Phi<'ctx>> for Value<'ctx> {
+    #[inline]
+    fn from(phi: Phi<'ctx>) -> Value<'ctx> {
+        Value::from_ptr(phi.llvm_phi)
     }
 }
@@ -0,0 +1,38 @@
+// Folds the lanes of a vector into one scalar. SMax and SMin compare as
+// signed, UMax and UMin as unsigned, and FMax and FMin take float vectors.
+// Float sums and products have builders of their own since they also take a
+// start value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VectorReduction {
+    Add,
+    Mul,
+    And,
+    Or,
+    Xor,
+    SMax,
+    SMin,
+    UMax,
+    UMin,
+    FMax,
+    FMin,
+}
+
+impl VectorReduction {
+    // The part of the intrinsic name after llvm.vector.reduce.
+    #[inline]
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            VectorReduction::Add => "add",
+            VectorReduction::Mul => "mul",
+            VectorReduction::And => "and",
+            VectorReduction::Or => "or",
+            VectorReduction::Xor => "xor",
+            VectorReduction::SMax => "smax",
+            VectorReduction::SMin => "smin",
+            VectorReduction::UMax => "umax",
+            VectorReduction::UMin => "umin",
+            VectorReduction::FMax => "fmax",
+            VectorReduction::FMin => "fmin",
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use std::ffi::CString;
+use std::os::raw::c_char;
+
+// Names shorter than this are kept on the stack; longer ones fall back to
+// a heap allocated CString that is freed as soon as the wrapper is dropped.
+const INLINE_CAPACITY: usize = 64;
+
+// A NUL terminated copy of a Rust string that lives only as long as the call
+// that needs it. LLVM copies every name it is handed, so nothing has to be
+// kept alive after the FFI call returns.
+pub enum SmallCStr {
+    Inline([u8; INLINE_CAPACITY]),
+    Heap(CString),
+}
+
+impl SmallCStr {
+    pub fn new(string: &str) -> SmallCStr {
+        // C strings stop at the first NUL, so anything after it would never
+        // be seen by LLVM anyway.
+        let bytes = string.as_bytes();
+        let bytes = match bytes.iter().position(|&b| b == 0) {
+            Some(nul) => &bytes[..nul],
+            None => bytes,
+        };
+
+        if bytes.len() < INLINE_CAPACITY {
+            let mut buf = [0u8; INLINE_CAPACITY];
+            buf[..bytes.len()].copy_from_slice(bytes);
+            SmallCStr::Inline(buf)
+        } else {
+            SmallCStr::Heap(CString::new(bytes).expect("interior NUL already stripped"))
+        }
+    }
+
+    #[inline]
+    pub fn as_ptr(&self) -> *const c_char {
+        match *self {
+            SmallCStr::Inline(ref buf) => buf.as_ptr() as *const c_char,
+            SmallCStr::Heap(ref string) => string.as_ptr(),
+        }
+    }
+}
@@ -0,0 +1,534 @@
+#![allow(dead_code)]
+
+extern crate llvm_sys;
+
+use self::llvm_sys::core::*;
+use self::llvm_sys::prelude::*;
+use self::llvm_sys::LLVMTypeKind;
+use context::{Context, ContextMarker};
+use message::take_message;
+use small_cstr::SmallCStr;
+use std::convert::TryFrom;
+use std::ffi::CStr;
+use std::marker::PhantomData;
+use values::{
+    value_refs, AnyValue, ArrayValue, FloatValue, IntValue, PointerValue, StructValue, Value,
+    VectorValue,
+};
+
+// Common operations on every kind of type handle.
+pub trait AnyType<'ctx>: Copy {
+    fn as_type_ref(&self) -> LLVMTypeRef;
+
+    /// Wraps a raw type without checking its kind.
+    ///
+    /// # Safety
+    ///
+    /// `typ` must be a live LLVM type of the kind `Self` stands for.
+    unsafe fn from_type_ref(typ: LLVMTypeRef) -> Self;
+
+    #[inline]
+    fn as_any_type(&self) -> Type<'ctx> {
+        Type::from_ptr(self.as_type_ref())
+    }
+
+    #[inline]
+    fn is_sized(&self) -> bool {
+        unsafe { LLVMTypeIsSized(self.as_type_ref()) == 1 }
+    }
+
+    #[inline]
+    fn ptr_type(&self, address_space: u32) -> PointerType<'ctx> {
+        unsafe { PointerType::from_type_ref(LLVMPointerType(self.as_type_ref(), address_space)) }
+    }
+
+    #[inline]
+    fn array_type(&self, count: u32) -> ArrayType<'ctx> {
+        unsafe { ArrayType::from_type_ref(LLVMArrayType(self.as_type_ref(), count)) }
+    }
+
+    #[inline]
+    fn vector_type(&self, count: u32) -> VectorType<'ctx> {
+        unsafe { VectorType::from_type_ref(LLVMVectorType(self.as_type_ref(), count)) }
+    }
+
+    #[inline]
+    fn dump(&self) {
+        unsafe { LLVMDumpType(self.as_type_ref()) }
+    }
+
+    fn print_to_string(&self) -> String {
+        take_message(unsafe { LLVMPrintTypeToString(self.as_type_ref()) })
+    }
+}
+
+// An untyped type, as returned by element, field and parameter lookups.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Type<'ctx> {
+    llvm_type: LLVMTypeRef,
+    _context: ContextMarker<'ctx>,
+}
+
+impl<'ctx> Type<'ctx> {
+    pub fn from_ptr(type_ptr: LLVMTypeRef) -> Type<'ctx> {
+        Type {
+            llvm_type: type_ptr,
+            _context: PhantomData,
+        }
+    }
+
+    pub fn as_ref(&self) -> LLVMTypeRef {
+        self.llvm_type
+    }
+
+    #[inline]
+    pub fn get_kind(&self) -> LLVMTypeKind {
+        unsafe { LLVMGetTypeKind(self.llvm_type) }
+    }
+
+    #[inline]
+    pub fn is_void_type(&self) -> bool {
+        self.get_kind() == LLVMTypeKind::LLVMVoidTypeKind
+    }
+
+    #[inline]
+    pub fn is_int_type(&self) -> bool {
+        self.get_kind() == LLVMTypeKind::LLVMIntegerTypeKind
+    }
+
+    pub fn is_float_type(&self) -> bool {
+        matches!(
+            self.get_kind(),
+            LLVMTypeKind::LLVMHalfTypeKind
+                | LLVMTypeKind::LLVMFloatTypeKind
+                | LLVMTypeKind::LLVMDoubleTypeKind
+                | LLVMTypeKind::LLVMX86_FP80TypeKind
+                | LLVMTypeKind::LLVMFP128TypeKind
+                | LLVMTypeKind::LLVMPPC_FP128TypeKind
+        )
+    }
+
+    #[inline]
+    pub fn is_pointer_type(&self) -> bool {
+        self.get_kind() == LLVMTypeKind::LLVMPointerTypeKind
+    }
+
+    #[inline]
+    pub fn is_struct_type(&self) -> bool {
+        self.get_kind() == LLVMTypeKind::LLVMStructTypeKind
+    }
+
+    #[inline]
+    pub fn is_array_type(&self) -> bool {
+        self.get_kind() == LLVMTypeKind::LLVMArrayTypeKind
+    }
+
+    #[inline]
+    pub fn is_vector_type(&self) -> bool {
+        self.get_kind() == LLVMTypeKind::LLVMVectorTypeKind
+    }
+
+    #[inline]
+    pub fn is_function_type(&self) -> bool {
+        self.get_kind() == LLVMTypeKind::LLVMFunctionTypeKind
+    }
+
+    #[inline]
+    pub fn into_void_type(self) -> Option<VoidType<'ctx>> {
+        VoidType::try_from(self).ok()
+    }
+
+    #[inline]
+    pub fn into_int_type(self) -> Option<IntType<'ctx>> {
+        IntType::try_from(self).ok()
+    }
+
+    #[inline]
+    pub fn into_float_type(self) -> Option<FloatType<'ctx>> {
+        FloatType::try_from(self).ok()
+    }
+
+    #[inline]
+    pub fn into_pointer_type(self) -> Option<PointerType<'ctx>> {
+        PointerType::try_from(self).ok()
+    }
+
+    #[inline]
+    pub fn into_struct_type(self) -> Option<StructType<'ctx>> {
+        StructType::try_from(self).ok()
+    }
+
+    #[inline]
+    pub fn into_array_type(self) -> Option<ArrayType<'ctx>> {
+        ArrayType::try_from(self).ok()
+    }
+
+    #[inline]
+    pub fn into_vector_type(self) -> Option<VectorType<'ctx>> {
+        VectorType::try_from(self).ok()
+    }
+
+    #[inline]
+    pub fn into_function_type(self) -> Option<FunctionType<'ctx>> {
+        FunctionType::try_from(self).ok()
+    }
+}
+
+impl<'ctx> AnyType<'ctx> for Type<'ctx> {
+    #[inline]
+    fn as_type_ref(&self) -> LLVMTypeRef {
+        self.llvm_type
+    }
+
+    #[inline]
+    unsafe fn from_type_ref(typ: LLVMTypeRef) -> Self {
+        Type::from_ptr(typ)
+    }
+}
+
+macro_rules! typed_type {
+    ($name:ident, $check:ident) => {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub struct $name<'ctx> {
+            llvm_type: LLVMTypeRef,
+            _context: ContextMarker<'ctx>,
+        }
+
+        impl<'ctx> $name<'ctx> {
+            pub fn as_ref(&self) -> LLVMTypeRef {
+                self.llvm_type
+            }
+        }
+
+        impl<'ctx> AnyType<'ctx> for $name<'ctx> {
+            #[inline]
+            fn as_type_ref(&self) -> LLVMTypeRef {
+                self.llvm_type
+            }
+
+            #[inline]
+            unsafe fn from_type_ref(typ: LLVMTypeRef) -> Self {
+                $name {
+                    llvm_type: typ,
+                    _context: PhantomData,
+                }
+            }
+        }
+
+        impl<'ctx> From<$name<'ctx>> for Type<'ctx> {
+            #[inline]
+            fn from(typ: $name<'ctx>) -> Type<'ctx> {
+                Type::from_ptr(typ.llvm_type)
+            }
+        }
+
+        impl<'ctx> TryFrom<Type<'ctx>> for $name<'ctx> {
+            type Error = Type<'ctx>;
+
+            fn try_from(typ: Type<'ctx>) -> Result<Self, Self::Error> {
+                if typ.$check() {
+                    Ok(unsafe { $name::from_type_ref(typ.as_ref()) })
+                } else {
+                    Err(typ)
+                }
+            }
+        }
+    };
+}
+
+// Constants every first class type can make: zero, undef and arrays of itself.
+macro_rules! const_values {
+    ($name:ident, $value:ident) => {
+        impl<'ctx> $name<'ctx> {
+            #[inline]
+            pub fn const_zero(&self) -> $value<'ctx> {
+                unsafe { $value::from_value_ref(LLVMConstNull(self.llvm_type)) }
+            }
+
+            #[inline]
+            pub fn get_undef(&self) -> $value<'ctx> {
+                unsafe { $value::from_value_ref(LLVMGetUndef(self.llvm_type)) }
+            }
+
+            #[inline]
+            pub fn const_array(&self, values: &[$value<'ctx>]) -> ArrayValue<'ctx> {
+                let mut values = value_refs(values);
+                unsafe {
+                    ArrayValue::from_value_ref(LLVMConstArray(
+                        self.llvm_type,
+                        values.as_mut_ptr(),
+                        values.len() as u32,
+                    ))
+                }
+            }
+        }
+    };
+}
+
+typed_type!(VoidType, is_void_type);
+typed_type!(IntType, is_int_type);
+typed_type!(FloatType, is_float_type);
+typed_type!(PointerType, is_pointer_type);
+typed_type!(StructType, is_struct_type);
+typed_type!(ArrayType, is_array_type);
+typed_type!(VectorType, is_vector_type);
+typed_type!(FunctionType, is_function_type);
+
+const_values!(IntType, IntValue);
+const_values!(FloatType, FloatValue);
+const_values!(PointerType, PointerValue);
+const_values!(StructType, StructValue);
+const_values!(ArrayType, ArrayValue);
+const_values!(VectorType, VectorValue);
+
+impl<'ctx> IntType<'ctx> {
+    #[inline]
+    pub fn get_bit_width(&self) -> u32 {
+        unsafe { LLVMGetIntTypeWidth(self.llvm_type) }
+    }
+
+    #[inline]
+    pub fn const_int(&self, value: u64, signed: bool) -> IntValue<'ctx> {
+        unsafe {
+            IntValue::from_value_ref(LLVMConstInt(
+                self.llvm_type,
+                value,
+                if signed { 1 } else { 0 },
+            ))
+        }
+    }
+
+    #[inline]
+    pub fn const_all_ones(&self) -> IntValue<'ctx> {
+        unsafe { IntValue::from_value_ref(LLVMConstAllOnes(self.llvm_type)) }
+    }
+}
+
+impl<'ctx> FloatType<'ctx> {
+    #[inline]
+    pub fn const_float(&self, value: f64) -> FloatValue<'ctx> {
+        unsafe { FloatValue::from_value_ref(LLVMConstReal(self.llvm_type, value)) }
+    }
+}
+
+impl<'ctx> PointerType<'ctx> {
+    #[inline]
+    pub fn get_element_type(&self) -> Type<'ctx> {
+        unsafe { Type::from_ptr(LLVMGetElementType(self.llvm_type)) }
+    }
+
+    #[inline]
+    pub fn get_address_space(&self) -> u32 {
+        unsafe { LLVMGetPointerAddressSpace(self.llvm_type) }
+    }
+
+    #[inline]
+    pub fn const_null(&self) -> PointerValue<'ctx> {
+        unsafe { PointerValue::from_value_ref(LLVMConstPointerNull(self.llvm_type)) }
+    }
+}
+
+impl<'ctx> ArrayType<'ctx> {
+    #[inline]
+    pub fn get_element_type(&self) -> Type<'ctx> {
+        unsafe { Type::from_ptr(LLVMGetElementType(self.llvm_type)) }
+    }
+
+    #[inline]
+    pub fn get_length(&self) -> u32 {
+        unsafe { LLVMGetArrayLength(self.llvm_type) }
+    }
+}
+
+impl<'ctx> VectorType<'ctx> {
+    // `element_type` has to be an integer, float or pointer type.
+    #[inline]
+    pub fn new<T: AnyType<'ctx>>(element_type: T, count: u32) -> VectorType<'ctx> {
+        element_type.vector_type(count)
+    }
+
+    // One lane per value. None unless the values are all constants of one
+    // scalar type, and there is at least one of them.
+    pub fn new_const_vector<V: AnyValue<'ctx>>(constant_values: &[V]) -> Option<VectorValue<'ctx>> {
+        let first_type = constant_values.first()?.get_type();
+        let valid = constant_values
+            .iter()
+            .all(|value| value.is_constant() && value.get_type() == first_type);
+        if !valid {
+            return None;
+        }
+        let mut constant_values = value_refs(constant_values);
+        unsafe {
+            Some(VectorValue::from_value_ref(LLVMConstVector(
+                constant_values.as_mut_ptr(),
+                constant_values.len() as u32,
+            )))
+        }
+    }
+
+    // A constant of this type with `value` in every lane, or None when
+    // `value` is not a constant of the element type.
+    #[inline]
+    pub fn const_splat<V: AnyValue<'ctx>>(&self, value: V) -> Option<VectorValue<'ctx>> {
+        if value.get_type() != self.get_element_type() {
+            return None;
+        }
+        VectorType::new_const_vector(&vec![value.as_any_value(); self.get_size() as usize])
+    }
+
+    #[inline]
+    pub fn get_element_type(&self) -> Type<'ctx> {
+        unsafe { Type::from_ptr(LLVMGetElementType(self.llvm_type)) }
+    }
+
+    #[inline]
+    pub fn get_size(&self) -> u32 {
+        unsafe { LLVMGetVectorSize(self.llvm_type) }
+    }
+}
+
+impl<'ctx> FunctionType<'ctx> {
+    // The function type lives in the context of its return type.
+    pub fn new<T: AnyType<'ctx>>(
+        return_type: T,
+        param_types: &[Type<'ctx>],
+        is_var_arg: bool,
+    ) -> FunctionType<'ctx> {
+        let mut param_types = type_refs(param_types);
+        unsafe {
+            FunctionType::from_type_ref(LLVMFunctionType(
+                return_type.as_type_ref(),
+                param_types.as_mut_ptr(),
+                param_types.len() as u32,
+                if is_var_arg { 1 } else { 0 },
+            ))
+        }
+    }
+
+    #[inline]
+    pub fn get_return_type(&self) -> Type<'ctx> {
+        unsafe { Type::from_ptr(LLVMGetReturnType(self.llvm_type)) }
+    }
+
+    #[inline]
+    pub fn count_param_types(&self) -> u32 {
+        unsafe { LLVMCountParamTypes(self.llvm_type) }
+    }
+
+    pub fn get_param_types(&self) -> Vec<Type<'ctx>> {
+        let mut types = vec![0 as LLVMTypeRef; self.count_param_types() as usize];
+        unsafe { LLVMGetParamTypes(self.llvm_type, types.as_mut_ptr()) };
+        types.into_iter().map(Type::from_ptr).collect()
+    }
+
+    #[inline]
+    pub fn is_var_arg(&self) -> bool {
+        unsafe { LLVMIsFunctionVarArg(self.llvm_type) == 1 }
+    }
+}
+
+impl<'ctx> StructType<'ctx> {
+    pub fn new_with_name(context: &'ctx Context, name: &str) -> StructType<'ctx> {
+        let name = SmallCStr::new(name);
+        unsafe { StructType::from_type_ref(LLVMStructCreateNamed(context.as_ref(), name.as_ptr())) }
+    }
+
+    pub fn new(context: &'ctx Context, fields: &[Type<'ctx>], packed: bool) -> StructType<'ctx> {
+        let mut fields = type_refs(fields);
+        unsafe {
+            StructType::from_type_ref(LLVMStructTypeInContext(
+                context.as_ref(),
+                fields.as_mut_ptr(),
+                fields.len() as u32,
+                if packed { 1 } else { 0 },
+            ))
+        }
+    }
+
+    #[inline]
+    pub fn new_const_struct(
+        context: &'ctx Context,
+        constant_values: &[Value<'ctx>],
+        packed: bool,
+    ) -> StructValue<'ctx> {
+        let mut constant_values = value_refs(constant_values);
+        unsafe {
+            StructValue::from_value_ref(LLVMConstStructInContext(
+                context.as_ref(),
+                constant_values.as_mut_ptr(),
+                constant_values.len() as u32,
+                if packed { 1 } else { 0 },
+            ))
+        }
+    }
+
+    #[inline]
+    pub fn set_body(&self, fields: &[Type<'ctx>], packed: bool) {
+        let mut fields = type_refs(fields);
+        unsafe {
+            LLVMStructSetBody(
+                self.llvm_type,
+                fields.as_mut_ptr(),
+                fields.len() as u32,
+                if packed { 1 } else { 0 },
+            )
+        }
+    }
+
+    // Literal structs have no name.
+    pub fn get_name(&self) -> Option<String> {
+        let name = unsafe { LLVMGetStructName(self.llvm_type) };
+        if name.is_null() {
+            None
+        } else {
+            Some(unsafe { CStr::from_ptr(name).to_string_lossy().into_owned() })
+        }
+    }
+
+    #[inline]
+    pub fn count_fields(&self) -> u32 {
+        unsafe { LLVMCountStructElementTypes(self.llvm_type) }
+    }
+
+    pub fn get_field_types(&self) -> Vec<Type<'ctx>> {
+        let mut types = vec![0 as LLVMTypeRef; self.count_fields() as usize];
+        unsafe { LLVMGetStructElementTypes(self.llvm_type, types.as_mut_ptr()) };
+        types.into_iter().map(Type::from_ptr).collect()
+    }
+
+    #[inline]
+    pub fn get_field_type_at_index(&self, index: u32) -> Option<Type<'ctx>> {
+        if index < self.count_fields() {
+            Some(unsafe { Type::from_ptr(LLVMStructGetTypeAtIndex(self.llvm_type, index)) })
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    pub fn is_packed(&self) -> bool {
+        unsafe { LLVMIsPackedStruct(self.llvm_type) == 1 }
+    }
+
+    #[inline]
+    pub fn is_opaque(&self) -> bool {
+        unsafe { LLVMIsOpaqueStruct(self.llvm_type) == 1 }
+    }
+
+    #[inline]
+    pub fn const_named_struct(&self, values: &[Value<'ctx>]) -> StructValue<'ctx> {
+        let mut values = value_refs(values);
+        unsafe {
+            StructValue::from_value_ref(LLVMConstNamedStruct(
+                self.llvm_type,
+                values.as_mut_ptr(),
+                values.len() as u32,
+            ))
+        }
+    }
+}
+
+#[inline]
+pub(crate) fn type_refs<'ctx, T: AnyType<'ctx>>(types: &[T]) -> Vec<LLVMTypeRef> {
+    types.iter().map(|typ| typ.as_type_ref()).collect()
+}
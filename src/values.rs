@@ -0,0 +1,434 @@
+#![allow(dead_code)]
+
+extern crate llvm_sys;
+
+use self::llvm_sys::core::*;
+use self::llvm_sys::prelude::*;
+use call_site::CallSite;
+use context::ContextMarker;
+use function::Function;
+use instruction::Instruction;
+use message::take_message;
+use small_cstr::SmallCStr;
+use std::convert::TryFrom;
+use std::marker::PhantomData;
+use std::slice;
+use types::{AnyType, ArrayType, FloatType, IntType, PointerType, StructType, Type, VectorType};
+
+// Common operations on every kind of value handle.
+pub trait AnyValue<'ctx>: Copy {
+    fn as_value_ref(&self) -> LLVMValueRef;
+
+    /// Wraps a raw value without checking its kind.
+    ///
+    /// # Safety
+    ///
+    /// `value` must be a live LLVM value of the kind `Self` stands for.
+    unsafe fn from_value_ref(value: LLVMValueRef) -> Self;
+
+    #[inline]
+    fn as_any_value(&self) -> Value<'ctx> {
+        Value::from_ptr(self.as_value_ref())
+    }
+
+    #[inline]
+    fn get_type(&self) -> Type<'ctx> {
+        unsafe { Type::from_ptr(LLVMTypeOf(self.as_value_ref())) }
+    }
+
+    fn get_name(&self) -> String {
+        let mut len = 0;
+        unsafe {
+            let ptr = LLVMGetValueName2(self.as_value_ref(), &mut len);
+            let bytes = slice::from_raw_parts(ptr as *const u8, len);
+            String::from_utf8_lossy(bytes).into_owned()
+        }
+    }
+
+    fn set_name(&self, name: &str) {
+        let val_name = SmallCStr::new(name);
+        let len = name.find('\0').unwrap_or(name.len());
+        unsafe { LLVMSetValueName2(self.as_value_ref(), val_name.as_ptr(), len) }
+    }
+
+    #[inline]
+    fn is_constant(&self) -> bool {
+        unsafe { LLVMIsConstant(self.as_value_ref()) == 1 }
+    }
+
+    #[inline]
+    fn is_undef(&self) -> bool {
+        unsafe { LLVMIsUndef(self.as_value_ref()) == 1 }
+    }
+
+    #[inline]
+    fn dump(&self) {
+        unsafe { LLVMDumpValue(self.as_value_ref()) }
+    }
+
+    fn print_to_string(&self) -> String {
+        take_message(unsafe { LLVMPrintValueToString(self.as_value_ref()) })
+    }
+}
+
+// Values that integer arithmetic can be built on: scalars and vectors of integers.
+// `BaseType` is the kind of type they can be extended or truncated to.
+pub trait IntMathValue<'ctx>: AnyValue<'ctx> {
+    type BaseType: AnyType<'ctx>;
+}
+
+// Values that floating point arithmetic can be built on: scalars and vectors of floats.
+// `Mask` is what comparing two of them yields, i1 or a vector of i1.
+pub trait FloatMathValue<'ctx>: AnyValue<'ctx> {
+    type BaseType: AnyType<'ctx>;
+    type Mask: AnyValue<'ctx>;
+}
+
+// Values that `icmp` accepts: integers, pointers and vectors of integers.
+pub trait IntCompareValue<'ctx>: AnyValue<'ctx> {
+    type Mask: AnyValue<'ctx>;
+}
+
+// An untyped value, as returned by loads, calls and other instructions whose
+// result kind can not be known statically.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Value<'ctx> {
+    llvm_value: LLVMValueRef,
+    _context: ContextMarker<'ctx>,
+}
+
+impl<'ctx> Value<'ctx> {
+    pub fn from_ptr(value_ptr: LLVMValueRef) -> Value<'ctx> {
+        Value {
+            llvm_value: value_ptr,
+            _context: PhantomData,
+        }
+    }
+
+    pub fn as_ref(&self) -> LLVMValueRef {
+        self.llvm_value
+    }
+
+    #[inline]
+    pub fn is_int_value(&self) -> bool {
+        self.get_type().is_int_type()
+    }
+
+    #[inline]
+    pub fn is_float_value(&self) -> bool {
+        self.get_type().is_float_type()
+    }
+
+    #[inline]
+    pub fn is_pointer_value(&self) -> bool {
+        self.get_type().is_pointer_type()
+    }
+
+    #[inline]
+    pub fn is_struct_value(&self) -> bool {
+        self.get_type().is_struct_type()
+    }
+
+    #[inline]
+    pub fn is_array_value(&self) -> bool {
+        self.get_type().is_array_type()
+    }
+
+    #[inline]
+    pub fn is_vector_value(&self) -> bool {
+        self.get_type().is_vector_type()
+    }
+
+    // A vector whose lanes are integers.
+    #[inline]
+    pub fn is_int_vector_value(&self) -> bool {
+        match self.get_type().into_vector_type() {
+            Some(vector_type) => vector_type.get_element_type().is_int_type(),
+            None => false,
+        }
+    }
+
+    // A vector whose lanes are floats.
+    #[inline]
+    pub fn is_float_vector_value(&self) -> bool {
+        match self.get_type().into_vector_type() {
+            Some(vector_type) => vector_type.get_element_type().is_float_type(),
+            None => false,
+        }
+    }
+
+    #[inline]
+    pub fn is_function_value(&self) -> bool {
+        unsafe { !LLVMIsAFunction(self.llvm_value).is_null() }
+    }
+
+    #[inline]
+    pub fn is_call_site(&self) -> bool {
+        unsafe { !LLVMIsACallInst(self.llvm_value).is_null() }
+    }
+
+    #[inline]
+    pub fn is_instruction(&self) -> bool {
+        unsafe { !LLVMIsAInstruction(self.llvm_value).is_null() }
+    }
+
+    #[inline]
+    pub fn into_int_value(self) -> Option<IntValue<'ctx>> {
+        IntValue::try_from(self).ok()
+    }
+
+    #[inline]
+    pub fn into_float_value(self) -> Option<FloatValue<'ctx>> {
+        FloatValue::try_from(self).ok()
+    }
+
+    #[inline]
+    pub fn into_pointer_value(self) -> Option<PointerValue<'ctx>> {
+        PointerValue::try_from(self).ok()
+    }
+
+    #[inline]
+    pub fn into_struct_value(self) -> Option<StructValue<'ctx>> {
+        StructValue::try_from(self).ok()
+    }
+
+    #[inline]
+    pub fn into_array_value(self) -> Option<ArrayValue<'ctx>> {
+        ArrayValue::try_from(self).ok()
+    }
+
+    #[inline]
+    pub fn into_vector_value(self) -> Option<VectorValue<'ctx>> {
+        VectorValue::try_from(self).ok()
+    }
+
+    #[inline]
+    pub fn into_int_vector_value(self) -> Option<IntVectorValue<'ctx>> {
+        IntVectorValue::try_from(self).ok()
+    }
+
+    #[inline]
+    pub fn into_float_vector_value(self) -> Option<FloatVectorValue<'ctx>> {
+        FloatVectorValue::try_from(self).ok()
+    }
+
+    #[inline]
+    pub fn into_function_value(self) -> Option<Function<'ctx>> {
+        Function::try_from(self).ok()
+    }
+
+    #[inline]
+    pub fn into_call_site(self) -> Option<CallSite<'ctx>> {
+        CallSite::try_from(self).ok()
+    }
+
+    #[inline]
+    pub fn into_instruction(self) -> Option<Instruction<'ctx>> {
+        Instruction::try_from(self).ok()
+    }
+}
+
+impl<'ctx> AnyValue<'ctx> for Value<'ctx> {
+    #[inline]
+    fn as_value_ref(&self) -> LLVMValueRef {
+        self.llvm_value
+    }
+
+    #[inline]
+    unsafe fn from_value_ref(value: LLVMValueRef) -> Self {
+        Value::from_ptr(value)
+    }
+}
+
+macro_rules! typed_value {
+    ($name:ident, $check:ident, $typ:ident) => {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub struct $name<'ctx> {
+            llvm_value: LLVMValueRef,
+            _context: ContextMarker<'ctx>,
+        }
+
+        impl<'ctx> $name<'ctx> {
+            pub fn as_ref(&self) -> LLVMValueRef {
+                self.llvm_value
+            }
+
+            #[inline]
+            pub fn get_type(&self) -> $typ<'ctx> {
+                unsafe { $typ::from_type_ref(LLVMTypeOf(self.llvm_value)) }
+            }
+        }
+
+        impl<'ctx> AnyValue<'ctx> for $name<'ctx> {
+            #[inline]
+            fn as_value_ref(&self) -> LLVMValueRef {
+                self.llvm_value
+            }
+
+            #[inline]
+            unsafe fn from_value_ref(value: LLVMValueRef) -> Self {
+                $name {
+                    llvm_value: value,
+                    _context: PhantomData,
+                }
+            }
+        }
+
+        impl<'ctx> From<$name<'ctx>> for Value<'ctx> {
+            #[inline]
+            fn from(value: $name<'ctx>) -> Value<'ctx> {
+                Value::from_ptr(value.llvm_value)
+            }
+        }
+
+        impl<'ctx> TryFrom<Value<'ctx>> for $name<'ctx> {
+            type Error = Value<'ctx>;
+
+            fn try_from(value: Value<'ctx>) -> Result<Self, Self::Error> {
+                if value.$check() {
+                    Ok(unsafe { $name::from_value_ref(value.as_ref()) })
+                } else {
+                    Err(value)
+                }
+            }
+        }
+    };
+}
+
+typed_value!(IntValue, is_int_value, IntType);
+typed_value!(FloatValue, is_float_value, FloatType);
+typed_value!(PointerValue, is_pointer_value, PointerType);
+typed_value!(StructValue, is_struct_value, StructType);
+typed_value!(ArrayValue, is_array_value, ArrayType);
+typed_value!(VectorValue, is_vector_value, VectorType);
+// Vectors split by lane kind, so integer and float arithmetic each only take
+// their own; VectorValue covers every vector for shuffles and the like.
+typed_value!(IntVectorValue, is_int_vector_value, VectorType);
+typed_value!(FloatVectorValue, is_float_vector_value, VectorType);
+
+impl<'ctx> IntValue<'ctx> {
+    #[inline]
+    pub fn is_const_int(&self) -> bool {
+        unsafe { !LLVMIsAConstantInt(self.llvm_value).is_null() }
+    }
+
+    // Returns the zero extended value of a constant integer.
+    #[inline]
+    pub fn get_zext_const(&self) -> Option<u64> {
+        if self.is_const_int() {
+            Some(unsafe { LLVMConstIntGetZExtValue(self.llvm_value) })
+        } else {
+            None
+        }
+    }
+
+    // Returns the sign extended value of a constant integer.
+    #[inline]
+    pub fn get_sext_const(&self) -> Option<i64> {
+        if self.is_const_int() {
+            Some(unsafe { LLVMConstIntGetSExtValue(self.llvm_value) })
+        } else {
+            None
+        }
+    }
+}
+
+impl<'ctx> VectorValue<'ctx> {
+    // None when the lanes are not integers.
+    #[inline]
+    pub fn into_int_vector_value(self) -> Option<IntVectorValue<'ctx>> {
+        Value::from(self).into_int_vector_value()
+    }
+
+    // None when the lanes are not floats.
+    #[inline]
+    pub fn into_float_vector_value(self) -> Option<FloatVectorValue<'ctx>> {
+        Value::from(self).into_float_vector_value()
+    }
+}
+
+impl<'ctx> From<IntVectorValue<'ctx>> for VectorValue<'ctx> {
+    #[inline]
+    fn from(value: IntVectorValue<'ctx>) -> VectorValue<'ctx> {
+        unsafe { VectorValue::from_value_ref(value.llvm_value) }
+    }
+}
+
+impl<'ctx> From<FloatVectorValue<'ctx>> for VectorValue<'ctx> {
+    #[inline]
+    fn from(value: FloatVectorValue<'ctx>) -> VectorValue<'ctx> {
+        unsafe { VectorValue::from_value_ref(value.llvm_value) }
+    }
+}
+
+impl<'ctx> PointerValue<'ctx> {
+    #[inline]
+    pub fn is_null(&self) -> bool {
+        unsafe { LLVMIsNull(self.llvm_value) == 1 }
+    }
+}
+
+impl<'ctx> IntMathValue<'ctx> for IntValue<'ctx> {
+    type BaseType = IntType<'ctx>;
+}
+impl<'ctx> IntMathValue<'ctx> for IntVectorValue<'ctx> {
+    type BaseType = VectorType<'ctx>;
+}
+
+impl<'ctx> FloatMathValue<'ctx> for FloatValue<'ctx> {
+    type BaseType = FloatType<'ctx>;
+    type Mask = IntValue<'ctx>;
+}
+impl<'ctx> FloatMathValue<'ctx> for FloatVectorValue<'ctx> {
+    type BaseType = VectorType<'ctx>;
+    type Mask = IntVectorValue<'ctx>;
+}
+
+impl<'ctx> IntCompareValue<'ctx> for IntValue<'ctx> {
+    type Mask = IntValue<'ctx>;
+}
+impl<'ctx> IntCompareValue<'ctx> for PointerValue<'ctx> {
+    type Mask = IntValue<'ctx>;
+}
+impl<'ctx> IntCompareValue<'ctx> for IntVectorValue<'ctx> {
+    type Mask = IntVectorValue<'ctx>;
+}
+
+// Functions are values too; `Function` is the typed handle for them.
+pub type FunctionValue<'ctx> = Function<'ctx>;
+
+impl<'ctx> AnyValue<'ctx> for Function<'ctx> {
+    #[inline]
+    fn as_value_ref(&self) -> LLVMValueRef {
+        self.as_ref()
+    }
+
+    #[inline]
+    unsafe fn from_value_ref(value: LLVMValueRef) -> Self {
+        Function::from_ptr(value)
+    }
+}
+
+impl<'ctx> From<Function<'ctx>> for Value<'ctx> {
+    #[inline]
+    fn from(function: Function<'ctx>) -> Value<'ctx> {
+        Value::from_ptr(function.as_ref())
+    }
+}
+
+impl<'ctx> TryFrom<Value<'ctx>> for Function<'ctx> {
+    type Error = Value<'ctx>;
+
+    fn try_from(value: Value<'ctx>) -> Result<Self, Self::Error> {
+        if value.is_function_value() {
+            Ok(Function::from_ptr(value.as_ref()))
+        } else {
+            Err(value)
+        }
+    }
+}
+
+#[inline]
+pub(crate) fn value_refs<'ctx, V: AnyValue<'ctx>>(values: &[V]) -> Vec<LLVMValueRef> {
+    values.iter().map(|value| value.as_value_ref()).collect()
+}
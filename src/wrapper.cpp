@@ -0,0 +1,96 @@
+// Bits of the C++ API the LLVM C API does not expose yet.
+
+#include "llvm-c/Core.h"
+#include "llvm/Config/llvm-config.h"
+#include "llvm/IR/Attributes.h"
+#include "llvm/IR/Function.h"
+#include "llvm/IR/GlobalAlias.h"
+#include "llvm/IR/Instruction.h"
+#include "llvm/IR/Operator.h"
+#include "llvm/IR/Verifier.h"
+#include "llvm/Support/raw_ostream.h"
+
+using namespace llvm;
+
+// Same bit layout as FastMathFlags in fast_math.rs.
+enum {
+  WrapperAllowReassoc = 1 << 0,
+  WrapperNoNaNs = 1 << 1,
+  WrapperNoInfs = 1 << 2,
+  WrapperNoSignedZeros = 1 << 3,
+  WrapperAllowReciprocal = 1 << 4,
+  WrapperAllowContract = 1 << 5,
+  WrapperApproxFunc = 1 << 6,
+};
+
+extern "C" LLVMBool LLVMWrapperCanUseFastMathFlags(LLVMValueRef Val) {
+  Value *V = unwrap(Val);
+  return isa<Instruction>(V) && isa<FPMathOperator>(V);
+}
+
+extern "C" unsigned LLVMWrapperGetFastMathFlags(LLVMValueRef Val) {
+  if (!LLVMWrapperCanUseFastMathFlags(Val))
+    return 0;
+  FastMathFlags FMF = unwrap<Instruction>(Val)->getFastMathFlags();
+  unsigned Flags = 0;
+  if (FMF.allowReassoc())
+    Flags |= WrapperAllowReassoc;
+  if (FMF.noNaNs())
+    Flags |= WrapperNoNaNs;
+  if (FMF.noInfs())
+    Flags |= WrapperNoInfs;
+  if (FMF.noSignedZeros())
+    Flags |= WrapperNoSignedZeros;
+  if (FMF.allowReciprocal())
+    Flags |= WrapperAllowReciprocal;
+  if (FMF.allowContract())
+    Flags |= WrapperAllowContract;
+  if (FMF.approxFunc())
+    Flags |= WrapperApproxFunc;
+  return Flags;
+}
+
+extern "C" void LLVMWrapperSetFastMathFlags(LLVMValueRef Val, unsigned Flags) {
+  if (!LLVMWrapperCanUseFastMathFlags(Val))
+    return;
+  FastMathFlags FMF;
+  FMF.setAllowReassoc(Flags & WrapperAllowReassoc);
+  FMF.setNoNaNs(Flags & WrapperNoNaNs);
+  FMF.setNoInfs(Flags & WrapperNoInfs);
+  FMF.setNoSignedZeros(Flags & WrapperNoSignedZeros);
+  FMF.setAllowReciprocal(Flags & WrapperAllowReciprocal);
+  FMF.setAllowContract(Flags & WrapperAllowContract);
+  FMF.setApproxFunc(Flags & WrapperApproxFunc);
+  unwrap<Instruction>(Val)->setFastMathFlags(FMF);
+}
+
+// LLVMDeleteGlobal casts to GlobalVariable, which an alias is not.
+extern "C" void LLVMWrapperEraseGlobalAlias(LLVMValueRef Alias) {
+  unwrap<GlobalAlias>(Alias)->eraseFromParent();
+}
+
+// LLVMVerifyFunction can only print its findings to stderr; this hands them
+// back, in a string for LLVMDisposeMessage, the way LLVMVerifyModule does.
+extern "C" LLVMBool LLVMWrapperVerifyFunction(LLVMValueRef Fn, char **OutMessage) {
+  std::string Message;
+  raw_string_ostream Stream(Message);
+  bool Broken = verifyFunction(*unwrap<Function>(Fn), &Stream);
+  *OutMessage = LLVMCreateMessage(Stream.str().c_str());
+  return Broken;
+}
+
+// `sret` and `byval` with their pointee type. LLVM 12 made every such
+// attribute typed; before that only byval could carry one, and sret is left
+// a plain enum attribute.
+extern "C" LLVMAttributeRef LLVMWrapperCreateTypeAttribute(LLVMContextRef C, unsigned KindID,
+                                                           LLVMTypeRef Ty) {
+  LLVMContext &Ctx = *unwrap(C);
+  Attribute::AttrKind Kind = static_cast<Attribute::AttrKind>(KindID);
+#if LLVM_VERSION_MAJOR >= 12
+  return wrap(Attribute::get(Ctx, Kind, unwrap(Ty)));
+#else
+  if (Kind == Attribute::ByVal)
+    return wrap(Attribute::getWithByValType(Ctx, unwrap(Ty)));
+  return wrap(Attribute::get(Ctx, Kind));
+#endif
+}
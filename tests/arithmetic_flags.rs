@@ -0,0 +1,174 @@
+extern crate llvm_sys_wrapper;
+
+use llvm_sys_wrapper::*;
+
+fn instcombine(module: &Module, function: Function) {
+    let fpm = FunctionPassManager::new(module);
+    fpm.add_instruction_combining_pass();
+    fpm.initialize();
+    fpm.run(function);
+    fpm.finalize();
+}
+
+#[test]
+fn test_wrap_and_exact_flags() {
+    let context = Context::new();
+    let builder = context.create_builder();
+    let module = context.create_module("wrap");
+    let i32_type = context.Int32Type();
+    let function = module.add_function("flags", fn_type!(i32_type, i32_type, i32_type));
+    builder.position_at_end(function.append_basic_block("entry"));
+    let a = function.get_param(0).into_int_value().unwrap();
+    let b = function.get_param(1).into_int_value().unwrap();
+
+    let values = [
+        builder.build_nsw_add_with_name(a, b, "nsw_add"),
+        builder.build_nuw_add_with_name(a, b, "nuw_add"),
+        builder.build_nsw_sub_with_name(a, b, "nsw_sub"),
+        builder.build_nuw_sub_with_name(a, b, "nuw_sub"),
+        builder.build_nsw_mul_with_name(a, b, "nsw_mul"),
+        builder.build_nuw_mul_with_name(a, b, "nuw_mul"),
+        builder.build_exact_sdiv_with_name(a, b, "exact_sdiv"),
+        builder.build_exact_udiv_with_name(a, b, "exact_udiv"),
+        builder.build_nsw_neg_with_name(a, "nsw_neg"),
+        builder.build_nuw_neg_with_name(a, "nuw_neg"),
+    ];
+    let total = values[1..]
+        .iter()
+        .fold(values[0], |total, &value| builder.build_xor(total, value));
+    builder.build_ret(total);
+    assert!(module.verify().is_ok());
+
+    let ir = module.print_module_to_string();
+    assert!(ir.contains("%nsw_add = add nsw i32 %0, %1"));
+    assert!(ir.contains("%nuw_add = add nuw i32 %0, %1"));
+    assert!(ir.contains("%nsw_sub = sub nsw i32 %0, %1"));
+    assert!(ir.contains("%nuw_sub = sub nuw i32 %0, %1"));
+    assert!(ir.contains("%nsw_mul = mul nsw i32 %0, %1"));
+    assert!(ir.contains("%nuw_mul = mul nuw i32 %0, %1"));
+    assert!(ir.contains("%exact_sdiv = sdiv exact i32 %0, %1"));
+    assert!(ir.contains("%exact_udiv = udiv exact i32 %0, %1"));
+    assert!(ir.contains("%nsw_neg = sub nsw i32 0, %0"));
+    assert!(ir.contains("%nuw_neg = sub nuw i32 0, %0"));
+}
+
+#[test]
+fn test_nsw_lets_the_optimizer_assume_no_overflow() {
+    let context = Context::new();
+    let builder = context.create_builder();
+    let module = context.create_module("nsw");
+    let i32_type = context.Int32Type();
+    let bool_type = context.Int1Type();
+
+    // x + 1 > x only holds for every x when the add cannot wrap
+    let mut functions = Vec::new();
+    for &(name, nsw) in &[("wrapping", false), ("nsw", true)] {
+        let function = module.add_function(name, fn_type!(bool_type, i32_type));
+        builder.position_at_end(function.append_basic_block("entry"));
+        let x = function.get_param(0).into_int_value().unwrap();
+        let next = if nsw {
+            builder.build_nsw_add(x, context.SInt32(1))
+        } else {
+            builder.build_add(x, context.SInt32(1))
+        };
+        builder.build_ret(builder.build_icmp_sgt(next, x));
+        functions.push(function);
+    }
+    for &function in &functions {
+        instcombine(&module, function);
+    }
+
+    let ir = module.print_module_to_string();
+    assert!(ir.contains("icmp ne i32 %0, 2147483647"));
+    assert!(ir.contains("define i1 @nsw(i32 %0) {\nentry:\n  ret i1 true\n}"));
+}
+
+#[test]
+fn test_builder_fast_math_flags() {
+    let context = Context::new();
+    let builder = context.create_builder();
+    let module = context.create_module("fast_math");
+    let float_type = context.FloatType();
+    let sqrt = module.add_function("sqrtf", fn_type!(float_type, float_type));
+    let function = module.add_function("kernel", fn_type!(float_type, float_type, float_type));
+    builder.position_at_end(function.append_basic_block("entry"));
+    let a = function.get_param(0).into_float_value().unwrap();
+    let b = function.get_param(1).into_float_value().unwrap();
+
+    assert_eq!(builder.get_fast_math_flags(), FastMathFlags::NONE);
+    let plain = builder.build_fadd_with_name(a, b, "plain");
+
+    builder.set_fast_math_flags(FastMathFlags::FAST);
+    let sum = builder.build_fadd_with_name(a, b, "sum");
+    let difference = builder.build_fsub_with_name(sum, b, "difference");
+    let root = builder.build_call_with_name(sqrt, &[difference.into()], "root");
+    let root = root.into_float_value().unwrap();
+
+    builder.set_fast_math_flags(FastMathFlags::NO_NANS | FastMathFlags::NO_INFS);
+    let product = builder.build_fmul_with_name(root, plain, "product");
+    let less = builder.build_fcmp_olt_with_name(product, a, "less");
+    let chosen = builder.build_select_with_name(less, product, b, "chosen");
+    // integer operations and constants are left alone
+    let mask = builder.build_and_with_name(less, context.UInt1(1), "mask");
+    let folded = builder.build_fmul(context.Float(2.0), context.Float(3.0));
+
+    builder.set_fast_math_flags(FastMathFlags::NONE);
+    let quotient = builder.build_fdiv_with_name(chosen, folded, "quotient");
+    builder.build_ret(quotient);
+    assert!(module.verify().is_ok());
+
+    let ir = module.print_module_to_string();
+    assert!(ir.contains("%plain = fadd float %0, %1"));
+    assert!(ir.contains("%sum = fadd fast float %0, %1"));
+    assert!(ir.contains("%difference = fsub fast float %sum, %1"));
+    assert!(ir.contains("%root = call fast float @sqrtf(float %difference)"));
+    assert!(ir.contains("%product = fmul nnan ninf float %root, %plain"));
+    assert!(ir.contains("%less = fcmp nnan ninf olt float %product, %0"));
+    assert!(ir.contains("%chosen = select nnan ninf i1 %less, float %product, float %1"));
+    assert!(ir.contains("%mask = and i1 %less, true"));
+    assert!(ir.contains("%quotient = fdiv float %chosen, 6.000000e+00"));
+
+    let mask = mask.as_any_value().into_instruction().unwrap();
+    assert!(!mask.can_use_fast_math_flags());
+    assert!(mask.get_fast_math_flags().is_empty());
+    let sum = sum.as_any_value().into_instruction().unwrap();
+    assert_eq!(sum.get_fast_math_flags(), FastMathFlags::FAST);
+    assert!(sum
+        .get_fast_math_flags()
+        .contains(FastMathFlags::ALLOW_CONTRACT));
+}
+
+#[test]
+fn test_instruction_fast_math_flags() {
+    let context = Context::new();
+    let builder = context.create_builder();
+    let module = context.create_module("instruction_flags");
+    let double_type = context.DoubleType();
+    let function = module.add_function("add_zero", fn_type!(double_type, double_type));
+    builder.position_at_end(function.append_basic_block("entry"));
+    let x = function.get_param(0).into_float_value().unwrap();
+    let sum = builder.build_fadd(x, context.Double(0.0));
+    builder.build_ret(sum);
+
+    // x + 0.0 is -0.0 + 0.0 = 0.0 for x = -0.0, so it only folds to x under nsz
+    instcombine(&module, function);
+    assert!(module
+        .print_module_to_string()
+        .contains("fadd double %0, 0.000000e+00"));
+
+    let sum = sum.as_any_value().into_instruction().unwrap();
+    assert!(sum.can_use_fast_math_flags());
+    let mut flags = FastMathFlags::ALLOW_CONTRACT;
+    flags |= FastMathFlags::NO_SIGNED_ZEROS;
+    sum.set_fast_math_flags(flags);
+    assert_eq!(sum.get_fast_math_flags(), flags);
+    assert_eq!(sum.get_fast_math_flags().bits(), 0b10_1000);
+    assert!(module
+        .print_module_to_string()
+        .contains("fadd nsz contract double %0, 0.000000e+00"));
+
+    instcombine(&module, function);
+    assert!(module
+        .print_module_to_string()
+        .contains("define double @add_zero(double %0) {\nentry:\n  ret double %0\n}"));
+}
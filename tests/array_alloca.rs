@@ -4,7 +4,7 @@ use llvm_sys_wrapper::*;
 
 #[test]
 fn test_array_alloca() {
-    LLVM::initialize();
+    LLVM::initialize().unwrap();
 
     // create context
     let context = Context::global_context();
@@ -26,64 +26,64 @@ fn test_array_alloca() {
     // alloca
     let buf = builder.build_array_alloca(context.Int8Type(), context.SInt32(7));
     let ptr = builder.build_alloca(context.Int8PointerType());
-    let mut args = [context.SInt32(0)];
-    builder.build_store(builder.build_inbounds_gep(buf, &mut args), ptr);
+    let args = [context.SInt32(0)];
+    builder.build_store(builder.build_inbounds_gep(buf, &args), ptr);
 
     // setup buf
-    let tmp = builder.build_load(ptr);
+    let tmp = builder.build_load(ptr).into_pointer_value().unwrap();
     builder.build_store(context.UInt8('H' as u64), tmp);
 
-    let mut args = [context.SInt32(1)];
+    let args = [context.SInt32(1)];
     builder.build_store(
-        builder.build_inbounds_gep(builder.build_load(ptr), &mut args),
+        builder.build_inbounds_gep(builder.build_load(ptr).into_pointer_value().unwrap(), &args),
         ptr,
     );
-    let tmp = builder.build_load(ptr);
+    let tmp = builder.build_load(ptr).into_pointer_value().unwrap();
     builder.build_store(context.UInt8('e' as u64), tmp);
 
-    let mut args = [context.SInt32(1)];
+    let args = [context.SInt32(1)];
     builder.build_store(
-        builder.build_inbounds_gep(builder.build_load(ptr), &mut args),
+        builder.build_inbounds_gep(builder.build_load(ptr).into_pointer_value().unwrap(), &args),
         ptr,
     );
-    let tmp = builder.build_load(ptr);
+    let tmp = builder.build_load(ptr).into_pointer_value().unwrap();
     builder.build_store(context.UInt8('l' as u64), tmp);
 
-    let mut args = [context.SInt32(1)];
+    let args = [context.SInt32(1)];
     builder.build_store(
-        builder.build_inbounds_gep(builder.build_load(ptr), &mut args),
+        builder.build_inbounds_gep(builder.build_load(ptr).into_pointer_value().unwrap(), &args),
         ptr,
     );
-    let tmp = builder.build_load(ptr);
+    let tmp = builder.build_load(ptr).into_pointer_value().unwrap();
     builder.build_store(context.UInt8('l' as u64), tmp);
 
-    let mut args = [context.SInt32(1)];
+    let args = [context.SInt32(1)];
     builder.build_store(
-        builder.build_inbounds_gep(builder.build_load(ptr), &mut args),
+        builder.build_inbounds_gep(builder.build_load(ptr).into_pointer_value().unwrap(), &args),
         ptr,
     );
-    let tmp = builder.build_load(ptr);
+    let tmp = builder.build_load(ptr).into_pointer_value().unwrap();
     builder.build_store(context.UInt8('o' as u64), tmp);
 
-    let mut args = [context.SInt32(1)];
+    let args = [context.SInt32(1)];
     builder.build_store(
-        builder.build_inbounds_gep(builder.build_load(ptr), &mut args),
+        builder.build_inbounds_gep(builder.build_load(ptr).into_pointer_value().unwrap(), &args),
         ptr,
     );
-    let tmp = builder.build_load(ptr);
+    let tmp = builder.build_load(ptr).into_pointer_value().unwrap();
     builder.build_store(context.UInt8('\n' as u64), tmp);
 
-    let mut args = [context.SInt32(1)];
+    let args = [context.SInt32(1)];
     builder.build_store(
-        builder.build_inbounds_gep(builder.build_load(ptr), &mut args),
+        builder.build_inbounds_gep(builder.build_load(ptr).into_pointer_value().unwrap(), &args),
         ptr,
     );
-    let tmp = builder.build_load(ptr);
+    let tmp = builder.build_load(ptr).into_pointer_value().unwrap();
     builder.build_store(context.UInt8('\0' as u64), tmp);
 
     // call printf function
-    let mut args = [buf];
-    let _call = builder.build_call(printf_func.as_ref(), &mut args);
+    let args = [buf.into()];
+    let _call = builder.build_call(printf_func, &args);
 
     // ret
     let _ret = builder.build_ret_void();
@@ -0,0 +1,194 @@
+extern crate llvm_sys_wrapper;
+
+use llvm_sys_wrapper::execution_engine::LLVMGetFunctionAddress;
+use llvm_sys_wrapper::*;
+use std::ffi::CString;
+use std::mem;
+use std::thread;
+
+const THREADS: usize = 8;
+const ROUNDS: i32 = 100_000;
+
+// void name(i32* counter, i32 rounds) { for (i = 0; i < rounds; i++) step(counter); }
+fn build_loop<'ctx, F>(context: &'ctx Context, module: &Module<'ctx>, name: &str, step: F)
+where
+    F: Fn(&Builder<'ctx>, Function<'ctx>, PointerValue<'ctx>),
+{
+    let builder = context.create_builder();
+    let i32_type = context.Int32Type();
+    let fun_type = fn_type!(context.VoidType(), context.PointerType(i32_type), i32_type);
+    let function = module.add_function(name, fun_type);
+    let entry = function.append_basic_block("entry");
+    let body = function.append_basic_block("body");
+    let exit = function.append_basic_block("exit");
+    let counter = function.get_param(0).into_pointer_value().unwrap();
+    let rounds = function.get_param(1).into_int_value().unwrap();
+
+    builder.position_at_end(entry);
+    builder.build_br(body);
+    builder.position_at_end(body);
+    let index = builder.build_phi(i32_type);
+    step(&builder, function, counter);
+    // the step may have added blocks of its own
+    let latch = builder.get_insert_block().unwrap();
+    let next = builder.build_add(
+        index.as_any_value().into_int_value().unwrap(),
+        i32_type.const_int(1, false),
+    );
+    index.add_incoming(i32_type.const_int(0, false), entry);
+    index.add_incoming(next, latch);
+    let more = builder.build_icmp_slt(next, rounds);
+    builder.build_cond_br(more, body, exit);
+    builder.position_at_end(exit);
+    builder.build_ret_void();
+}
+
+fn hammer(engine: &Engine, name: &str) -> i32 {
+    let name = CString::new(name).unwrap();
+    let address = unsafe { LLVMGetFunctionAddress(engine.as_ref(), name.as_ptr()) };
+    assert_ne!(address, 0);
+    let function: extern "C" fn(*mut i32, i32) = unsafe { mem::transmute(address as usize) };
+
+    let mut counter = Box::new(0i32);
+    let address = &mut *counter as *mut i32 as usize;
+    let threads: Vec<_> = (0..THREADS)
+        .map(|_| thread::spawn(move || function(address as *mut i32, ROUNDS)))
+        .collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    *counter
+}
+
+#[test]
+fn test_atomic_counters_across_threads() {
+    LLVM::initialize().unwrap();
+    let context = Context::new();
+    let module = context.create_module("atomics");
+    let i32_type = context.Int32Type();
+
+    build_loop(&context, &module, "add_rmw", |builder, _, counter| {
+        builder.build_atomic_rmw(
+            AtomicRMWBinOp::Add,
+            counter,
+            i32_type.const_int(1, false),
+            AtomicOrdering::Monotonic,
+            SyncScope::System,
+        );
+    });
+
+    // a compare-and-swap retry loop around an atomic load
+    build_loop(
+        &context,
+        &module,
+        "add_cas",
+        |builder, function, counter| {
+            let retry = function.append_basic_block("retry");
+            let done = function.append_basic_block("done");
+            builder.build_br(retry);
+            builder.position_at_end(retry);
+            let old = builder
+                .build_atomic_load(counter, AtomicOrdering::Monotonic, SyncScope::System)
+                .into_int_value()
+                .unwrap();
+            let new = builder.build_add(old, i32_type.const_int(1, false));
+            let pair = builder.build_weak_cmpxchg(
+                counter,
+                old,
+                new,
+                AtomicOrdering::AcquireRelease,
+                AtomicOrdering::Monotonic,
+                SyncScope::System,
+            );
+            let swapped = builder
+                .build_extract_value(pair, 1)
+                .into_int_value()
+                .unwrap();
+            builder.build_cond_br(swapped, done, retry);
+            builder.position_at_end(done);
+        },
+    );
+
+    assert!(module.verify().is_ok());
+    let ir = module.print_module_to_string();
+    assert!(ir.contains("atomicrmw add i32* %0, i32 1 monotonic"));
+    assert!(ir.contains("load atomic i32, i32* %0 monotonic, align 4"));
+    assert!(ir.contains("cmpxchg weak i32* %0, i32 %"));
+    assert!(ir.contains(" acq_rel monotonic, align 4"));
+
+    let engine = module.create_jit_engine().unwrap();
+    let expected = THREADS as i32 * ROUNDS;
+    assert_eq!(hammer(&engine, "add_rmw"), expected);
+    assert_eq!(hammer(&engine, "add_cas"), expected);
+}
+
+#[test]
+fn test_orderings_scopes_and_fences() {
+    let context = Context::new();
+    let builder = context.create_builder();
+    let module = context.create_module("orderings");
+    let i64_type = context.Int64Type();
+    let function = module.add_function(
+        "publish",
+        fn_type!(i64_type, context.PointerType(i64_type), i64_type),
+    );
+    builder.position_at_end(function.append_basic_block("entry"));
+    let slot = function.get_param(0).into_pointer_value().unwrap();
+    let value = function.get_param(1).into_int_value().unwrap();
+
+    builder.build_atomic_store(
+        value,
+        slot,
+        AtomicOrdering::Release,
+        SyncScope::SingleThread,
+    );
+    builder.build_fence(AtomicOrdering::SequentiallyConsistent, SyncScope::System);
+    builder.build_fence(AtomicOrdering::Acquire, SyncScope::SingleThread);
+    let old = builder
+        .build_atomic_rmw(
+            AtomicRMWBinOp::UMax,
+            slot,
+            value,
+            AtomicOrdering::SequentiallyConsistent,
+            SyncScope::System,
+        )
+        .into_int_value()
+        .unwrap();
+    builder.build_cmpxchg(
+        slot,
+        old,
+        value,
+        AtomicOrdering::SequentiallyConsistent,
+        AtomicOrdering::Acquire,
+        SyncScope::SingleThread,
+    );
+    let loaded = builder.build_atomic_load_with_name(
+        slot,
+        AtomicOrdering::Acquire,
+        SyncScope::System,
+        "loaded",
+    );
+    builder.build_ret(loaded);
+    assert!(module.verify().is_ok());
+
+    let ir = module.print_module_to_string();
+    assert!(
+        ir.contains("store atomic i64 %1, i64* %0 syncscope(\"singlethread\") release, align 8")
+    );
+    assert!(ir.contains("fence seq_cst"));
+    assert!(ir.contains("fence syncscope(\"singlethread\") acquire"));
+    assert!(ir.contains("atomicrmw umax i64* %0, i64 %1 seq_cst"));
+    assert!(
+        ir.contains("cmpxchg i64* %0, i64 %2, i64 %1 syncscope(\"singlethread\") seq_cst acquire")
+    );
+    assert!(ir.contains("%loaded = load atomic i64, i64* %0 acquire, align 8"));
+
+    assert_eq!(
+        AtomicOrdering::from(LLVMAtomicOrdering::LLVMAtomicOrderingAcquireRelease),
+        AtomicOrdering::AcquireRelease
+    );
+    assert_eq!(
+        LLVMAtomicRMWBinOp::from(AtomicRMWBinOp::FSub),
+        LLVMAtomicRMWBinOp::LLVMAtomicRMWBinOpFSub
+    );
+}
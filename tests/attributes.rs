@@ -0,0 +1,121 @@
+extern crate llvm_sys_wrapper;
+
+use llvm_sys_wrapper::*;
+
+#[test]
+fn test_runtime_helper_attributes() {
+    let context = Context::new();
+    let builder = context.create_builder();
+    let module = context.create_module("attributes");
+
+    // internal fastcc helper with a noalias buffer param
+    let helper_type = fn_type!(
+        context.Int8Type(),
+        context.Int8PointerType(),
+        context.Int32Type()
+    );
+    let helper = module.add_function("helper", helper_type);
+    helper.set_call_conv(CallConv::Fast);
+    helper.set_linkage(Linkage::Internal);
+    helper.add_attribute(AttributeLoc::Function, Attribute::NoUnwind);
+    helper.add_attribute(AttributeLoc::Function, Attribute::NoInline);
+    helper.add_attribute(AttributeLoc::Return, Attribute::ZExt);
+    helper.add_attribute(AttributeLoc::Param(0), Attribute::NoAlias);
+    helper.add_attribute(AttributeLoc::Param(0), Attribute::Dereferenceable(16));
+    helper.add_attribute(AttributeLoc::Param(0), Attribute::ReadOnly);
+
+    assert_eq!(helper.get_call_conv(), CallConv::Fast);
+    assert_eq!(helper.get_linkage(), Linkage::Internal);
+    assert!(helper.has_attribute(AttributeLoc::Param(0), Attribute::NoAlias));
+    assert!(!helper.has_attribute(AttributeLoc::Param(1), Attribute::NoAlias));
+
+    helper.remove_attribute(AttributeLoc::Function, Attribute::NoInline);
+    assert!(!helper.has_attribute(AttributeLoc::Function, Attribute::NoInline));
+
+    let entry_block = helper.append_basic_block("entry");
+    builder.position_at_end(entry_block);
+    let buffer = helper.get_param(0).into_pointer_value().unwrap();
+    let byte = builder.build_load(buffer);
+    builder.build_ret(byte);
+
+    // the call site picks up the callee's convention and takes its own attributes
+    let main = module.add_function("main", fn_type!(context.Int8Type()));
+    main.set_visibility(Visibility::Hidden);
+    assert_eq!(main.get_visibility(), Visibility::Hidden);
+    let entry_block = main.append_basic_block("entry");
+    builder.position_at_end(entry_block);
+    let buffer = builder.build_alloca(context.ArrayType(context.Int8Type(), 16));
+    let buffer = builder.build_bitcast(buffer, context.Int8PointerType());
+    let result = builder.build_call(helper, &[buffer, context.SInt32(0).into()]);
+    let call = result.into_call_site().unwrap();
+    assert_eq!(call.get_call_conv(), CallConv::Fast);
+    call.add_attribute(AttributeLoc::Function, Attribute::NoUnwind);
+    call.add_attribute(AttributeLoc::Param(0), Attribute::NonNull);
+    assert!(call.has_attribute(AttributeLoc::Param(0), Attribute::NonNull));
+    assert!(!call.is_tail_call());
+    builder.build_ret(result);
+
+    match module.verify() {
+        Ok(_) => {}
+        Err(msg) => panic!("Error: {}", msg),
+    }
+
+    let ir = module.print_module_to_string();
+    assert!(ir.contains(
+        "define internal fastcc zeroext i8 @helper(i8* noalias readonly dereferenceable(16) %0, i32 %1) #0"
+    ));
+    assert!(ir.contains("define hidden i8 @main()"));
+    assert!(ir.contains("call fastcc i8 @helper(i8* nonnull"));
+    assert!(ir.contains("attributes #0 = { nounwind }"));
+}
+
+#[test]
+fn test_type_attributes() {
+    let context = Context::new();
+    let builder = context.create_builder();
+    let module = context.create_module("type_attributes");
+    let pair = context.StructTypeNamed("T");
+    pair.set_body(
+        &[context.Int32Type().into(), context.Int32Type().into()],
+        false,
+    );
+    let pair_ptr = pair.ptr_type(0);
+
+    // fills in the caller's slot from a copy the caller passed by value
+    let copy = module.add_function("copy", fn_type!(context.VoidType(), pair_ptr, pair_ptr));
+    copy.add_attribute(
+        AttributeLoc::Param(0),
+        Attribute::StructRet(pair.as_any_type()),
+    );
+    copy.add_attribute(AttributeLoc::Param(1), Attribute::ByVal(pair.as_any_type()));
+    assert!(copy.has_attribute(
+        AttributeLoc::Param(0),
+        Attribute::StructRet(pair.as_any_type())
+    ));
+    assert!(!copy.has_attribute(
+        AttributeLoc::Param(1),
+        Attribute::StructRet(pair.as_any_type())
+    ));
+    builder.position_at_end(copy.append_basic_block("entry"));
+    let source = copy.get_param(1).into_pointer_value().unwrap();
+    let dest = copy.get_param(0).into_pointer_value().unwrap();
+    builder.build_store(builder.build_load(source), dest);
+    builder.build_ret_void();
+
+    let main = module.add_function("main", fn_type!(context.VoidType()));
+    builder.position_at_end(main.append_basic_block("entry"));
+    let slot = builder.build_alloca_with_name(pair, "slot");
+    let call = builder.build_call(copy, &[slot.into(), slot.into()]);
+    let call = call.into_call_site().unwrap();
+    call.add_attribute(
+        AttributeLoc::Param(0),
+        Attribute::StructRet(pair.as_any_type()),
+    );
+    call.add_attribute(AttributeLoc::Param(1), Attribute::ByVal(pair.as_any_type()));
+    builder.build_ret_void();
+    assert!(module.verify().is_ok());
+
+    let ir = module.print_module_to_string();
+    assert!(ir.contains("define void @copy(%T* sret(%T) %0, %T* byval(%T) %1)"));
+    assert!(ir.contains("call void @copy(%T* sret(%T) %slot, %T* byval(%T) %slot)"));
+}
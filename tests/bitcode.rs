@@ -0,0 +1,87 @@
+extern crate llvm_sys_wrapper;
+
+use llvm_sys_wrapper::*;
+use std::env;
+use std::fs;
+
+fn build_add_module(context: &Context) -> Module<'_> {
+    let builder = context.create_builder();
+    let module = context.create_module("cached");
+    let fun_type = fn_type!(
+        context.Int32Type(),
+        context.Int32Type(),
+        context.Int32Type()
+    );
+    let function = module.add_function("add", fun_type);
+    builder.position_at_end(function.append_basic_block("entry"));
+    let lhs = function.get_param(0).into_int_value().unwrap();
+    let rhs = function.get_param(1).into_int_value().unwrap();
+    let sum = builder.build_add(lhs, rhs);
+    builder.build_ret(sum);
+    module
+}
+
+#[test]
+fn test_bitcode_memory_round_trip() {
+    let context = Context::new();
+    let module = build_add_module(&context);
+    let bitcode = module.write_bitcode_to_memory();
+    // "BC" 0xC0DE
+    assert_eq!(&bitcode[..4], &[0x42, 0x43, 0xC0, 0xDE]);
+
+    let parsed = Module::parse_bitcode(&bitcode, &context).unwrap();
+    // only the module identifier differs, bitcode does not record it
+    let skip_id = |ir: String| ir.lines().skip(1).collect::<Vec<_>>().join("\n");
+    assert_eq!(
+        skip_id(parsed.print_module_to_string()),
+        skip_id(module.print_module_to_string())
+    );
+    let add = parsed.named_function("add").unwrap();
+    assert_eq!(add.params_count(), 2);
+
+    // the parsed module is as good as the one we built
+    LLVM::initialize().unwrap();
+    let engine = parsed.create_interpreter().unwrap();
+    let add = engine.named_function("add").unwrap();
+    let i32_type = context.Int32Type().as_ref();
+    let mut args = unsafe {
+        [
+            execution_engine::LLVMCreateGenericValueOfInt(i32_type, 40, 1),
+            execution_engine::LLVMCreateGenericValueOfInt(i32_type, 2, 1),
+        ]
+    };
+    let result = engine.run_function(add, &mut args);
+    assert_eq!(result.to_int(), 42);
+}
+
+#[test]
+fn test_bitcode_file_round_trip() {
+    let context = Context::new();
+    let module = build_add_module(&context);
+    let path = env::temp_dir().join(format!("llvm_sys_wrapper_{}.bc", std::process::id()));
+    let filename = path.to_str().unwrap();
+
+    module.write_bitcode_to_file(filename).unwrap();
+    let parsed = Module::parse_bitcode_from_file(filename, &context);
+    fs::remove_file(&path).unwrap();
+
+    let parsed = parsed.unwrap();
+    assert!(parsed.named_function("add").is_some());
+    assert!(parsed.verify().is_ok());
+}
+
+#[test]
+fn test_bitcode_errors() {
+    let context = Context::new();
+    assert!(Module::parse_bitcode(b"not bitcode", &context).is_err());
+    assert!(Module::parse_bitcode(&[], &context).is_err());
+
+    let missing = env::temp_dir().join("llvm_sys_wrapper_missing/none.bc");
+    let err = Module::parse_bitcode_from_file(missing.to_str().unwrap(), &context).unwrap_err();
+    assert!(!err.is_empty());
+
+    let module = build_add_module(&context);
+    assert!(module
+        .write_bitcode_to_file(missing.to_str().unwrap())
+        .is_err());
+}
@@ -0,0 +1,110 @@
+extern crate llvm_sys_wrapper;
+
+use llvm_sys_wrapper::*;
+
+fn opcodes(block: BasicBlock) -> Vec<LLVMOpcode> {
+    block
+        .get_instructions()
+        .map(|instruction| instruction.get_opcode())
+        .collect()
+}
+
+#[test]
+fn test_hoist_allocas_into_entry() {
+    let context = Context::new();
+    let builder = context.create_builder();
+    let module = context.create_module("hoist");
+    let i32_type = context.Int32Type();
+    let function = module.add_function("count", fn_type!(i32_type, i32_type));
+    let entry = function.append_basic_block("entry");
+    let body = function.append_basic_block("body");
+    builder.position_at_end(entry);
+    builder.build_br(body);
+    builder.position_at_end(body);
+    assert_eq!(builder.get_insert_block(), Some(body));
+
+    // every local gets its slot up front, wherever it is first needed
+    let param = function.get_param(0).into_int_value().unwrap();
+    let mut slots = Vec::new();
+    for name in &["a", "b"] {
+        let slot = {
+            let guard = builder.save_insert_point();
+            guard.position_at_start(entry);
+            assert_eq!(guard.get_insert_block(), Some(entry));
+            guard.build_alloca_with_name(i32_type, name)
+        };
+        assert_eq!(builder.get_insert_block(), Some(body));
+        builder.build_store(param, slot);
+        slots.push(slot);
+    }
+    let a = builder.build_load(slots[0]).into_int_value().unwrap();
+    let b = builder.build_load(slots[1]).into_int_value().unwrap();
+    builder.build_ret(builder.build_add(a, b));
+
+    assert_eq!(
+        opcodes(entry),
+        vec![
+            LLVMOpcode::LLVMAlloca,
+            LLVMOpcode::LLVMAlloca,
+            LLVMOpcode::LLVMBr
+        ]
+    );
+    assert_eq!(
+        body.get_first_instruction().unwrap().get_opcode(),
+        LLVMOpcode::LLVMStore
+    );
+    assert!(module.verify().is_ok());
+}
+
+#[test]
+fn test_position_before_and_clear() {
+    let context = Context::new();
+    let builder = context.create_builder();
+    let module = context.create_module("before");
+    let i32_type = context.Int32Type();
+    let function = module.add_function("twice", fn_type!(i32_type, i32_type));
+    let entry = function.append_basic_block("entry");
+    builder.position_at_end(entry);
+    let param = function.get_param(0).into_int_value().unwrap();
+    let ret = builder.build_ret(param).into_instruction().unwrap();
+
+    // the instructions land in order, ahead of the return
+    builder.position_before(ret);
+    let sum = builder.build_add(param, param);
+    let product = builder.build_mul(sum, param);
+    ret.set_operand(0, product);
+    assert_eq!(
+        opcodes(entry),
+        vec![
+            LLVMOpcode::LLVMAdd,
+            LLVMOpcode::LLVMMul,
+            LLVMOpcode::LLVMRet
+        ]
+    );
+
+    {
+        let guard = builder.save_insert_point();
+        guard.clear_insertion_position();
+        assert!(guard.get_insert_block().is_none());
+    }
+    assert_eq!(builder.get_insert_block(), Some(entry));
+    builder.build_sub(product, param);
+    assert_eq!(
+        entry.get_last_instruction().unwrap().get_opcode(),
+        LLVMOpcode::LLVMRet
+    );
+    assert_eq!(entry.count_instructions(), 4);
+
+    // nested guards unwind in turn
+    builder.clear_insertion_position();
+    {
+        let outer = builder.save_insert_point();
+        outer.position_at_end(entry);
+        {
+            let inner = outer.save_insert_point();
+            inner.position_at_start(entry);
+        }
+        assert_eq!(builder.get_insert_block(), Some(entry));
+    }
+    assert!(builder.get_insert_block().is_none());
+}
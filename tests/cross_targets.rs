@@ -0,0 +1,75 @@
+extern crate llvm_sys_wrapper;
+
+use llvm_sys_wrapper::*;
+
+fn emit_assembly(triple: &str, cpu: &str, features: &str) -> String {
+    let context = Context::new();
+    let builder = context.create_builder();
+    let module = context.create_module("cross");
+    let fun_type = fn_type!(context.Int32Type(), context.Int32Type());
+    let function = module.add_function("increment", fun_type);
+    builder.position_at_end(function.append_basic_block("entry"));
+    let param = function.get_param(0).into_int_value().unwrap();
+    let sum = builder.build_add(param, context.SInt32(1));
+    builder.build_ret(sum);
+
+    let target_machine = Target::from_triple(triple)
+        .unwrap()
+        .create_target_machine(triple)
+        .cpu(cpu)
+        .features(features)
+        .build()
+        .unwrap();
+    target_machine.configure_module(&module);
+    let assembly = target_machine
+        .emit_to_memory(&module, FileType::Assembly)
+        .unwrap();
+    String::from_utf8(assembly).unwrap()
+}
+
+#[test]
+fn test_cross_compile() {
+    LLVM::initialize_target(Arch::AArch64).unwrap();
+    LLVM::initialize_target(Arch::RISCV).unwrap();
+    LLVM::initialize_target(Arch::WebAssembly).unwrap();
+
+    let aarch64 = emit_assembly("aarch64-unknown-linux-gnu", "cortex-a53", "+neon");
+    assert!(aarch64.contains("add\tw0, w0, #1"));
+
+    let riscv = emit_assembly("riscv64-unknown-linux-gnu", "generic-rv64", "+m");
+    assert!(riscv.contains("addiw\ta0, a0, 1"));
+
+    let wasm = emit_assembly("wasm32-unknown-unknown", "generic", "");
+    assert!(wasm.contains("i32.add"));
+}
+
+#[test]
+fn test_initialize_all_targets() {
+    LLVM::initialize_all_targets();
+    let names: Vec<String> = Target::all().iter().map(|t| t.get_name()).collect();
+    for name in ["aarch64", "arm", "riscv64", "wasm32", "x86-64"].iter() {
+        assert!(names.iter().any(|n| n == name), "{} is missing", name);
+    }
+    assert!(Target::from_name("sparcv9").unwrap().has_target_machine());
+}
+
+#[test]
+fn test_host_cpu() {
+    LLVM::initialize().unwrap();
+    let cpu = LLVM::host_cpu_name();
+    let features = LLVM::host_cpu_features();
+    assert!(!cpu.is_empty());
+    assert!(features
+        .split(',')
+        .all(|f| f.starts_with('+') || f.starts_with('-')));
+
+    let triple = Target::default_triple();
+    let target_machine = Target::from_triple(&triple)
+        .unwrap()
+        .create_target_machine(&triple)
+        .cpu(&cpu)
+        .features(&features)
+        .build()
+        .unwrap();
+    assert_eq!(target_machine.get_cpu(), cpu);
+}
@@ -0,0 +1,243 @@
+extern crate llvm_sys;
+extern crate llvm_sys_wrapper;
+
+use llvm_sys::execution_engine::LLVMAddGlobalMapping;
+use llvm_sys_wrapper::*;
+use std::os::raw::c_void;
+use std::ptr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// Builds, against the C++ runtime the process already links:
+//
+//   void thrower(int v) { throw v; }
+//   void middle(int v) { counter++ on the way out; thrower(v); }
+//   int catcher() { try { middle(41); } catch (int v) { return v + counter; } return 0; }
+//
+// Without `define_thrower`, thrower is only declared, for the host to supply.
+fn build_module<'ctx>(context: &'ctx Context, define_thrower: bool) -> Module<'ctx> {
+    let builder = context.create_builder();
+    let module = context.create_module("exceptions");
+    let void_type = context.VoidType();
+    let i32_type = context.Int32Type();
+    let i64_type = context.Int64Type();
+    let i8_ptr = context.CharPointerType();
+    let landing_type = context.StructType(&[i8_ptr.into(), i32_type.into()], false);
+
+    let personality = module.add_function("__gxx_personality_v0", fn_type!(i32_type,,,));
+    let allocate = module.add_function("__cxa_allocate_exception", fn_type!(i8_ptr, i64_type));
+    let throw = module.add_function("__cxa_throw", fn_type!(void_type, i8_ptr, i8_ptr, i8_ptr));
+    let begin_catch = module.add_function("__cxa_begin_catch", fn_type!(i8_ptr, i8_ptr));
+    let end_catch = module.add_function("__cxa_end_catch", fn_type!(void_type));
+    let typeid_for = module.add_function("llvm.eh.typeid.for", fn_type!(i32_type, i8_ptr));
+    let int_type_info = module.add_global(i8_ptr, "_ZTIi");
+    let counter = module.add_global(i32_type, "counter");
+    counter.set_initializer(i32_type.const_int(0, false));
+
+    let thrower = module.add_function("thrower", fn_type!(void_type, i32_type));
+    let type_info = builder.build_bitcast(int_type_info, i8_ptr);
+    if define_thrower {
+        builder.position_at_end(thrower.append_basic_block("entry"));
+        let exception = builder.build_call(allocate, &[i64_type.const_int(4, false).into()]);
+        let slot = builder.build_bitcast(exception, context.PointerType(i32_type));
+        builder.build_store(thrower.get_param(0), slot.into_pointer_value().unwrap());
+        builder.build_call(throw, &[exception, type_info, i8_ptr.const_null().into()]);
+        builder.build_unreachable();
+    }
+
+    let middle = module.add_function("middle", fn_type!(void_type, i32_type));
+    middle.set_personality_function(personality);
+    let entry = middle.append_basic_block("entry");
+    let done = middle.append_basic_block("done");
+    let cleanup = middle.append_basic_block("cleanup");
+    builder.position_at_end(entry);
+    builder.build_invoke(thrower, &[middle.get_param(0)], done, cleanup);
+    builder.position_at_end(done);
+    builder.build_ret_void();
+    builder.position_at_end(cleanup);
+    let landing_pad = builder.build_landing_pad(landing_type, personality, &[], true);
+    let count = builder
+        .build_load(counter.as_pointer_value())
+        .into_int_value()
+        .unwrap();
+    let count = builder.build_add(count, i32_type.const_int(1, false));
+    builder.build_store(count, counter.as_pointer_value());
+    builder.build_resume(landing_pad);
+
+    let catcher = module.add_function("catcher", fn_type!(i32_type));
+    catcher.set_personality_function(personality);
+    let entry = catcher.append_basic_block("entry");
+    let done = catcher.append_basic_block("done");
+    let landing = catcher.append_basic_block("landing");
+    let caught = catcher.append_basic_block("caught");
+    let rethrow = catcher.append_basic_block("rethrow");
+    builder.position_at_end(entry);
+    builder.build_invoke(
+        middle,
+        &[i32_type.const_int(41, false).into()],
+        done,
+        landing,
+    );
+    builder.position_at_end(done);
+    builder.build_ret(i32_type.const_int(0, false));
+    builder.position_at_end(landing);
+    let landing_pad = builder.build_landing_pad(landing_type, personality, &[type_info], false);
+    let exception = builder.build_extract_value(landing_pad, 0);
+    let selector = builder
+        .build_extract_value(landing_pad, 1)
+        .into_int_value()
+        .unwrap();
+    let int_selector = builder
+        .build_call(typeid_for, &[type_info])
+        .into_int_value()
+        .unwrap();
+    let is_int = builder.build_icmp_eq(selector, int_selector);
+    builder.build_cond_br(is_int, caught, rethrow);
+    builder.position_at_end(caught);
+    let thrown = builder.build_call(begin_catch, &[exception]);
+    let thrown = builder.build_bitcast(thrown, context.PointerType(i32_type));
+    let value = builder
+        .build_load(thrown.into_pointer_value().unwrap())
+        .into_int_value()
+        .unwrap();
+    builder.build_call(end_catch, &[]);
+    let count = builder
+        .build_load(counter.as_pointer_value())
+        .into_int_value()
+        .unwrap();
+    builder.build_ret(builder.build_add(value, count));
+    builder.position_at_end(rethrow);
+    builder.build_resume(landing_pad);
+
+    module
+}
+
+#[test]
+fn test_throw_and_catch_in_jit() {
+    LLVM::initialize().unwrap();
+    let context = Context::new();
+    let module = build_module(&context, true);
+    assert!(module.verify().is_ok());
+    let catcher = module.named_function("catcher").unwrap();
+    assert_eq!(
+        catcher
+            .get_personality_function()
+            .map(|function| function.get_name()),
+        Some("__gxx_personality_v0".to_string())
+    );
+    assert!(module
+        .named_function("thrower")
+        .unwrap()
+        .get_personality_function()
+        .is_none());
+
+    let engine = module.create_jit_engine().unwrap();
+    let catcher = engine.named_function("catcher").unwrap();
+    // 41 thrown, plus one for the cleanup that ran while unwinding through middle
+    assert_eq!(engine.run_function(catcher, &mut []).to_int(), 42);
+}
+
+extern "C-unwind" {
+    fn __cxa_allocate_exception(size: usize) -> *mut c_void;
+    fn __cxa_throw(
+        exception: *mut c_void,
+        type_info: *const c_void,
+        destructor: *const c_void,
+    ) -> !;
+    static _ZTIi: u8;
+}
+
+static HOST_DROPS: AtomicUsize = AtomicUsize::new(0);
+
+struct HostGuard;
+
+impl Drop for HostGuard {
+    fn drop(&mut self) {
+        HOST_DROPS.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+// `throw value;` from a Rust frame, which has to be unwound on the way out too.
+extern "C-unwind" fn host_thrower(value: i32) {
+    let _guard = HostGuard;
+    unsafe {
+        let exception = __cxa_allocate_exception(4) as *mut i32;
+        *exception = value;
+        __cxa_throw(
+            exception as *mut c_void,
+            &_ZTIi as *const u8 as *const c_void,
+            ptr::null(),
+        )
+    }
+}
+
+#[test]
+fn test_catch_host_exception_in_jit() {
+    LLVM::initialize().unwrap();
+    let context = Context::new();
+    let module = build_module(&context, false);
+    assert!(module.verify().is_ok());
+
+    // the engine only looks up definitions, so take the declaration beforehand
+    let thrower = module.named_function("thrower").unwrap();
+    assert!(thrower.get_entry_block().is_none());
+    let engine = module.create_jit_engine().unwrap();
+    unsafe {
+        LLVMAddGlobalMapping(
+            engine.as_ref(),
+            thrower.as_ref(),
+            host_thrower as extern "C-unwind" fn(i32) as *mut c_void,
+        );
+    }
+    let catcher = engine.named_function("catcher").unwrap();
+    // the exception left the host frame, ran middle's cleanup and was caught in catcher
+    assert_eq!(engine.run_function(catcher, &mut []).to_int(), 42);
+    assert_eq!(HOST_DROPS.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn test_funclet_pads() {
+    let context = Context::new();
+    let builder = context.create_builder();
+    let module = context.create_module("funclets");
+    let void_type = context.VoidType();
+    let i8_ptr = context.CharPointerType();
+    let personality = module.add_function("__CxxFrameHandler3", fn_type!(context.Int32Type(),,,));
+    let may_throw = module.add_function("may_throw", fn_type!(void_type));
+
+    let function = module.add_function("guarded", fn_type!(void_type));
+    function.set_personality_function(personality);
+    let entry = function.append_basic_block("entry");
+    let done = function.append_basic_block("done");
+    let dispatch = function.append_basic_block("dispatch");
+    let handler = function.append_basic_block("handler");
+    let exit = function.append_basic_block("exit");
+    let cleanup = function.append_basic_block("cleanup");
+
+    builder.position_at_end(entry);
+    builder.build_invoke(may_throw, &[], done, dispatch);
+    builder.position_at_end(done);
+    builder.build_invoke(may_throw, &[], exit, cleanup);
+    builder.position_at_end(exit);
+    builder.build_ret_void();
+
+    builder.position_at_end(dispatch);
+    let catch_switch = builder.build_catch_switch(None, None, &[handler]);
+    builder.position_at_end(handler);
+    let null = i8_ptr.const_null().into();
+    let catch_pad = builder.build_catch_pad(
+        catch_switch,
+        &[null, context.Int32Type().const_int(64, false).into(), null],
+    );
+    builder.build_catch_ret(catch_pad, done);
+
+    builder.position_at_end(cleanup);
+    let cleanup_pad = builder.build_cleanup_pad(None, &[]);
+    builder.build_cleanup_ret(cleanup_pad, None);
+
+    assert!(module.verify().is_ok());
+    let ir = module.print_module_to_string();
+    assert!(ir.contains("catchswitch within none [label %handler] unwind to caller"));
+    assert!(ir.contains("catchret from %"));
+    assert!(ir.contains("cleanuppad within none []"));
+    assert!(ir.contains("cleanupret from %"));
+}
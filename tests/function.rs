@@ -0,0 +1,155 @@
+extern crate llvm_sys_wrapper;
+
+use llvm_sys_wrapper::*;
+
+#[test]
+fn test_looked_up_function_introspection() {
+    let context = Context::new();
+    let builder = context.create_builder();
+    let module = context.create_module("introspection");
+
+    let fun_type = fn_type!(
+        context.DoubleType(),
+        context.Int32Type(),
+        context.Int64Type(),
+        context.DoubleType()
+    );
+    let created = module.add_function("pick", fun_type);
+    assert!(created.get_entry_block().is_none());
+
+    let entry_block = created.append_basic_block("entry");
+    let other_block = created.append_basic_block("other");
+    builder.position_at_end(entry_block);
+    builder.build_br(other_block);
+    builder.position_at_end(other_block);
+    builder.build_ret(created.get_param(2));
+
+    // a function found by name knows as much as the one we created
+    let function = module.named_function("pick").unwrap();
+    assert_eq!(function, created);
+    assert_eq!(function.get_name(), "pick");
+    assert_eq!(function.get_parent(), module.as_ref());
+    assert_eq!(function.get_function_type(), fun_type);
+    assert!(function.get_return_type().is_float_type());
+
+    let param_types = function.get_param_types();
+    assert_eq!(param_types.len(), 3);
+    assert_eq!(param_types[0].into_int_type().unwrap().get_bit_width(), 32);
+    assert_eq!(param_types[1].into_int_type().unwrap().get_bit_width(), 64);
+    assert!(param_types[2].is_float_type());
+
+    let params = function.get_params();
+    assert_eq!(params.len(), 3);
+    assert_eq!(params[2], function.get_param(2));
+
+    assert_eq!(function.count_basic_blocks(), 2);
+    assert_eq!(function.get_basic_blocks(), vec![entry_block, other_block]);
+    assert_eq!(function.get_entry_block(), Some(entry_block));
+    assert_eq!(function.count_instructions(), 2);
+
+    match module.verify() {
+        Ok(_) => {}
+        Err(msg) => panic!("Error: {}", msg),
+    }
+}
+
+#[test]
+fn test_delete_function() {
+    let context = Context::new();
+    let module = context.create_module("delete");
+
+    let fun_type = fn_type!(context.VoidType());
+    module.add_function("keep", fun_type);
+    let doomed = module.add_function("doomed", fun_type);
+
+    let existing = module.get_or_add_function("doomed", fn_type!(context.Int8Type()));
+    assert_eq!(existing, doomed);
+    assert!(existing.get_return_type().is_void_type());
+
+    unsafe { doomed.delete() };
+    let ir = module.print_module_to_string();
+    assert!(ir.contains("@keep"));
+    assert!(!ir.contains("@doomed"));
+}
+
+#[test]
+fn test_indirect_calls() {
+    LLVM::initialize().unwrap();
+    let context = Context::new();
+    let builder = context.create_builder();
+    let module = context.create_module("indirect");
+    let i32_type = context.Int32Type();
+    let callback_type = fn_type!(i32_type, i32_type);
+
+    let double_it = module.add_function("double_it", callback_type);
+    builder.position_at_end(double_it.append_basic_block("entry"));
+    let x = double_it.get_param(0).into_int_value().unwrap();
+    builder.build_ret(builder.build_add(x, x));
+
+    // a one-slot table holding the callback, as a vtable would
+    let slot = module.add_global(context.PointerType(callback_type), "slot");
+    slot.set_initializer(double_it);
+
+    let main = module.add_function("main", fn_type!(i32_type));
+    builder.position_at_end(main.append_basic_block("entry"));
+    let callee = builder
+        .build_load_with_name(slot.as_pointer_value(), "callee")
+        .into_pointer_value()
+        .unwrap();
+    let first = builder.build_indirect_call_with_name(
+        callback_type,
+        callee,
+        &[context.SInt32(10).into()],
+        "first",
+    );
+    let second = builder.build_indirect_tail_call(callback_type, callee, &[first]);
+    builder.build_ret(second);
+    assert!(module.verify().is_ok());
+
+    let ir = module.print_module_to_string();
+    assert!(ir.contains("%first = call i32 %callee(i32 10)"));
+    assert!(ir.contains("tail call i32 %callee(i32 %first)"));
+
+    let engine = module.create_jit_engine().unwrap();
+    let main = engine.named_function("main").unwrap();
+    assert_eq!(engine.run_function(main, &mut []).to_int(), 40);
+}
+
+#[test]
+fn test_indirect_invoke() {
+    let context = Context::new();
+    let builder = context.create_builder();
+    let module = context.create_module("indirect_invoke");
+    let void_type = context.VoidType();
+    let i8_ptr = context.CharPointerType();
+    let callback_type = fn_type!(void_type, i8_ptr);
+    let personality = module.add_function("__gxx_personality_v0", fn_type!(context.Int32Type(),,,));
+
+    let function = module.add_function(
+        "run",
+        fn_type!(void_type, context.PointerType(callback_type), i8_ptr),
+    );
+    function.set_personality_function(personality);
+    let entry = function.append_basic_block("entry");
+    let done = function.append_basic_block("done");
+    let cleanup = function.append_basic_block("cleanup");
+    builder.position_at_end(entry);
+    let callee = function.get_param(0).into_pointer_value().unwrap();
+    builder.build_indirect_invoke(
+        callback_type,
+        callee,
+        &[function.get_param(1)],
+        done,
+        cleanup,
+    );
+    builder.position_at_end(done);
+    builder.build_ret_void();
+    builder.position_at_end(cleanup);
+    let landing_type = context.StructType(&[i8_ptr.into(), context.Int32Type().into()], false);
+    let landing_pad = builder.build_landing_pad(landing_type, personality, &[], true);
+    builder.build_resume(landing_pad);
+    assert!(module.verify().is_ok());
+
+    let ir = module.print_module_to_string();
+    assert!(ir.contains("invoke void %0(i8* %1)\n          to label %done unwind label %cleanup"));
+}
@@ -0,0 +1,117 @@
+extern crate llvm_sys_wrapper;
+
+use llvm_sys_wrapper::*;
+
+#[test]
+fn test_global_properties() {
+    let context = Context::new();
+    let module = context.create_module("globals");
+
+    // a static lookup table
+    let i32_type = context.Int32Type();
+    let table = module.add_global(context.ArrayType(i32_type, 3), "table");
+    table.set_initializer(i32_type.const_array(&[
+        i32_type.const_int(1, false),
+        i32_type.const_int(2, false),
+        i32_type.const_int(3, false),
+    ]));
+    table.set_constant(true);
+    table.set_linkage(Linkage::Private);
+    table.set_unnamed_addr(UnnamedAddr::Global);
+    table.set_alignment(16);
+    table.set_section(Some(".rodata.tables"));
+
+    assert!(table.is_constant());
+    assert_eq!(table.get_linkage(), Linkage::Private);
+    assert_eq!(table.get_unnamed_addr(), UnnamedAddr::Global);
+    assert_eq!(table.get_alignment(), 16);
+    assert_eq!(table.get_section(), Some(".rodata.tables".to_string()));
+    assert!(table.get_initializer().unwrap().is_array_value());
+
+    // a thread local counter
+    let counter = module.add_global(context.Int64Type(), "counter");
+    counter.set_initializer(context.SInt64(0));
+    counter.set_thread_local_mode(Some(ThreadLocalMode::InitialExec));
+    assert!(counter.is_thread_local());
+    assert_eq!(
+        counter.get_thread_local_mode(),
+        Some(ThreadLocalMode::InitialExec)
+    );
+    assert_eq!(counter.get_section(), None);
+
+    // an external declaration living in another address space
+    let device = module.add_global_in_address_space(context.Int8Type(), "device", 1);
+    device.set_externally_initialized(true);
+    assert!(device.is_externally_initialized());
+    assert_eq!(device.get_address_space(), 1);
+    assert!(device.get_initializer().is_none());
+
+    let comdat = module.get_or_insert_comdat("shared");
+    comdat.set_selection_kind(ComdatSelectionKind::Largest);
+    let shared = module.add_global(i32_type, "shared");
+    shared.set_initializer(i32_type.const_zero());
+    shared.set_linkage(Linkage::LinkOnceODR);
+    shared.set_visibility(Visibility::Hidden);
+    shared.set_comdat(comdat);
+    assert_eq!(shared.get_comdat(), Some(comdat));
+    assert_eq!(
+        shared.get_comdat().unwrap().get_selection_kind(),
+        ComdatSelectionKind::Largest
+    );
+    assert!(table.get_comdat().is_none());
+
+    match module.verify() {
+        Ok(_) => {}
+        Err(msg) => panic!("Error: {}", msg),
+    }
+
+    let ir = module.print_module_to_string();
+    assert!(ir.contains("$shared = comdat largest"));
+    assert!(ir.contains(
+        "@table = private unnamed_addr constant [3 x i32] [i32 1, i32 2, i32 3], section \".rodata.tables\", align 16"
+    ));
+    assert!(ir.contains("@counter = thread_local(initialexec) global i64 0"));
+    assert!(ir.contains("@device = external addrspace(1) externally_initialized global i8"));
+    assert!(ir.contains("@shared = linkonce_odr hidden global i32 0, comdat"));
+}
+
+#[test]
+fn test_global_lookup() {
+    let context = Context::new();
+    let builder = context.create_builder();
+    let module = context.create_module("lookup");
+
+    let names = ["first", "second", "third"];
+    for name in names.iter() {
+        let global = module.add_global(context.Int32Type(), name);
+        global.set_initializer(context.SInt32(0));
+    }
+
+    let found: Vec<String> = module
+        .get_globals()
+        .map(|global| global.get_name())
+        .collect();
+    assert_eq!(found, vec!["first", "second", "third"]);
+
+    let second = module.named_global("second").unwrap();
+    assert!(module.named_global("fourth").is_none());
+
+    // globals are pointers when used as values
+    let function = module.add_function("bump", fn_type!(context.VoidType()));
+    builder.position_at_end(function.append_basic_block("entry"));
+    let value = builder
+        .build_load(second.as_pointer_value())
+        .into_int_value()
+        .unwrap();
+    let value = builder.build_add(value, context.SInt32(1));
+    builder.build_store(value, second.into());
+    builder.build_ret_void();
+
+    unsafe { module.named_global("third").unwrap().delete() };
+    assert_eq!(module.get_globals().count(), 2);
+
+    match module.verify() {
+        Ok(_) => {}
+        Err(msg) => panic!("Error: {}", msg),
+    }
+}
@@ -5,7 +5,7 @@ use llvm_sys_wrapper::*;
 #[test]
 fn test_puts() {
     // 参考: [llvm で Hello wolrd!! 〜llvm入門 その2〜](http://blog.64p.org/entry/2012/07/18/172418)
-    LLVM::initialize();
+    LLVM::initialize().unwrap();
 
     // create context
     let context = Context::global_context();
@@ -25,8 +25,8 @@ fn test_puts() {
     let puts_type = fn_type!(context.Int32Type(), context.CharPointerType());
     let puts_func = module.add_function("puts", puts_type);
 
-    let mut args = [helloworld];
-    let _call = builder.build_call(puts_func.as_ref(), &mut args);
+    let args = [helloworld.into()];
+    let _call = builder.build_call(puts_func, &args);
 
     let _ret = builder.build_ret_void();
 
@@ -0,0 +1,300 @@
+extern crate llvm_sys_wrapper;
+
+use llvm_sys_wrapper::*;
+
+const IR: &str = r#"
+define i32 @pick(i32 %a, i32 %b) {
+entry:
+  %zero = add i32 %a, 0
+  %sum = add i32 %zero, %b
+  %cond = icmp sgt i32 %sum, 10
+  br i1 %cond, label %big, label %done
+
+big:
+  %twice = mul i32 %sum, 2
+  br label %done
+
+done:
+  %result = phi i32 [ %sum, %entry ], [ %twice, %big ]
+  ret i32 %result
+}
+"#;
+
+fn block_names(function: Function) -> Vec<String> {
+    function
+        .get_basic_blocks()
+        .iter()
+        .map(|block| block.get_name())
+        .collect()
+}
+
+#[test]
+fn test_walk_blocks_and_operands() {
+    let context = Context::new();
+    let module = context.parse_ir(IR).unwrap();
+    let function = module.named_function("pick").unwrap();
+    assert_eq!(block_names(function), vec!["entry", "big", "done"]);
+
+    let entry = function.get_entry_block().unwrap();
+    assert_eq!(entry.get_parent(), Some(function));
+    assert_eq!(entry.count_instructions(), 4);
+    let first = entry.get_first_instruction().unwrap();
+    assert_eq!(first.get_opcode(), LLVMOpcode::LLVMAdd);
+    assert_eq!(first.get_parent(), Some(entry));
+    assert!(first.get_previous_instruction().is_none());
+
+    let terminator = entry.get_terminator().unwrap();
+    assert!(terminator.is_terminator());
+    assert_eq!(entry.get_last_instruction(), Some(terminator));
+    assert_eq!(terminator.get_opcode(), LLVMOpcode::LLVMBr);
+    // a conditional branch stores its condition, then the false and true targets
+    let targets: Vec<String> = terminator
+        .get_operands()
+        .into_iter()
+        .filter_map(BasicBlock::from_value)
+        .map(|block| block.get_name())
+        .collect();
+    assert_eq!(targets, vec!["done", "big"]);
+    assert!(terminator.get_operand(3).is_none());
+
+    let sum = first.get_next_instruction().unwrap();
+    assert_eq!(sum.get_users().len(), 3);
+    let opcodes: Vec<LLVMOpcode> = entry
+        .get_instructions()
+        .map(|instruction| instruction.get_opcode())
+        .collect();
+    assert_eq!(
+        opcodes,
+        vec![
+            LLVMOpcode::LLVMAdd,
+            LLVMOpcode::LLVMAdd,
+            LLVMOpcode::LLVMICmp,
+            LLVMOpcode::LLVMBr,
+        ]
+    );
+}
+
+#[test]
+fn test_peephole_rewrite() {
+    let context = Context::new();
+    let module = context.parse_ir(IR).unwrap();
+    let function = module.named_function("pick").unwrap();
+
+    // fold every `add x, 0` into x
+    let mut folded = 0;
+    for block in function.get_basic_blocks() {
+        for instruction in block.get_instructions() {
+            if instruction.get_opcode() != LLVMOpcode::LLVMAdd {
+                continue;
+            }
+            let zero = instruction
+                .get_operand(1)
+                .and_then(|operand| operand.into_int_value())
+                .and_then(|operand| operand.get_zext_const());
+            if zero == Some(0) {
+                instruction.replace_all_uses_with(instruction.get_operand(0).unwrap());
+                assert!(!instruction.has_uses());
+                unsafe { instruction.erase_from_parent() };
+                folded += 1;
+            }
+        }
+    }
+    assert_eq!(folded, 1);
+    assert!(module.verify().is_ok());
+
+    let sum = function
+        .get_entry_block()
+        .unwrap()
+        .get_first_instruction()
+        .unwrap();
+    assert_eq!(sum.get_name(), "sum");
+    assert_eq!(sum.get_operand(0), Some(function.get_param(0)));
+    assert_eq!(function.count_instructions(), 7);
+
+    // operands can be swapped in place too
+    let b = function.get_param(1);
+    assert!(sum.set_operand(0, b));
+    assert!(!sum.set_operand(2, b));
+    assert_eq!(sum.get_operands(), vec![b, b]);
+    assert!(module.verify().is_ok());
+}
+
+#[test]
+fn test_split_block() {
+    let context = Context::new();
+    let module = context.parse_ir(IR).unwrap();
+    let function = module.named_function("pick").unwrap();
+    let entry = function.get_entry_block().unwrap();
+    let cond = entry.get_instructions().nth(2).unwrap();
+    assert_eq!(cond.get_name(), "cond");
+    let old_phi = function
+        .get_last_basic_block()
+        .unwrap()
+        .get_first_instruction()
+        .unwrap()
+        .as_ref();
+
+    let (tail, replaced) = entry.split_at(cond, "tail").unwrap();
+    assert_eq!(block_names(function), vec!["entry", "tail", "big", "done"]);
+    assert_eq!(entry.count_instructions(), 3);
+    assert_eq!(tail.count_instructions(), 2);
+    assert_eq!(tail.get_first_instruction().unwrap().get_name(), "cond");
+    assert_eq!(
+        entry.get_terminator().unwrap().get_operand(0),
+        Some(tail.as_value())
+    );
+
+    // the phi in `done` now comes from the new block instead of entry
+    let done = function.get_last_basic_block().unwrap();
+    let phi = done.get_first_instruction().unwrap();
+    assert_eq!(phi.get_opcode(), LLVMOpcode::LLVMPHI);
+    assert_eq!(phi.get_name(), "result");
+    assert_eq!(phi.get_users().len(), 1);
+    assert_eq!(replaced, vec![(old_phi, phi)]);
+    assert!(module.verify().is_ok());
+
+    let other = function.get_basic_blocks()[2];
+    assert!(entry.split_at(cond, "nope").is_none());
+    assert!(other.split_at(cond, "nope").is_none());
+}
+
+#[test]
+fn test_move_and_delete_blocks() {
+    let context = Context::new();
+    let builder = context.create_builder();
+    let module = context.parse_ir(IR).unwrap();
+    let function = module.named_function("pick").unwrap();
+    let blocks = function.get_basic_blocks();
+    let (entry, big, done) = (blocks[0], blocks[1], blocks[2]);
+
+    done.move_before(big);
+    assert_eq!(block_names(function), vec!["entry", "done", "big"]);
+    big.move_after(entry);
+    assert_eq!(block_names(function), vec!["entry", "big", "done"]);
+    assert_eq!(big.get_previous_basic_block(), Some(entry));
+    assert_eq!(big.get_next_basic_block(), Some(done));
+    assert!(done.get_next_basic_block().is_none());
+    assert!(module.verify().is_ok());
+
+    // send entry straight to done, then drop the block nothing reaches
+    unsafe { entry.get_terminator().unwrap().erase_from_parent() };
+    assert!(entry.get_terminator().is_none());
+    builder.position_at_end(entry);
+    builder.build_br(done);
+    let phi = done.get_first_instruction().unwrap();
+    let sum = phi.get_operand(0).unwrap();
+    phi.replace_all_uses_with(sum);
+    unsafe {
+        phi.erase_from_parent();
+        big.delete();
+    }
+    done.set_name("exit");
+    assert_eq!(block_names(function), vec!["entry", "exit"]);
+    assert!(module.verify().is_ok());
+}
+
+const SWITCH_IR: &str = r#"
+define float @scale(i32 %n, float %x) {
+entry:
+  %half = fmul float %x, 5.000000e-01
+  %twice = fmul float %x, 2.000000e+00
+  switch i32 %n, label %done [
+    i32 0, label %done
+    i32 1, label %done
+    i32 2, label %other
+  ]
+
+other:
+  br label %done
+
+done:
+  %result = phi fast float [ %half, %entry ], [ %half, %entry ], [ %half, %entry ], [ %x, %other ]
+  %keep = phi float [ %twice, %entry ], [ %twice, %entry ], [ %twice, %entry ], [ %x, %other ]
+  %sum = fadd float %result, %keep
+  ret float %sum
+}
+"#;
+
+#[test]
+fn test_split_block_with_repeated_successor() {
+    let context = Context::new();
+    let module = context.parse_ir(SWITCH_IR).unwrap();
+    let function = module.named_function("scale").unwrap();
+    let entry = function.get_entry_block().unwrap();
+    let twice = entry.get_instructions().nth(1).unwrap();
+
+    let done = function.get_last_basic_block().unwrap();
+    let old_phis: Vec<_> = done
+        .get_instructions()
+        .take(2)
+        .map(|phi| phi.as_ref())
+        .collect();
+
+    // each phi is rebuilt once, though the switch reaches `done` three times
+    let (tail, replaced) = entry.split_at(twice, "tail").unwrap();
+    assert!(module.verify().is_ok());
+    let phis: Vec<Instruction> = done.get_instructions().take(2).collect();
+    assert_eq!(
+        replaced,
+        vec![(old_phis[0], phis[0]), (old_phis[1], phis[1])]
+    );
+    for phi in &phis {
+        assert_eq!(phi.get_opcode(), LLVMOpcode::LLVMPHI);
+    }
+    assert_eq!(phis[0].get_name(), "result");
+    assert_eq!(phis[0].get_fast_math_flags(), FastMathFlags::FAST);
+    assert_eq!(phis[1].get_name(), "keep");
+    assert!(phis[1].get_fast_math_flags().is_empty());
+
+    let ir = module.print_module_to_string();
+    assert!(ir.contains(
+        "%result = phi fast float [ %half, %tail ], [ %half, %tail ], [ %half, %tail ], [ %x, %other ]"
+    ));
+    assert!(ir.contains(
+        "%keep = phi float [ %twice, %tail ], [ %twice, %tail ], [ %twice, %tail ], [ %x, %other ]"
+    ));
+    assert_eq!(done.count_instructions(), 4);
+    assert_eq!(tail.count_instructions(), 2);
+}
+
+#[test]
+fn test_split_block_keeps_phis_and_pads_at_the_top() {
+    let context = Context::new();
+    let module = context.parse_ir(IR).unwrap();
+    let function = module.named_function("pick").unwrap();
+    let done = function.get_last_basic_block().unwrap();
+    let phi = done.get_first_instruction().unwrap();
+    assert!(done.split_at(phi, "nope").is_none());
+
+    let module = context
+        .parse_ir(
+            r#"
+declare void @may_throw()
+declare i32 @__gxx_personality_v0(...)
+
+define void @guarded() personality i32 (...)* @__gxx_personality_v0 {
+entry:
+  invoke void @may_throw() to label %ok unwind label %pad
+
+ok:
+  ret void
+
+pad:
+  %caught = landingpad { i8*, i32 } cleanup
+  resume { i8*, i32 } %caught
+}
+"#,
+        )
+        .unwrap();
+    let function = module.named_function("guarded").unwrap();
+    let pad = function.get_last_basic_block().unwrap();
+    let landing_pad = pad.get_first_instruction().unwrap();
+    assert_eq!(landing_pad.get_opcode(), LLVMOpcode::LLVMLandingPad);
+    assert!(pad.split_at(landing_pad, "nope").is_none());
+    assert_eq!(function.count_basic_blocks(), 3);
+
+    // right after the pad is fine
+    let resume = landing_pad.get_next_instruction().unwrap();
+    assert!(pad.split_at(resume, "rethrow").is_some());
+    assert!(module.verify().is_ok());
+}
@@ -6,7 +6,7 @@ use llvm_sys_wrapper::*;
 #[test]
 fn test_jit_engine() {
     // initialize LLVM
-    LLVM::initialize();
+    LLVM::initialize().unwrap();
 
     // create context
     let context = Context::global_context();
@@ -30,8 +30,8 @@ fn test_jit_engine() {
     let printf_func = module.add_function("printf", printf_type);
 
     // call printf function
-    let mut args = [hello, world];
-    let _call = builder.build_call(printf_func.as_ref(), &mut args);
+    let args = [hello.into(), world.into()];
+    let _call = builder.build_call(printf_func, &args);
     // ret void
     let _ret = builder.build_ret_void();
 
@@ -41,9 +41,9 @@ fn test_jit_engine() {
             //module.dump();
 
             let interperter = module.create_jit_engine().unwrap();
-            let named_function = module.named_function("main");
+            let named_function = interperter.named_function("main").unwrap();
             let mut params = [];
-            let run_result = interperter.run_function(named_function.as_ref(), &mut params);
+            let run_result = interperter.run_function(named_function, &mut params);
             let _ = run_result.to_int();
         }
         Err(msg) => panic!("Error: {}", msg),
@@ -6,7 +6,7 @@ use LLVM::{Const, Type};
 #[test]
 fn it_works() {
     // 参考: [Go言語で利用するLLVM入門](https://postd.cc/an-introduction-to-llvm-in-go/)
-    LLVM::initialize();
+    LLVM::initialize().unwrap();
 
     // setup our builder and module
     let builder = Builder::new();
@@ -30,8 +30,8 @@ fn it_works() {
     builder.build_store(const_b_value, b);
 
     // return a + b
-    let a_val = builder.build_load(a);
-    let b_val = builder.build_load(b);
+    let a_val = builder.build_load(a).into_int_value().unwrap();
+    let b_val = builder.build_load(b).into_int_value().unwrap();
     let ab_val = builder.build_add(a_val, b_val);
     builder.build_ret(ab_val);
 
@@ -0,0 +1,60 @@
+extern crate llvm_sys_wrapper;
+
+use llvm_sys_wrapper::*;
+
+const RUNTIME: &str = r#"
+define i32 @double_it(i32 %value) {
+entry:
+  %result = mul i32 %value, 2
+  ret i32 %result
+}
+"#;
+
+#[test]
+fn test_link_runtime_into_unit() {
+    let context = Context::new();
+    let builder = context.create_builder();
+    let unit = context.create_module("unit");
+
+    // the unit only declares what the runtime provides
+    let helper_type = fn_type!(context.Int32Type(), context.Int32Type());
+    let double_it = unit.add_function("double_it", helper_type);
+    let main = unit.add_function("main", fn_type!(context.Int32Type()));
+    builder.position_at_end(main.append_basic_block("entry"));
+    let call = builder.build_call(double_it, &[context.SInt32(21).into()]);
+    builder.build_ret(call);
+
+    let runtime = context.parse_ir(RUNTIME).unwrap();
+    unit.link_in(runtime).unwrap();
+
+    let double_it = unit.named_function("double_it").unwrap();
+    assert!(double_it.get_entry_block().is_some());
+    assert_eq!(unit.count_functions(), 2);
+    assert!(unit.verify().is_ok());
+
+    LLVM::initialize().unwrap();
+    let engine = unit.create_interpreter().unwrap();
+    let main = engine.named_function("main").unwrap();
+    assert_eq!(engine.run_function(main, &mut []).to_int(), 42);
+}
+
+#[test]
+fn test_link_conflict() {
+    let context = Context::new();
+    let unit = context.parse_ir(RUNTIME).unwrap();
+    let runtime = context.parse_ir(RUNTIME).unwrap();
+
+    match unit.link_in(runtime) {
+        Err(LinkError::SymbolConflict { name, message }) => {
+            assert_eq!(name, "double_it");
+            assert!(message.contains("symbol multiply defined"));
+        }
+        other => panic!("expected a symbol conflict, got {:?}", other),
+    }
+
+    // the destination is still intact and the context handles errors as before
+    assert_eq!(unit.count_functions(), 1);
+    assert!(unit.verify().is_ok());
+    let other = context.create_module("other");
+    unit.link_in(other).unwrap();
+}
@@ -3,9 +3,10 @@ extern crate llvm_sys_wrapper;
 use llvm_sys_wrapper::*;
 
 #[test]
+#[allow(clippy::unnecessary_cast)]
 fn test_list() {
     // initialize LLVM
-    LLVM::initialize();
+    LLVM::initialize().unwrap();
 
     // create context
     let context = Context::global_context();
@@ -22,10 +23,10 @@ fn test_list() {
 
     // define struct Pair<int, &Pair>
     let pair_type = context.StructTypeNamed("Pair");
-    let pair_pointer_type = context.PointerType(pair_type.as_ref());
+    let pair_pointer_type = context.PointerType(pair_type);
     // define struct body
-    let mut field_types = [context.Int32Type(), pair_pointer_type];
-    pair_type.set_body(&mut field_types, false);
+    let field_types = [context.Int32Type().into(), pair_pointer_type.into()];
+    pair_type.set_body(&field_types, false);
 
     // setup strings
     let fmt_num = builder.build_global_string_ptr("%d");
@@ -42,9 +43,9 @@ fn test_list() {
     //
 
     // alloca array of Pair
-    let buf = builder.build_array_alloca(pair_type.as_ref(), context.SInt32(5));
-    let ptr = builder.build_alloca(context.PointerType(pair_type.as_ref()));
-    let next_ptr = builder.build_alloca(context.PointerType(pair_type.as_ref()));
+    let buf = builder.build_array_alloca(pair_type, context.SInt32(5));
+    let ptr = builder.build_alloca(context.PointerType(pair_type));
+    let next_ptr = builder.build_alloca(context.PointerType(pair_type));
     let i32_ptr = builder.build_alloca(context.PointerType(context.Int32Type()));
     let null_pointer = builder.build_int_to_ptr(context.UInt32(0), pair_pointer_type);
 
@@ -52,23 +53,27 @@ fn test_list() {
     for i in 0..5 {
         let index = context.UInt32(i as u64);
         // get pointer to buf[i]
-        let mut args = [index];
-        builder.build_store(builder.build_inbounds_gep(buf, &mut args), ptr);
+        let args = [index];
+        builder.build_store(builder.build_inbounds_gep(buf, &args), ptr);
 
         // // ptr.value = i
         builder.build_store(
-            builder.build_struct_gep(builder.build_load(ptr), 0),
+            builder.build_struct_gep(builder.build_load(ptr).into_pointer_value().unwrap(), 0),
             i32_ptr,
         );
-        builder.build_store(context.SInt32(i as u64), builder.build_load(i32_ptr));
+        builder.build_store(
+            context.SInt32(i),
+            builder.build_load(i32_ptr).into_pointer_value().unwrap(),
+        );
 
         // // ptr.next = next pointer
-        let tmp = builder.build_struct_gep(builder.build_load(ptr), 1);
+        let tmp =
+            builder.build_struct_gep(builder.build_load(ptr).into_pointer_value().unwrap(), 1);
         if i == 4 {
             builder.build_store(null_pointer, tmp);
         } else {
-            let mut args = [context.UInt32(i + 1 as u64)];
-            builder.build_store(builder.build_inbounds_gep(buf, &mut args), next_ptr);
+            let args = [context.UInt32(i + 1 as u64)];
+            builder.build_store(builder.build_inbounds_gep(buf, &args), next_ptr);
             builder.build_store(builder.build_load(next_ptr), tmp);
         }
     }
@@ -78,14 +83,14 @@ fn test_list() {
     //
 
     // display '('
-    let mut args = [str_lparen];
-    builder.build_call(printf_func.as_ref(), &mut args);
+    let args = [str_lparen.into()];
+    builder.build_call(printf_func, &args);
 
     // display values
 
     // get pointer to buf[i]
-    let mut args = [context.UInt32(0)];
-    builder.build_store(builder.build_inbounds_gep(buf, &mut args), ptr);
+    let args = [context.UInt32(0)];
+    builder.build_store(builder.build_inbounds_gep(buf, &args), ptr);
 
     // define loop block
     let loop_block = function.append_basic_block("loop");
@@ -96,25 +101,28 @@ fn test_list() {
 
     // loop start
     builder.build_store(
-        builder.build_struct_gep(builder.build_load(ptr), 0),
+        builder.build_struct_gep(builder.build_load(ptr).into_pointer_value().unwrap(), 0),
         i32_ptr,
     );
-    let val = builder.build_load(builder.build_load(i32_ptr));
-    let mut args = [fmt_num, val];
-    builder.build_call(printf_func.as_ref(), &mut args);
+    let val = builder.build_load(builder.build_load(i32_ptr).into_pointer_value().unwrap());
+    let args = [fmt_num.into(), val];
+    builder.build_call(printf_func, &args);
 
     // get ptr.next
-    let next = builder.build_struct_gep(builder.build_load(ptr), 1);
+    let next = builder.build_struct_gep(builder.build_load(ptr).into_pointer_value().unwrap(), 1);
     builder.build_store(builder.build_load(next), ptr);
-    let cond = builder.build_icmp_eq(null_pointer, builder.build_load(ptr));
+    let cond = builder.build_icmp_eq(
+        null_pointer,
+        builder.build_load(ptr).into_pointer_value().unwrap(),
+    );
 
     builder.build_cond_br(cond, end_block, print_space_block);
 
     // print space
     builder.position_at_end(print_space_block);
 
-    let mut args = [str_space];
-    builder.build_call(printf_func.as_ref(), &mut args);
+    let args = [str_space.into()];
+    builder.build_call(printf_func, &args);
 
     builder.build_br(loop_block);
 
@@ -122,8 +130,8 @@ fn test_list() {
     builder.position_at_end(end_block);
 
     // display ')'
-    let mut args = [str_rparen];
-    builder.build_call(printf_func.as_ref(), &mut args);
+    let args = [str_rparen.into()];
+    builder.build_call(printf_func, &args);
 
     // ret void
     let _ret = builder.build_ret_void();
@@ -0,0 +1,99 @@
+extern crate llvm_sys_wrapper;
+
+use llvm_sys_wrapper::*;
+
+// Returns the byte at `index` of a buffer after `fill` has run on it.
+fn build_probe<'ctx, F>(context: &'ctx Context, module: &Module<'ctx>, index: u64, fill: F)
+where
+    F: Fn(&Builder<'ctx>, PointerValue<'ctx>),
+{
+    let builder = context.create_builder();
+    let i8_type = context.Int8Type();
+    let function = module.add_function("probe", fn_type!(i8_type));
+    builder.position_at_end(function.append_basic_block("entry"));
+    let buffer = builder.build_array_alloca(i8_type, context.UInt64(16));
+    fill(&builder, buffer);
+    let byte = builder.build_inbounds_gep(buffer, &[context.UInt64(index)]);
+    builder.build_ret(builder.build_load(byte));
+}
+
+fn run_probe(module: Module) -> u64 {
+    assert!(module.verify().is_ok());
+    let engine = module.create_jit_engine().unwrap();
+    let probe = engine.named_function("probe").unwrap();
+    engine.run_function(probe, &mut []).to_int()
+}
+
+#[test]
+fn test_memset() {
+    LLVM::initialize().unwrap();
+    let context = Context::new();
+    let module = context.create_module("memset");
+    build_probe(&context, &module, 9, |builder, buffer| {
+        let size = context.UInt64(16);
+        builder.build_memset(buffer, 1, context.UInt8(7), size, false);
+    });
+    let ir = module.print_module_to_string();
+    assert!(ir.contains("call void @llvm.memset.p0i8.i64(i8* align 1 %"));
+    assert!(ir.contains("i8 7, i64 16, i1 false)"));
+    assert_eq!(run_probe(module), 7);
+}
+
+#[test]
+fn test_memcpy() {
+    LLVM::initialize().unwrap();
+    let context = Context::new();
+    let module = context.create_module("memcpy");
+    build_probe(&context, &module, 12, |builder, buffer| {
+        let source = builder.build_global_string_ptr("hello, memcpy!!");
+        let size = context.UInt64(16);
+        builder.build_memcpy(buffer, 1, source, 1, size, false);
+    });
+    assert!(module
+        .print_module_to_string()
+        .contains("call void @llvm.memcpy.p0i8.p0i8.i64("));
+    assert_eq!(run_probe(module), u64::from(b'y'));
+}
+
+#[test]
+fn test_memmove_overlapping() {
+    LLVM::initialize().unwrap();
+    let context = Context::new();
+    let module = context.create_module("memmove");
+    build_probe(&context, &module, 4, |builder, buffer| {
+        let source = builder.build_global_string_ptr("abcdefghijklmno");
+        builder.build_memcpy(buffer, 1, source, 1, context.UInt64(16), false);
+        // shift the first eight bytes two to the right
+        let dest = builder.build_inbounds_gep(buffer, &[context.UInt64(2)]);
+        builder.build_memmove(dest, 1, buffer, 1, context.UInt64(8), false);
+    });
+    assert!(module
+        .print_module_to_string()
+        .contains("call void @llvm.memmove.p0i8.p0i8.i64("));
+    assert_eq!(run_probe(module), u64::from(b'c'));
+}
+
+#[test]
+fn test_volatile_and_aligned() {
+    let context = Context::new();
+    let builder = context.create_builder();
+    let module = context.create_module("volatile");
+    let function = module.add_function(
+        "clear",
+        fn_type!(context.VoidType(), context.Int8PointerType()),
+    );
+    builder.position_at_end(function.append_basic_block("entry"));
+    let buffer = function.get_param(0).into_pointer_value().unwrap();
+    let size = context.UInt32(64);
+    let call = builder.build_memset(buffer, 16, context.UInt8(0), size, true);
+    builder.build_memcpy(buffer, 8, buffer, 8, size, true);
+    builder.build_ret_void();
+    assert!(module.verify().is_ok());
+
+    assert!(call.is_call_site());
+    let ir = module.print_module_to_string();
+    assert!(ir.contains("call void @llvm.memset.p0i8.i32(i8* align 16 %0, i8 0, i32 64, i1 true)"));
+    assert!(ir.contains(
+        "call void @llvm.memcpy.p0i8.p0i8.i32(i8* align 8 %0, i8* align 8 %0, i32 64, i1 true)"
+    ));
+}
@@ -0,0 +1,138 @@
+extern crate llvm_sys_wrapper;
+
+use llvm_sys_wrapper::*;
+use std::collections::HashSet;
+
+#[test]
+fn test_lookup_and_iteration() {
+    let context = Context::new();
+    let builder = context.create_builder();
+    let module = context.create_module("iteration");
+
+    let fun_type = fn_type!(context.Int32Type());
+    let names = ["used", "unused", "main"];
+    for name in names.iter() {
+        let function = module.add_function(name, fun_type);
+        builder.position_at_end(function.append_basic_block("entry"));
+        builder.build_ret(context.SInt32(0));
+    }
+    let printf_type = fn_type!(context.Int32Type(), context.Int8PointerType() ,,,);
+    module.add_function("printf", printf_type);
+
+    assert!(module.named_function("missing").is_none());
+    assert_eq!(module.named_function("main").unwrap().get_name(), "main");
+    assert_eq!(module.count_functions(), 4);
+    let declarations: Vec<String> = module
+        .get_functions()
+        .filter(|function| function.get_entry_block().is_none())
+        .map(|function| function.get_name())
+        .collect();
+    assert_eq!(declarations, vec!["printf"]);
+
+    let global = module.add_global(context.Int32Type(), "answer");
+    global.set_initializer(context.SInt32(42));
+    assert_eq!(module.count_globals(), 1);
+
+    let alias = module.add_alias(global.get_type(), global, "answer_alias");
+    assert_eq!(module.named_alias("answer_alias"), Some(alias));
+    assert_eq!(alias.get_aliasee(), global.into());
+    assert_eq!(module.count_aliases(), 1);
+    assert!(module.named_alias("answer").is_none());
+
+    // an ifunc whose resolver hands back `used`
+    let resolver_type = fn_type!(context.PointerType(fun_type));
+    let resolver = module.add_function("resolve_pick", resolver_type);
+    builder.position_at_end(resolver.append_basic_block("entry"));
+    builder.build_ret(module.named_function("used").unwrap());
+    let ifunc = module.add_ifunc("pick", fun_type, 0, resolver);
+    assert_eq!(ifunc.get_resolver(), Some(resolver));
+    assert_eq!(module.named_ifunc("pick"), Some(ifunc));
+    assert_eq!(module.get_ifuncs().collect::<Vec<_>>(), vec![ifunc]);
+
+    match module.verify() {
+        Ok(_) => {}
+        Err(msg) => panic!("Error: {}", msg),
+    }
+
+    let ir = module.print_module_to_string();
+    assert!(ir.contains("@answer_alias = alias i32, i32* @answer"));
+    assert!(ir.contains("@pick = ifunc i32 (), i32 ()* ()* @resolve_pick"));
+}
+
+#[test]
+fn test_strip_dead_functions() {
+    let context = Context::new();
+    let builder = context.create_builder();
+    let module = context.create_module("strip");
+
+    let fun_type = fn_type!(context.VoidType());
+    let helper = module.add_function("helper", fun_type);
+    builder.position_at_end(helper.append_basic_block("entry"));
+    builder.build_ret_void();
+    let dead = module.add_function("dead", fun_type);
+    builder.position_at_end(dead.append_basic_block("entry"));
+    builder.build_ret_void();
+    let main = module.add_function("main", fun_type);
+    builder.position_at_end(main.append_basic_block("entry"));
+    builder.build_call(helper, &[]);
+    builder.build_ret_void();
+
+    // a function nobody calls, other than main, is dead
+    let dead_names: Vec<String> = module
+        .get_functions()
+        .filter(|function| function.get_name() != "main")
+        .filter(|function| unsafe { LLVMGetFirstUse(function.as_ref()).is_null() })
+        .map(|function| function.get_name())
+        .collect();
+    assert_eq!(dead_names, vec!["dead"]);
+
+    for name in dead_names.iter() {
+        assert!(module.delete_function(name));
+    }
+    assert!(!module.delete_function("dead"));
+
+    let remaining: HashSet<String> = module.get_functions().map(|f| f.get_name()).collect();
+    assert_eq!(remaining.len(), 2);
+    assert!(remaining.contains("helper") && remaining.contains("main"));
+}
+
+#[test]
+fn test_named_metadata() {
+    let context = Context::new();
+    let module = context.create_module("metadata");
+    assert!(module.get_named_metadata_names().is_empty());
+
+    let node = unsafe {
+        let text = "wrapper";
+        let string =
+            LLVMMDStringInContext2(context.as_ref(), text.as_ptr() as *const _, text.len());
+        let mut operands = [string];
+        let node = LLVMMDNodeInContext2(context.as_ref(), operands.as_mut_ptr(), 1);
+        Value::from_ptr(LLVMMetadataAsValue(context.as_ref(), node))
+    };
+    module.add_named_metadata_operand("llvm.ident", node);
+    module.add_named_metadata_operand("llvm.ident", node);
+
+    assert_eq!(module.get_named_metadata_names(), vec!["llvm.ident"]);
+    assert_eq!(module.count_named_metadata_operands("llvm.ident"), 2);
+    assert_eq!(module.get_named_metadata_operands("llvm.ident").len(), 2);
+    assert_eq!(module.count_named_metadata_operands("missing"), 0);
+}
+
+#[test]
+fn test_delete_alias() {
+    let context = Context::new();
+    let module = context.create_module("delete_alias");
+    let global = module.add_global(context.Int32Type(), "answer");
+    global.set_initializer(context.SInt32(42));
+    let kept = module.add_alias(global.get_type(), global, "kept");
+    let alias = module.add_alias(global.get_type(), global, "doomed");
+    assert_eq!(module.count_aliases(), 2);
+
+    unsafe { alias.delete() };
+    assert!(module.named_alias("doomed").is_none());
+    assert_eq!(module.named_alias("kept"), Some(kept));
+    assert_eq!(module.count_aliases(), 1);
+    assert!(module.verify().is_ok());
+    assert!(!module.print_module_to_string().contains("@doomed"));
+}
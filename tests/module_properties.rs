@@ -0,0 +1,97 @@
+extern crate llvm_sys_wrapper;
+
+use llvm_sys_wrapper::*;
+
+#[test]
+fn test_module_properties() {
+    let context = Context::new();
+    let module = context.create_module("props");
+    assert_eq!(module.get_identifier(), "props");
+    assert_eq!(module.get_source_file_name(), "props");
+    assert_eq!(module.get_target_triple(), "");
+    assert_eq!(module.get_data_layout(), "");
+    assert_eq!(module.get_inline_asm(), "");
+
+    module.set_identifier("renamed");
+    module.set_source_file_name("src/props.rs");
+    module.set_target_triple("x86_64-unknown-linux-gnu");
+    module.set_data_layout("e-m:e-i64:64-f80:128-n8:16:32:64-S128");
+    module.set_inline_asm(".globl marker");
+    module.append_inline_asm("marker:");
+
+    assert_eq!(module.get_identifier(), "renamed");
+    assert_eq!(module.get_source_file_name(), "src/props.rs");
+    assert_eq!(module.get_target_triple(), "x86_64-unknown-linux-gnu");
+    assert_eq!(
+        module.get_data_layout(),
+        "e-m:e-i64:64-f80:128-n8:16:32:64-S128"
+    );
+    assert_eq!(module.get_inline_asm(), ".globl marker\nmarker:\n");
+
+    let ir = module.print_module_to_string();
+    assert!(ir.contains("; ModuleID = 'renamed'"));
+    assert!(ir.contains("source_filename = \"src/props.rs\""));
+    assert!(ir.contains("target triple = \"x86_64-unknown-linux-gnu\""));
+    assert!(ir.contains("module asm \".globl marker\""));
+}
+
+#[test]
+fn test_module_flags() {
+    let context = Context::new();
+    let module = context.create_module("flags");
+    assert!(module.get_module_flag("PIC Level").is_none());
+
+    module.add_module_flag(ModuleFlagBehavior::Max, "PIC Level", context.SInt32(2));
+    module.add_module_flag(ModuleFlagBehavior::Error, "wchar_size", context.SInt32(4));
+    module.add_module_flag(
+        ModuleFlagBehavior::Override,
+        "frame-pointer",
+        context.SInt32(1),
+    );
+    assert!(module.verify().is_ok());
+
+    let pic_level = module.get_module_flag("PIC Level").unwrap();
+    assert_eq!(pic_level, context.SInt32(2).into());
+    assert!(module.get_module_flag("missing").is_none());
+
+    let ir = module.print_module_to_string();
+    assert!(ir.contains("!{i32 7, !\"PIC Level\", i32 2}"));
+    assert!(ir.contains("!{i32 1, !\"wchar_size\", i32 4}"));
+    assert!(ir.contains("!{i32 4, !\"frame-pointer\", i32 1}"));
+}
+
+#[test]
+fn test_clone_template() {
+    let context = Context::new();
+    let builder = context.create_builder();
+    let template = context.create_module("template");
+    template.add_module_flag(ModuleFlagBehavior::Warning, "answer", context.SInt32(42));
+    let function = template.add_function("entry", fn_type!(context.VoidType()));
+    builder.position_at_end(function.append_basic_block("entry"));
+    builder.build_ret_void();
+
+    let triples = ["x86_64-unknown-linux-gnu", "aarch64-unknown-linux-gnu"];
+    let clones: Vec<Module> = triples
+        .iter()
+        .map(|triple| {
+            let clone = template.clone();
+            clone.set_target_triple(triple);
+            clone
+        })
+        .collect();
+
+    for (clone, triple) in clones.iter().zip(triples.iter()) {
+        assert_eq!(clone.get_target_triple(), *triple);
+        assert!(clone.get_module_flag("answer").is_some());
+        let function = clone.named_function("entry").unwrap();
+        assert_ne!(function, template.named_function("entry").unwrap());
+        assert_eq!(function.count_basic_blocks(), 1);
+    }
+    assert_eq!(template.get_target_triple(), "");
+
+    // clones are independent of the template and of each other
+    clones[0].delete_function("entry");
+    assert!(clones[0].named_function("entry").is_none());
+    assert!(clones[1].named_function("entry").is_some());
+    assert!(template.named_function("entry").is_some());
+}
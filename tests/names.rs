@@ -0,0 +1,73 @@
+extern crate llvm_sys_wrapper;
+
+use llvm_sys_wrapper::*;
+use std::thread;
+
+fn build_named_module(id: usize) -> String {
+    let context = Context::new();
+    let builder = context.create_builder();
+    let module = context.create_module(&format!("names_{}", id));
+
+    let fun_type = fn_type!(context.Int32Type(), context.Int32Type());
+    let function = module.add_function("add_many", fun_type);
+    let entry_block = function.append_basic_block("entry");
+    builder.position_at_end(entry_block);
+
+    // short names live on the stack, long ones on the heap
+    let long_name = format!("a_rather_long_value_name_that_does_not_fit_inline_{}", id);
+    let mut acc = function.get_param(0).into_int_value().unwrap();
+    for i in 0..1000 {
+        let name = if i % 2 == 0 {
+            format!("short{}", i)
+        } else {
+            format!("{}_{}", long_name, i)
+        };
+        acc = builder.build_add_with_name(acc, context.SInt32(1), &name);
+    }
+    builder.build_ret(acc);
+
+    match module.verify() {
+        Ok(_) => module.print_module_to_string(),
+        Err(msg) => panic!("Error: {}", msg),
+    }
+}
+
+#[test]
+fn test_names_survive_the_call() {
+    let ir = build_named_module(0);
+    assert!(ir.contains("%short0 = add"));
+    assert!(ir.contains("%a_rather_long_value_name_that_does_not_fit_inline_0_999 = add"));
+}
+
+#[test]
+fn test_names_stop_at_nul() {
+    let context = Context::new();
+    let builder = context.create_builder();
+    let module = context.create_module("nul");
+
+    let function = module.add_function("f", fn_type!(context.VoidType()));
+    let entry_block = function.append_basic_block("entry\0ignored");
+    builder.position_at_end(entry_block);
+    builder.build_alloca_with_name(context.Int8Type(), "buf\0ignored");
+    builder.build_ret_void();
+
+    let ir = module.print_module_to_string();
+    assert!(ir.contains("entry:"));
+    assert!(ir.contains("%buf = alloca i8"));
+    assert!(!ir.contains("ignored"));
+}
+
+#[test]
+fn test_names_from_many_threads() {
+    let handles: Vec<_> = (0..4)
+        .map(|id| thread::spawn(move || build_named_module(id)))
+        .collect();
+
+    for (id, handle) in handles.into_iter().enumerate() {
+        let ir = handle.join().unwrap();
+        assert!(ir.contains(&format!(
+            "%a_rather_long_value_name_that_does_not_fit_inline_{}_1 = add",
+            id
+        )));
+    }
+}
@@ -0,0 +1,47 @@
+extern crate llvm_sys_wrapper;
+
+use llvm_sys_wrapper::*;
+
+fn build_answer<'ctx>(context: &'ctx Context) -> Module<'ctx> {
+    let builder = context.create_builder();
+    let module = context.create_module("ownership");
+
+    let fun_type = fn_type!(context.Int32Type());
+    let function = module.add_function("answer", fun_type);
+    let entry_block = function.append_basic_block("entry");
+    builder.position_at_end(entry_block);
+    builder.build_ret(context.SInt32(42));
+
+    module
+}
+
+#[test]
+fn test_engine_owns_module() {
+    LLVM::initialize().unwrap();
+
+    for _ in 0..16 {
+        let context = Context::new();
+        let module = build_answer(&context);
+        if let Err(msg) = module.verify() {
+            panic!("Error: {}", msg);
+        }
+
+        // the module is moved into the engine, which disposes it
+        let engine = module.create_jit_engine().unwrap();
+        let answer = engine.named_function("answer").unwrap();
+        let mut params = [];
+        let run_result = engine.run_function(answer, &mut params);
+        assert_eq!(run_result.to_int(), 42);
+
+        assert!(engine.named_function("question").is_none());
+    }
+}
+
+#[test]
+fn test_module_dropped_before_context() {
+    for _ in 0..16 {
+        let context = Context::new();
+        let module = build_answer(&context);
+        assert!(module.print_module_to_string().contains("ret i32 42"));
+    }
+}
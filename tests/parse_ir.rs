@@ -0,0 +1,69 @@
+extern crate llvm_sys_wrapper;
+
+use llvm_sys_wrapper::*;
+use std::env;
+use std::fs;
+
+const HELPERS: &str = r#"
+define i32 @clamp(i32 %value, i32 %low, i32 %high) {
+entry:
+  %below = icmp slt i32 %value, %low
+  %raised = select i1 %below, i32 %low, i32 %value
+  %above = icmp sgt i32 %raised, %high
+  %result = select i1 %above, i32 %high, i32 %raised
+  ret i32 %result
+}
+"#;
+
+#[test]
+fn test_parse_ir() {
+    let context = Context::new();
+    let module = context.parse_ir(HELPERS).unwrap();
+    assert!(module.verify().is_ok());
+
+    let clamp = module.named_function("clamp").unwrap();
+    assert_eq!(clamp.params_count(), 3);
+    assert_eq!(clamp.count_instructions(), 5);
+
+    // printing and parsing again is stable
+    let printed = module.print_module_to_string();
+    let reparsed = context.parse_ir(&printed).unwrap();
+    assert_eq!(reparsed.print_module_to_string(), printed);
+}
+
+#[test]
+fn test_parse_ir_file() {
+    let context = Context::new();
+    let path = env::temp_dir().join(format!("llvm_sys_wrapper_{}.ll", std::process::id()));
+    let filename = path.to_str().unwrap();
+    fs::write(&path, HELPERS).unwrap();
+    let module = context.parse_ir_file(filename);
+    fs::remove_file(&path).unwrap();
+
+    let module = module.unwrap();
+    assert!(module.named_function("clamp").is_some());
+
+    let err = context.parse_ir_file(filename).unwrap_err();
+    assert_eq!((err.line, err.column), (0, 0));
+    assert!(!err.message.is_empty());
+}
+
+#[test]
+fn test_parse_ir_diagnostic() {
+    let context = Context::new();
+    let broken = "define i32 @broken() {\nentry:\n  ret i32 %missing\n}\n";
+    let err = context.parse_ir(broken).unwrap_err();
+    assert_eq!(err.line, 3);
+    assert_eq!(err.column, 11);
+    assert_eq!(err.message, "use of undefined value '%missing'");
+    assert_eq!(err.source_line, Some("  ret i32 %missing".to_string()));
+    assert_eq!(err.to_string(), "3:11: use of undefined value '%missing'");
+
+    let err = context.parse_ir("define void @f() {").unwrap_err();
+    assert_eq!(err.line, 1);
+    assert_eq!(err.column, 19);
+    assert_eq!(
+        err.message,
+        "found end of file when expecting more instructions"
+    );
+}
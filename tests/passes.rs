@@ -0,0 +1,169 @@
+extern crate llvm_sys_wrapper;
+
+use llvm_sys_wrapper::*;
+
+// int square_sum(int n) { int total = 0; for (int i = 0; i < n; i++) total += i * i; return total; }
+// written the way a front end does it, with every local in an alloca
+fn build_square_sum<'ctx>(context: &'ctx Context, module: &Module<'ctx>) -> Function<'ctx> {
+    let builder = context.create_builder();
+    let i32_type = context.Int32Type();
+    let function = module.add_function("square_sum", fn_type!(i32_type, i32_type));
+    let entry = function.append_basic_block("entry");
+    let cond = function.append_basic_block("cond");
+    let body = function.append_basic_block("body");
+    let exit = function.append_basic_block("exit");
+
+    builder.position_at_end(entry);
+    let n = builder.build_alloca(i32_type);
+    let total = builder.build_alloca(i32_type);
+    let i = builder.build_alloca(i32_type);
+    builder.build_store(function.get_param(0), n);
+    builder.build_store(context.SInt32(0), total);
+    builder.build_store(context.SInt32(0), i);
+    builder.build_br(cond);
+
+    builder.position_at_end(cond);
+    let i_val = builder.build_load(i).into_int_value().unwrap();
+    let n_val = builder.build_load(n).into_int_value().unwrap();
+    let more = builder.build_icmp_slt(i_val, n_val);
+    builder.build_cond_br(more, body, exit);
+
+    builder.position_at_end(body);
+    let i_val = builder.build_load(i).into_int_value().unwrap();
+    let square = builder.build_mul(i_val, i_val);
+    let total_val = builder.build_load(total).into_int_value().unwrap();
+    let sum = builder.build_add(total_val, square);
+    builder.build_store(sum, total);
+    let next = builder.build_add(i_val, context.SInt32(1));
+    builder.build_store(next, i);
+    builder.build_br(cond);
+
+    builder.position_at_end(exit);
+    let result = builder.build_load(total);
+    builder.build_ret(result);
+    function
+}
+
+#[test]
+fn test_function_pass_manager() {
+    let context = Context::new();
+    let module = context.create_module("fpm");
+    let function = build_square_sum(&context, &module);
+    assert!(module.print_module_to_string().contains("alloca"));
+
+    let fpm = FunctionPassManager::new(&module);
+    fpm.add_promote_memory_to_register_pass();
+    fpm.add_instruction_combining_pass();
+    fpm.add_reassociate_pass();
+    fpm.add_gvn_pass();
+    fpm.add_cfg_simplification_pass();
+    fpm.initialize();
+    assert!(fpm.run(function));
+    fpm.finalize();
+
+    let ir = module.print_module_to_string();
+    assert!(!ir.contains("alloca"));
+    assert!(ir.contains("phi i32"));
+    assert!(module.verify().is_ok());
+}
+
+#[test]
+fn test_module_pass_manager() {
+    let context = Context::new();
+    let builder = context.create_builder();
+    let module = context.create_module("pm");
+    let square_sum = build_square_sum(&context, &module);
+    square_sum.set_linkage(Linkage::Internal);
+    let main = module.add_function("main", fn_type!(context.Int32Type()));
+    builder.position_at_end(main.append_basic_block("entry"));
+    let call = builder.build_call(square_sum, &[context.SInt32(4).into()]);
+    builder.build_ret(call);
+
+    let pm = PassManager::new();
+    pm.add_always_inliner_pass();
+    pm.add_function_inlining_pass();
+    pm.add_promote_memory_to_register_pass();
+    pm.add_instruction_combining_pass();
+    pm.add_global_dce_pass();
+    assert!(pm.run(&module));
+
+    // the helper was inlined into its only caller and then dropped
+    assert!(module.named_function("square_sum").is_none());
+    assert!(module.verify().is_ok());
+}
+
+#[test]
+fn test_o2_pipeline() {
+    let context = Context::new();
+    let builder = context.create_builder();
+    let module = context.create_module("o2");
+    let square_sum = build_square_sum(&context, &module);
+    square_sum.set_linkage(Linkage::Internal);
+    let main = module.add_function("main", fn_type!(context.Int32Type()));
+    builder.position_at_end(main.append_basic_block("entry"));
+    let call = builder.build_call(square_sum, &[context.SInt32(4).into()]);
+    builder.build_ret(call);
+
+    let fpm = FunctionPassManager::o2(&module);
+    fpm.initialize();
+    for function in module.get_functions() {
+        fpm.run(function);
+    }
+    fpm.finalize();
+    PassManager::o2().run(&module);
+
+    // 0 + 1 + 4 + 9, computed at compile time
+    let ir = module.print_module_to_string();
+    assert!(ir.contains("ret i32 14"), "{}", ir);
+    assert_eq!(module.count_functions(), 1);
+}
+
+#[test]
+fn test_every_pass_links() {
+    let context = Context::new();
+    let module = context.create_module("all");
+    build_square_sum(&context, &module);
+
+    let pm = PassManager::new();
+    pm.add_promote_memory_to_register_pass();
+    pm.add_demote_memory_to_register_pass();
+    pm.add_scalar_repl_aggregates_pass();
+    pm.add_instruction_combining_pass();
+    pm.add_reassociate_pass();
+    pm.add_gvn_pass();
+    pm.add_new_gvn_pass();
+    pm.add_early_cse_pass();
+    pm.add_sccp_pass();
+    pm.add_correlated_value_propagation_pass();
+    pm.add_jump_threading_pass();
+    pm.add_cfg_simplification_pass();
+    pm.add_dce_pass();
+    pm.add_aggressive_dce_pass();
+    pm.add_dead_store_elimination_pass();
+    pm.add_memcpy_opt_pass();
+    pm.add_tail_call_elimination_pass();
+    pm.add_licm_pass();
+    pm.add_loop_rotate_pass();
+    pm.add_loop_deletion_pass();
+    pm.add_loop_unroll_pass();
+    pm.add_ind_var_simplify_pass();
+    pm.add_loop_vectorize_pass();
+    pm.add_slp_vectorize_pass();
+    pm.add_lower_switch_pass();
+    pm.add_basic_alias_analysis_pass();
+    pm.add_type_based_alias_analysis_pass();
+    pm.add_verifier_pass();
+    pm.add_function_inlining_pass();
+    pm.add_always_inliner_pass();
+    pm.add_global_dce_pass();
+    pm.add_global_optimizer_pass();
+    pm.add_ipsccp_pass();
+    pm.add_constant_merge_pass();
+    pm.add_dead_arg_elimination_pass();
+    pm.add_function_attrs_pass();
+    pm.add_merge_functions_pass();
+    pm.add_strip_dead_prototypes_pass();
+    pm.add_strip_symbols_pass();
+    pm.run(&module);
+    assert!(module.verify().is_ok());
+}
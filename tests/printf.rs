@@ -5,7 +5,7 @@ use llvm_sys_wrapper::*;
 #[test]
 fn test_printf() {
     // initialize LLVM
-    LLVM::initialize();
+    LLVM::initialize().unwrap();
 
     // create context
     let context = Context::global_context();
@@ -29,8 +29,8 @@ fn test_printf() {
     let printf_func = module.add_function("printf", printf_type);
 
     // call printf function
-    let mut args = [hello, world];
-    let _call = builder.build_call(printf_func.as_ref(), &mut args);
+    let args = [hello.into(), world.into()];
+    let _call = builder.build_call(printf_func, &args);
     // ret void
     let _ret = builder.build_ret_void();
 
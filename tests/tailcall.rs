@@ -14,7 +14,7 @@ use llvm_sys_wrapper::*;
 #[test]
 fn test_tailcall() {
     // initialize LLVM
-    LLVM::initialize();
+    LLVM::initialize().unwrap();
 
     // create context
     let context = Context::global_context();
@@ -39,7 +39,7 @@ fn test_tailcall() {
     let x = builder.build_alloca(context.Int32Type());
     let param = fastcc_func.get_param(0);
     builder.build_store(param, x);
-    let x_val = builder.build_load(x);
+    let x_val = builder.build_load(x).into_int_value().unwrap();
 
     // define blocks
     let then_block = fastcc_func.append_basic_block("then");
@@ -52,8 +52,8 @@ fn test_tailcall() {
     // if false
     builder.position_at_end(else_block);
     let x2 = builder.build_sub(x_val, context.UInt32(1));
-    let mut args = [x2];
-    let ret = builder.build_tail_call(fastcc_func.as_ref(), &mut args);
+    let args = [x2.into()];
+    let ret = builder.build_tail_call(fastcc_func, &args);
     builder.build_ret(ret);
 
     // if true
@@ -71,15 +71,15 @@ fn test_tailcall() {
 
     // call fastcc
     let arg = context.UInt32(100000000);
-    let mut args = [arg];
-    let ret = builder.build_call(fastcc_func.as_ref(), &mut args);
+    let args = [arg.into()];
+    let ret = builder.build_call(fastcc_func, &args);
 
     // setup format string
     let fmt_d = builder.build_global_string_ptr("%d\n");
 
     // call printf function
-    let mut args = [fmt_d, ret];
-    let _call = builder.build_call(printf_func.as_ref(), &mut args);
+    let args = [fmt_d.into(), ret];
+    let _call = builder.build_call(printf_func, &args);
     // ret void
     let _ret = builder.build_ret_void();
 
@@ -0,0 +1,119 @@
+extern crate llvm_sys_wrapper;
+
+use llvm_sys_wrapper::*;
+use std::env;
+use std::fs;
+
+fn build_module<'ctx>(context: &'ctx Context, name: &str) -> Module<'ctx> {
+    let builder = context.create_builder();
+    let module = context.create_module(name);
+    let function = module.add_function(name, fn_type!(context.Int32Type()));
+    builder.position_at_end(function.append_basic_block("entry"));
+    builder.build_ret(context.SInt32(7));
+    module
+}
+
+#[test]
+fn test_target_lookup() {
+    LLVM::initialize().unwrap();
+
+    let triple = Target::default_triple();
+    let target = Target::from_triple(&triple).unwrap();
+    assert!(target.has_target_machine());
+    assert!(!target.get_description().is_empty());
+    assert!(Target::all().contains(&target));
+    assert_eq!(Target::from_name(&target.get_name()), Some(target));
+
+    assert!(Target::from_triple("nonsense-unknown-nowhere").is_err());
+    assert!(Target::from_name("nonsense").is_none());
+    assert_eq!(
+        Target::normalize_triple("x86_64-linux-gnu"),
+        "x86_64-unknown-linux-gnu"
+    );
+}
+
+#[test]
+fn test_target_machine() {
+    LLVM::initialize().unwrap();
+
+    let triple = "x86_64-unknown-linux-gnu";
+    let target = match Target::from_triple(triple) {
+        Ok(target) => target,
+        // the host LLVM is not an x86 one
+        Err(_) => return,
+    };
+    let target_machine = target
+        .create_target_machine(triple)
+        .cpu("x86-64")
+        .features("+sse2")
+        .opt_level(CodegenLevel::O3)
+        .reloc_mode(RelocMode::PIC)
+        .code_model(CodeModel::Small)
+        .build()
+        .unwrap();
+
+    assert_eq!(target_machine.get_target(), target);
+    assert_eq!(target_machine.get_triple(), triple);
+    assert_eq!(target_machine.get_cpu(), "x86-64");
+    assert_eq!(target_machine.get_features(), "+sse2");
+    assert!(target_machine.get_data_layout().starts_with("e-m:e-"));
+
+    // one machine for several modules
+    let context = Context::new();
+    for name in ["first", "second"].iter() {
+        let module = build_module(&context, name);
+        target_machine.configure_module(&module);
+        assert_eq!(module.get_target_triple(), triple);
+        assert_eq!(module.get_data_layout(), target_machine.get_data_layout());
+
+        let path = env::temp_dir().join(format!(
+            "llvm_sys_wrapper_{}_{}.o",
+            std::process::id(),
+            name
+        ));
+        target_machine
+            .emit_to_file(&module, path.to_str().unwrap(), FileType::Object)
+            .unwrap();
+        let object = fs::read(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(&object[..4], b"\x7fELF");
+    }
+}
+
+#[test]
+fn test_emit_to_memory() {
+    LLVM::initialize().unwrap();
+
+    let triple = Target::default_triple();
+    let target_machine = Target::from_triple(&triple)
+        .unwrap()
+        .create_target_machine(&triple)
+        .reloc_mode(RelocMode::PIC)
+        .build()
+        .unwrap();
+
+    let context = Context::new();
+    let module = build_module(&context, "answer");
+    target_machine.configure_module(&module);
+
+    let object = target_machine
+        .emit_to_memory(&module, FileType::Object)
+        .unwrap();
+    assert!(!object.is_empty());
+
+    target_machine.set_asm_verbosity(false);
+    let assembly = target_machine
+        .emit_to_memory(&module, FileType::Assembly)
+        .unwrap();
+    let assembly = String::from_utf8(assembly).unwrap();
+    assert!(assembly.contains("answer:"));
+
+    // the same text ends up in a file
+    let path = env::temp_dir().join(format!("llvm_sys_wrapper_{}.s", std::process::id()));
+    target_machine
+        .emit_to_file(&module, path.to_str().unwrap(), FileType::Assembly)
+        .unwrap();
+    let written = fs::read_to_string(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert_eq!(written, assembly);
+}
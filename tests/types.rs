@@ -0,0 +1,153 @@
+extern crate llvm_sys_wrapper;
+
+use llvm_sys_wrapper::*;
+use std::convert::TryFrom;
+
+#[test]
+fn test_type_introspection() {
+    let context = Context::new();
+
+    let i16_type = context.Int16Type();
+    assert_eq!(i16_type.get_bit_width(), 16);
+    assert!(i16_type.is_sized());
+    assert!(!context.VoidType().is_sized());
+
+    let ptr_type = context.PointerType(i16_type);
+    assert_eq!(ptr_type.get_address_space(), 0);
+    assert_eq!(ptr_type.get_element_type().into_int_type(), Some(i16_type));
+
+    let array_type = context.ArrayType(context.DoubleType(), 8);
+    assert_eq!(array_type.get_length(), 8);
+    assert!(array_type.get_element_type().is_float_type());
+    assert_eq!(array_type.print_to_string(), "[8 x double]");
+
+    let vector_type = context.Int32Type().vector_type(4);
+    assert_eq!(vector_type.get_size(), 4);
+    assert_eq!(vector_type.print_to_string(), "<4 x i32>");
+
+    let fun_type = fn_type!(context.Int32Type(), context.Int8PointerType() ,,,);
+    assert!(fun_type.is_var_arg());
+    assert_eq!(fun_type.count_param_types(), 1);
+    assert!(fun_type.get_param_types()[0].is_pointer_type());
+    assert!(fun_type.get_return_type().is_int_type());
+
+    assert!(IntType::try_from(ptr_type.as_any_type()).is_err());
+    assert!(ptr_type.as_any_type().into_pointer_type().is_some());
+}
+
+#[test]
+fn test_struct_types() {
+    let context = Context::new();
+
+    let node_type = context.StructTypeNamed("Node");
+    assert!(node_type.is_opaque());
+    assert!(!node_type.is_sized());
+    assert_eq!(node_type.get_name(), Some("Node".to_string()));
+
+    let fields = [
+        context.Int64Type().into(),
+        context.PointerType(node_type).into(),
+    ];
+    node_type.set_body(&fields, false);
+    assert!(!node_type.is_opaque());
+    assert!(!node_type.is_packed());
+    assert_eq!(node_type.count_fields(), 2);
+    assert_eq!(node_type.get_field_types(), fields.to_vec());
+    assert!(node_type.get_field_type_at_index(2).is_none());
+
+    let pair_type = context.StructType(&fields, true);
+    assert!(pair_type.is_packed());
+    assert_eq!(pair_type.get_name(), None);
+}
+
+#[test]
+fn test_typed_constants() {
+    let context = Context::new();
+
+    let i8_type = context.Int8Type();
+    let minus_one = i8_type.const_int(-1i64 as u64, true);
+    assert_eq!(minus_one.get_type(), i8_type);
+    assert_eq!(minus_one.get_sext_const(), Some(-1));
+    assert_eq!(i8_type.const_all_ones().get_zext_const(), Some(255));
+    assert_eq!(i8_type.const_zero().get_zext_const(), Some(0));
+    assert!(i8_type.get_undef().is_undef());
+
+    let half = context.FloatType().const_float(0.5);
+    assert_eq!(half.print_to_string(), "float 5.000000e-01");
+
+    let null = context.Int8PointerType().const_null();
+    assert!(null.is_null());
+    assert_eq!(
+        null.get_type().get_element_type().into_int_type(),
+        Some(i8_type)
+    );
+
+    let array = i8_type.const_array(&[i8_type.const_int(1, false), minus_one]);
+    assert_eq!(array.get_type().get_length(), 2);
+
+    let node_type = context.StructTypeNamed("Pair");
+    node_type.set_body(&[i8_type.into(), i8_type.into()], false);
+    let pair = node_type.const_named_struct(&[minus_one.into(), minus_one.into()]);
+    assert_eq!(pair.get_type(), node_type);
+}
+
+#[test]
+fn test_anonymous_const_struct() {
+    let context = Context::new();
+    let module = context.create_module("const_struct");
+    let fields = [context.SInt32(7).into(), context.Double(0.5).into()];
+    let value = StructType::new_const_struct(&context, &fields, false);
+    assert_eq!(
+        value.get_type(),
+        context.StructType(
+            &[context.Int32Type().into(), context.DoubleType().into()],
+            false
+        )
+    );
+    assert!(value.is_constant());
+
+    let global = module.add_global(value.get_type(), "pair");
+    global.set_initializer(value);
+    assert!(module.verify().is_ok());
+    assert!(module
+        .print_module_to_string()
+        .contains("@pair = global { i32, double } { i32 7, double 5.000000e-01 }"));
+}
+
+#[test]
+fn test_function_type_from_runtime_params() {
+    let context = Context::new();
+    let module = context.create_module("lowering");
+
+    // parameter lists only known at run time, as a front-end would see them
+    let declared = ["i64", "double", "i64", "ptr"];
+    let params: Vec<Type> = declared
+        .iter()
+        .map(|name| match *name {
+            "i64" => context.Int64Type().into(),
+            "double" => context.DoubleType().into(),
+            _ => context.Int8PointerType().into(),
+        })
+        .collect();
+
+    let fun_type = context.function_type(context.VoidType(), &params, false);
+    assert_eq!(fun_type.count_param_types(), 4);
+    assert_eq!(fun_type.get_param_types(), params);
+    assert!(fun_type.get_return_type().is_void_type());
+    assert!(!fun_type.is_var_arg());
+
+    let function = module.add_function("lowered", fun_type);
+    assert_eq!(function.params_count(), 4);
+    assert_eq!(function.get_function_type(), fun_type);
+
+    // the macro builds the very same type
+    let printf_type = context.function_type(
+        context.Int32Type(),
+        &[context.Int8PointerType().into()],
+        true,
+    );
+    assert_eq!(
+        printf_type,
+        fn_type!(context.Int32Type(), context.Int8PointerType() ,,,)
+    );
+}
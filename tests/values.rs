@@ -0,0 +1,84 @@
+extern crate llvm_sys_wrapper;
+
+use llvm_sys_wrapper::*;
+use std::convert::TryFrom;
+
+#[test]
+fn test_checked_conversions() {
+    let context = Context::new();
+    let builder = context.create_builder();
+    let module = context.create_module("values");
+
+    let fun_type = fn_type!(
+        context.DoubleType(),
+        context.Int32Type(),
+        context.DoubleType()
+    );
+    let function = module.add_function("mix", fun_type);
+    let entry_block = function.append_basic_block("entry");
+    builder.position_at_end(entry_block);
+
+    let int_param = function.get_param(0);
+    let float_param = function.get_param(1);
+    assert!(int_param.is_int_value());
+    assert!(float_param.is_float_value());
+    assert!(int_param.into_float_value().is_none());
+    assert!(float_param.into_int_value().is_none());
+    assert!(PointerValue::try_from(int_param).is_err());
+
+    let x = IntValue::try_from(int_param).unwrap();
+    let y = float_param.into_float_value().unwrap();
+
+    // int math stays int, float math stays float
+    let x2 = builder.build_mul(x, context.SInt32(2));
+    let fx = builder.build_si_to_fp(x2, context.DoubleType());
+    let sum = builder.build_fadd(fx, y);
+    let is_big = builder.build_fcmp_ogt(sum, context.Double(100.0));
+    let result = builder.build_select(is_big, context.Double(100.0), sum);
+
+    let slot = builder.build_alloca(context.Int32Type());
+    assert!(slot.as_any_value().is_pointer_value());
+    builder.build_ret(result);
+
+    assert!(function.as_any_value().into_function_value().is_some());
+    assert!(x.as_any_value().into_function_value().is_none());
+
+    match module.verify() {
+        Ok(_) => {}
+        Err(msg) => panic!("Error: {}", msg),
+    }
+}
+
+#[test]
+fn test_constants() {
+    let context = Context::new();
+
+    let minus_one = context.SInt32(-1i64 as u64);
+    assert_eq!(minus_one.get_sext_const(), Some(-1));
+    assert_eq!(context.UInt8(200).get_zext_const(), Some(200));
+    assert!(minus_one.is_constant());
+
+    let null = context.PointerNull(context.Int8PointerType());
+    assert!(null.is_null());
+
+    let array = context.ConstArray(context.Int8Type(), &[context.UInt8(1), context.UInt8(2)]);
+    assert!(array.as_any_value().is_array_value());
+    assert_eq!(array.print_to_string(), "[2 x i8] c\"\\01\\02\"");
+}
+
+#[test]
+fn test_global_context_shorthands() {
+    // LLVM::Type and LLVM::Const live in the global context, so they go with
+    // its builders and modules; a Context::new() builder will not take them
+    let context = Context::global_context();
+    let builder = context.create_builder();
+    let module = context.create_module("shorthands");
+    let function = module.add_function("answer", fn_type!(LLVM::Type::Int32()));
+    builder.position_at_end(function.append_basic_block("entry"));
+    let param = builder.build_alloca(LLVM::Type::Int32());
+    builder.build_store(LLVM::Const::SInt32(40), param);
+    let loaded = builder.build_load(param).into_int_value().unwrap();
+    builder.build_ret(builder.build_add(loaded, LLVM::Const::SInt32(2)));
+    assert!(module.verify().is_ok());
+    assert_eq!(context.Int32Type(), LLVM::Type::Int32());
+}
@@ -0,0 +1,286 @@
+extern crate llvm_sys_wrapper;
+
+use llvm_sys_wrapper::*;
+
+fn run<'ctx>(module: Module<'ctx>, name: &str) -> FuncallResult {
+    assert!(module.verify().is_ok());
+    let engine = module.create_jit_engine().unwrap();
+    let function = engine.named_function(name).unwrap();
+    engine.run_function(function, &mut [])
+}
+
+#[test]
+fn test_vector_types_and_constants() {
+    let context = Context::new();
+    let i32x8 = context.VectorType(context.Int32Type(), 8);
+    assert_eq!(i32x8.get_size(), 8);
+    assert_eq!(i32x8.get_element_type(), context.Int32Type().as_any_type());
+    assert_eq!(i32x8.print_to_string(), "<8 x i32>");
+    assert_eq!(
+        VectorType::new(context.FloatType(), 4).print_to_string(),
+        "<4 x float>"
+    );
+    assert_eq!(
+        LLVM::Type::Vector(LLVM::Type::Double(), 2).print_to_string(),
+        "<2 x double>"
+    );
+
+    let lanes = context
+        .ConstVector(&[context.SInt32(1), context.SInt32(2), context.SInt32(3)])
+        .unwrap();
+    assert_eq!(lanes.print_to_string(), "<3 x i32> <i32 1, i32 2, i32 3>");
+    assert_eq!(
+        context.ConstSplat(context.Float(0.5), 4).unwrap().print_to_string(),
+        "<4 x float> <float 5.000000e-01, float 5.000000e-01, float 5.000000e-01, float 5.000000e-01>"
+    );
+    assert_eq!(
+        i32x8.const_splat(context.SInt32(7)).unwrap().get_type(),
+        i32x8
+    );
+    assert_eq!(
+        LLVM::Const::Splat(LLVM::Const::UInt8(1), 2)
+            .unwrap()
+            .print_to_string(),
+        "<2 x i8> <i8 1, i8 1>"
+    );
+    assert!(LLVM::Const::Vector(&[LLVM::Const::SInt64(4)])
+        .unwrap()
+        .is_constant());
+}
+
+#[test]
+fn test_const_vector_needs_constants_of_one_type() {
+    let context = Context::new();
+    let builder = context.create_builder();
+    let module = context.create_module("const_vectors");
+    let i32_type = context.Int32Type();
+    let function = module.add_function("param", fn_type!(context.VoidType(), i32_type));
+    builder.position_at_end(function.append_basic_block("entry"));
+    let param = function.get_param(0);
+
+    assert!(context.ConstVector::<Value>(&[]).is_none());
+    assert!(context
+        .ConstVector(&[context.SInt32(1).into(), param])
+        .is_none());
+    assert!(context
+        .ConstVector(&[context.SInt32(1).into(), Value::from(context.SInt64(2))])
+        .is_none());
+    assert!(LLVM::Const::Vector(&[context.Float(1.0)]).is_some());
+
+    assert!(context.ConstSplat(param, 4).is_none());
+    let i32x4 = VectorType::new(i32_type, 4);
+    assert!(i32x4.const_splat(context.SInt32(1)).is_some());
+    assert!(i32x4.const_splat(context.SInt8(1)).is_none());
+    assert!(i32x4.const_splat(context.Float(1.0)).is_none());
+}
+
+#[test]
+fn test_element_shuffle_and_reduce() {
+    LLVM::initialize().unwrap();
+    let context = Context::new();
+    let builder = context.create_builder();
+    let module = context.create_module("vectors");
+    let i32_type = context.Int32Type();
+    let function = module.add_function("lanes", fn_type!(i32_type));
+    builder.position_at_end(function.append_basic_block("entry"));
+
+    // <1, 2, 3, 4> + <10, 10, 10, 10>, reversed, with lane 0 replaced by 100
+    let left = context
+        .ConstVector(&[
+            context.SInt32(1),
+            context.SInt32(2),
+            context.SInt32(3),
+            context.SInt32(4),
+        ])
+        .unwrap();
+    // loaded from a stack slot so the builder cannot fold the whole chain
+    let slot = builder.build_alloca(i32_type);
+    builder.build_store(context.SInt32(10), slot);
+    let ten = builder.build_load(slot).into_int_value().unwrap();
+    let tens = builder.build_vector_splat(ten, 4);
+    let sum = builder.build_add(
+        left.into_int_vector_value().unwrap(),
+        tens.into_int_vector_value().unwrap(),
+    );
+    let reverse = context
+        .ConstVector(&[
+            context.SInt32(3),
+            context.SInt32(2),
+            context.SInt32(1),
+            context.SInt32(0),
+        ])
+        .unwrap();
+    let reversed = builder.build_shuffle_vector(sum.into(), sum.get_type().get_undef(), reverse);
+    let replaced = builder.build_insert_element(reversed, context.SInt32(100), context.SInt32(0));
+    let first = builder
+        .build_extract_element(replaced, context.SInt32(0))
+        .into_int_value()
+        .unwrap();
+    let last = builder
+        .build_extract_element(replaced, context.SInt32(3))
+        .into_int_value()
+        .unwrap();
+    // 100 + 13 + 12 + 11 = 136, 136 + 100 * 11 = 1236
+    let total = builder
+        .build_vector_reduce(VectorReduction::Add, replaced)
+        .unwrap()
+        .into_int_value()
+        .unwrap();
+    let scaled = builder.build_mul(first, last);
+    builder.build_ret(builder.build_add(total, scaled));
+
+    let ir = module.print_module_to_string();
+    assert!(ir.contains("shufflevector <4 x i32>"));
+    assert!(ir.contains("insertelement <4 x i32>"));
+    assert!(ir.contains("extractelement <4 x i32>"));
+    assert!(ir.contains("call i32 @llvm.vector.reduce.add.v4i32(<4 x i32>"));
+    assert_eq!(run(module, "lanes").to_int(), 1236);
+}
+
+#[test]
+fn test_integer_reductions() {
+    LLVM::initialize().unwrap();
+    let context = Context::new();
+    let module = context.create_module("reductions");
+    let i32_type = context.Int32Type();
+    let values = context
+        .ConstVector(&[
+            context.SInt32(7),
+            context.SInt32(-3i64 as u64),
+            context.SInt32(15),
+            context.SInt32(5),
+            context.SInt32(13),
+            context.SInt32(21),
+            context.SInt32(-11i64 as u64),
+            context.SInt32(27),
+        ])
+        .unwrap();
+    let cases = [
+        (VectorReduction::Add, 74),
+        (VectorReduction::Mul, 127_702_575),
+        (VectorReduction::And, 1),
+        (VectorReduction::Or, -1),
+        (VectorReduction::Xor, 6),
+        (VectorReduction::SMax, 27),
+        (VectorReduction::SMin, -11),
+        (VectorReduction::UMax, -3),
+        (VectorReduction::UMin, 5),
+    ];
+    let builder = context.create_builder();
+    for (index, &(op, _)) in cases.iter().enumerate() {
+        let function = module.add_function(&format!("reduce{}", index), fn_type!(i32_type));
+        builder.position_at_end(function.append_basic_block("entry"));
+        builder.build_ret(builder.build_vector_reduce(op, values).unwrap());
+    }
+    assert!(module.verify().is_ok());
+    let engine = module.create_jit_engine().unwrap();
+    for (index, &(op, expected)) in cases.iter().enumerate() {
+        let function = engine.named_function(&format!("reduce{}", index)).unwrap();
+        let result = engine.run_function(function, &mut []).to_int() as u32 as i32;
+        assert_eq!(result, expected, "{:?}", op);
+    }
+}
+
+#[test]
+fn test_float_reductions() {
+    LLVM::initialize().unwrap();
+    let context = Context::new();
+    let builder = context.create_builder();
+    let module = context.create_module("float_reductions");
+    let float_type = context.FloatType();
+    let values = context
+        .ConstVector(&[
+            context.Float(1.5),
+            context.Float(-2.0),
+            context.Float(4.0),
+            context.Float(0.25),
+        ])
+        .unwrap();
+
+    let function = module.add_function("sum", fn_type!(float_type));
+    builder.position_at_end(function.append_basic_block("entry"));
+    let twos = context.ConstSplat(context.Float(2.0), 4).unwrap();
+    let scaled = builder.build_fmul(
+        values.into_float_vector_value().unwrap(),
+        twos.into_float_vector_value().unwrap(),
+    );
+    let sum = builder
+        .build_vector_reduce_fadd(context.Float(10.0), scaled.into())
+        .unwrap();
+    builder.build_ret(sum);
+
+    let function = module.add_function("product", fn_type!(float_type));
+    builder.position_at_end(function.append_basic_block("entry"));
+    let product = builder.build_vector_reduce_fmul(context.Float(1.0), values);
+    builder.build_ret(product.unwrap());
+
+    let function = module.add_function("max", fn_type!(float_type));
+    builder.position_at_end(function.append_basic_block("entry"));
+    let max = builder.build_vector_reduce(VectorReduction::FMax, values);
+    builder.build_ret(max.unwrap());
+
+    let ir = module.print_module_to_string();
+    assert!(ir.contains("@llvm.vector.reduce.fadd.v4f32(float 1.000000e+01, <4 x float>"));
+    assert!(module.verify().is_ok());
+    let engine = module.create_jit_engine().unwrap();
+    let call = |name: &str| {
+        let function = engine.named_function(name).unwrap();
+        engine.run_function(function, &mut []).to_float()
+    };
+    assert_eq!(call("sum"), 17.5);
+    assert_eq!(call("product"), -3.0);
+    assert_eq!(call("max"), 4.0);
+}
+
+#[test]
+fn test_vector_kinds() {
+    let context = Context::new();
+    let builder = context.create_builder();
+    let module = context.create_module("kinds");
+    let float_type = context.FloatType();
+    let function = module.add_function("kinds", fn_type!(context.VoidType()));
+    builder.position_at_end(function.append_basic_block("entry"));
+
+    let ints = context.ConstSplat(context.SInt32(3), 4).unwrap();
+    let floats = context.ConstSplat(context.Float(1.5), 4).unwrap();
+    let pointers = context
+        .ConstSplat(context.PointerType(float_type).const_null(), 2)
+        .unwrap();
+    assert!(ints.into_float_vector_value().is_none());
+    assert!(floats.into_int_vector_value().is_none());
+    assert!(pointers.into_int_vector_value().is_none());
+    assert!(pointers.into_float_vector_value().is_none());
+
+    let value = Value::from(ints);
+    assert!(value.is_vector_value() && value.is_int_vector_value());
+    assert!(!value.is_float_vector_value());
+    assert!(Value::from(floats).into_float_vector_value().is_some());
+    assert!(context
+        .SInt32(3)
+        .as_any_value()
+        .into_int_vector_value()
+        .is_none());
+
+    // comparing float lanes gives a vector of i1 that integer operations take
+    let floats = floats.into_float_vector_value().unwrap();
+    let mask = builder.build_fcmp_olt(floats, floats);
+    let flipped = builder.build_not(mask);
+    assert_eq!(flipped.get_type().print_to_string(), "<4 x i1>");
+    let ints = ints.into_int_vector_value().unwrap();
+    let equal = builder.build_icmp_eq(ints, ints);
+    assert_eq!(builder.build_and(equal, flipped).get_type().get_size(), 4);
+}
+
+#[test]
+fn test_reduce_without_insert_point() {
+    let context = Context::new();
+    let builder = context.create_builder();
+    let values = context.ConstSplat(context.SInt32(1), 4).unwrap();
+    let err = builder
+        .build_vector_reduce(VectorReduction::Add, values)
+        .unwrap_err();
+    assert_eq!(err, "the builder is not positioned in a block");
+    assert!(builder
+        .build_vector_reduce_fadd(context.Float(0.0), values)
+        .is_err());
+}
@@ -0,0 +1,100 @@
+extern crate llvm_sys_wrapper;
+
+use llvm_sys_wrapper::*;
+use std::env;
+use std::fs;
+
+fn build_max<'ctx>(context: &'ctx Context, module: &Module<'ctx>, name: &str) -> Function<'ctx> {
+    let builder = context.create_builder();
+    let i32_type = context.Int32Type();
+    let function = module.add_function(name, fn_type!(i32_type, i32_type, i32_type));
+    let entry = function.append_basic_block("entry");
+    let left = function.append_basic_block("left");
+    let right = function.append_basic_block("right");
+    builder.position_at_end(entry);
+    let a = function.get_param(0).into_int_value().unwrap();
+    let b = function.get_param(1).into_int_value().unwrap();
+    let greater = builder.build_icmp_sgt(a, b);
+    builder.build_cond_br(greater, left, right);
+    builder.position_at_end(left);
+    builder.build_ret(a);
+    builder.position_at_end(right);
+    builder.build_ret(b);
+    function
+}
+
+#[test]
+fn test_pinpoint_invalid_function() {
+    let context = Context::new();
+    let builder = context.create_builder();
+    let module = context.create_module("verify");
+    for index in 0..5 {
+        build_max(&context, &module, &format!("max{}", index));
+    }
+
+    // a block without a terminator
+    let broken = module.add_function("broken", fn_type!(context.VoidType()));
+    builder.position_at_end(broken.append_basic_block("entry"));
+    builder.build_alloca(context.Int32Type());
+
+    assert!(module.verify().is_err());
+    let invalid: Vec<(String, String)> = module
+        .get_functions()
+        .filter_map(|function| {
+            function
+                .verify()
+                .err()
+                .map(|err| (function.get_name(), err))
+        })
+        .collect();
+    assert_eq!(invalid.len(), 1);
+    assert_eq!(invalid[0].0, "broken");
+    assert!(invalid[0]
+        .1
+        .contains("Basic Block in function 'broken' does not have terminator!"));
+
+    builder.build_ret_void();
+    assert_eq!(broken.verify(), Ok(()));
+    assert!(module.verify().is_ok());
+}
+
+#[test]
+fn test_view_cfg() {
+    let context = Context::new();
+    let module = context.create_module("cfg");
+    let function = build_max(&context, &module, "max");
+
+    let path = env::temp_dir().join(format!("llvm_sys_wrapper_{}.dot", std::process::id()));
+    let filename = path.to_str().unwrap();
+
+    function.view_cfg(filename).unwrap();
+    let dot = fs::read_to_string(&path).unwrap();
+    assert!(dot.starts_with("digraph \"CFG for 'max' function\" {"));
+    assert!(dot.contains("Node0 [shape=record,label=\"{entry:\\l  %2 = icmp sgt i32 %0, %1\\l  "));
+    assert!(dot.contains("Node0 -> Node1;"));
+    assert!(dot.contains("Node0 -> Node2;"));
+
+    function.view_cfg_only(filename).unwrap();
+    let dot = fs::read_to_string(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert!(dot.contains("Node1 [shape=record,label=\"{left:\\l}\"];"));
+    assert!(!dot.contains("icmp"));
+    assert_eq!(dot.matches(" -> ").count(), 2);
+
+    let missing = env::temp_dir().join("llvm_sys_wrapper_missing/cfg.dot");
+    assert!(function.view_cfg(missing.to_str().unwrap()).is_err());
+}
+
+#[test]
+fn test_view_cfg_escapes_function_name() {
+    let context = Context::new();
+    let module = context.create_module("cfg_names");
+    let function = build_max(&context, &module, "say \"max\" \\o/");
+
+    let path = env::temp_dir().join(format!("llvm_sys_wrapper_names_{}.dot", std::process::id()));
+    function.view_cfg_only(path.to_str().unwrap()).unwrap();
+    let dot = fs::read_to_string(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert!(dot.starts_with("digraph \"CFG for 'say \\\"max\\\" \\\\o/' function\" {\n"));
+    assert!(dot.contains("\tlabel=\"CFG for 'say \\\"max\\\" \\\\o/' function\";\n"));
+}